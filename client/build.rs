@@ -1,6 +1,12 @@
 use winresource::WindowsResource;
 
 fn main() {
+    // exposed for `airshipper version`
+    println!(
+        "cargo:rustc-env=AIRSHIPPER_TARGET={}",
+        std::env::var("TARGET").unwrap()
+    );
+
     // #[cfg(target_os = "windows")] does not work in build.rs for cross-compilation
     let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap();
     if target_os != "windows" {
@@ -1,15 +1,33 @@
-use crate::{Result, net};
+use crate::{ClientError, Result, net, profiles::Profile};
+use reqwest::{
+    Method, StatusCode,
+    header::{ACCEPT_RANGES, CONTENT_LENGTH, RANGE},
+};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
+/// The channel used by default, all other channels may be less stable
+pub const STABLE_CHANNEL: &str = "weekly";
+pub const NIGHTLY_CHANNEL: &str = "nightly";
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub struct Channel(pub String);
 
+impl Channel {
+    pub fn is_stable(&self) -> bool {
+        self.0 == STABLE_CHANNEL
+    }
+}
+
 // Channels are lowercase when received from the server but should be
 // displayed with the first character uppercase when displayed
 impl Display for Channel {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}", (self.0[..1]).to_uppercase(), &self.0[1..])
+        let mut chars = self.0.chars();
+        match chars.next() {
+            Some(first) => write!(f, "{}{}", first.to_uppercase(), chars.as_str()),
+            None => Ok(()),
+        }
     }
 }
 
@@ -22,8 +40,129 @@ impl Channels {
     pub(crate) async fn fetch(url: String) -> Result<Self> {
         let response = net::query(url).await?;
 
-        let names_json = response.json::<Vec<Channel>>().await?;
+        let names_json = response.json::<Vec<Channel>>()?;
 
         Ok(Channels { names: names_json })
     }
 }
+
+/// Signature of the end of central directory record which ends every zip archive
+const EOCD_SIGNATURE: &[u8] = b"PK\x05\x06";
+/// Size of the end of central directory record without its trailing comment
+const EOCD_MIN_LEN: usize = 22;
+
+/// What the download server reported for a channel which passed [`check`].
+#[derive(Debug, Clone)]
+pub struct ChannelCheck {
+    pub version: String,
+    pub size: u64,
+}
+
+/// Makes sure the download server of `profile` provides `channel` in a way the updater
+/// can use: the version is available and the download supports range requests and is
+/// a zip archive. Only the end of the archive is downloaded.
+pub(crate) async fn check(profile: &Profile, channel: Channel) -> Result<ChannelCheck> {
+    let mut profile = profile.clone();
+    profile.channel = channel;
+
+    let version = latest_version(&profile).await?;
+    let size = check_download(&profile, &version).await?;
+    Ok(ChannelCheck { version, size })
+}
+
+/// The latest version of the channel of `profile`.
+pub(crate) async fn latest_version(profile: &Profile) -> Result<String> {
+    let version =
+        net::download_server_request(Method::GET, &profile.version_url(), profile)
+            .send()
+            .await?
+            .error_for_status()
+            .map_err(|e| not_found(profile, e))?;
+    let headers = version.headers().clone();
+    let version = version.text().await?;
+    if net::is_html(&headers, version.as_bytes()) {
+        return Err(ClientError::HtmlResponse(profile.version_url()));
+    }
+    Ok(version.trim().to_string())
+}
+
+/// Makes sure the download of `version` supports range requests and is a zip archive,
+/// returns its size.
+pub(crate) async fn check_download(profile: &Profile, version: &str) -> Result<u64> {
+    let incompatible = |reason: &str| ClientError::IncompatibleServer(reason.to_string());
+    let download_url = profile.download_url(version);
+    let request = |method, url: &str| net::download_server_request(method, url, profile);
+
+    let head = request(Method::HEAD, &download_url)
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| not_found(profile, e))?;
+    if net::is_html(head.headers(), &[]) {
+        return Err(ClientError::HtmlResponse(download_url));
+    }
+    let header = |name| head.headers().get(name).and_then(|v| v.to_str().ok());
+    if !header(ACCEPT_RANGES).is_some_and(|ranges| ranges.contains("bytes")) {
+        return Err(incompatible("the download does not support range requests"));
+    }
+    // `Response::content_length` is always 0 for HEAD requests
+    let size = header(CONTENT_LENGTH)
+        .and_then(|len| len.parse::<u64>().ok())
+        .filter(|len| *len >= EOCD_MIN_LEN as u64)
+        .ok_or_else(|| incompatible("the download size is unknown"))?;
+
+    // the record is followed by a comment of up to u16::MAX bytes
+    let tail_len = size.min((EOCD_MIN_LEN + u16::MAX as usize) as u64);
+    let tail = request(Method::GET, &download_url)
+        .header(RANGE, format!("bytes=-{tail_len}"))
+        .send()
+        .await?
+        .error_for_status()?;
+    if tail.status() != StatusCode::PARTIAL_CONTENT {
+        return Err(incompatible("the download server ignored a range request"));
+    }
+    let headers = tail.headers().clone();
+    let tail = tail.bytes().await?;
+    if net::is_html(&headers, &tail) {
+        return Err(ClientError::HtmlResponse(download_url));
+    }
+    if find_eocd(&tail).is_none() {
+        return Err(incompatible("the download is not a zip archive"));
+    }
+
+    Ok(size)
+}
+
+/// Unknown channels are reported as 404 by the download server.
+fn not_found(profile: &Profile, e: reqwest::Error) -> ClientError {
+    match e.status() {
+        Some(StatusCode::NOT_FOUND) => {
+            ClientError::UnknownChannel(profile.channel.0.clone())
+        },
+        _ => e.into(),
+    }
+}
+
+/// Position of the end of central directory record within the end of a zip archive
+fn find_eocd(tail: &[u8]) -> Option<usize> {
+    tail.windows(EOCD_SIGNATURE.len())
+        .rposition(|window| window == EOCD_SIGNATURE)
+        .filter(|pos| tail.len() - pos >= EOCD_MIN_LEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_eocd() {
+        let mut tail = vec![0; 100];
+        tail.extend_from_slice(EOCD_SIGNATURE);
+        tail.extend_from_slice(&[0; EOCD_MIN_LEN - 4]);
+        assert_eq!(find_eocd(&tail), Some(100));
+        // a signature too close to the end can't be a complete record
+        tail.truncate(110);
+        assert_eq!(find_eocd(&tail), None);
+        assert_eq!(find_eocd(b"not a zip archive at all"), None);
+    }
+}
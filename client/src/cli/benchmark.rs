@@ -0,0 +1,115 @@
+//! `airshipper benchmark` measures the connection to the download server by fetching
+//! the start of the latest game archive, without installing anything.
+use super::parse::OutputFormat;
+use crate::{
+    ClientError, Result, channels,
+    logger::{bytes_per_second, pretty_bytes},
+    net,
+    profiles::Profile,
+};
+use colored::Colorize;
+use reqwest::{Method, StatusCode, header::RANGE};
+use serde::Serialize;
+use std::time::Instant;
+
+#[derive(Debug, Serialize)]
+struct Benchmark {
+    host: String,
+    version: String,
+    /// the sample size which was asked for
+    requested_bytes: u64,
+    /// less than requested if the archive is smaller
+    downloaded_bytes: u64,
+    first_byte_ms: u128,
+    transfer_ms: u128,
+    /// `None` if the sample arrived too fast to measure
+    bytes_per_second: Option<u64>,
+    /// updates can't download single files without range requests
+    range_requests: bool,
+}
+
+/// Downloads `sample_size` bytes of the latest version and prints the throughput.
+pub(super) async fn benchmark(
+    profile: &Profile,
+    sample_size: u64,
+    format: OutputFormat,
+) -> Result<()> {
+    let host = profile
+        .download_host()
+        .unwrap_or_else(|| profile.server.url().to_string());
+    let version = channels::latest_version(profile).await?;
+    if format == OutputFormat::Text {
+        println!(
+            "Downloading {} of version {version} from {host}",
+            pretty_bytes(sample_size)
+        );
+    }
+
+    let started = Instant::now();
+    let mut response = net::download_server_request(
+        Method::GET,
+        &profile.download_url(&version),
+        profile,
+    )
+    .header(RANGE, format!("bytes=0-{}", sample_size.saturating_sub(1)))
+    .send()
+    .await?
+    .error_for_status()?;
+    let range_requests = response.status() == StatusCode::PARTIAL_CONTENT;
+
+    // servers ignoring the range send the whole archive, which is cut off here
+    let mut first_byte = None;
+    let mut downloaded_bytes = 0;
+    while downloaded_bytes < sample_size
+        && let Some(chunk) = response.chunk().await?
+    {
+        first_byte.get_or_insert_with(|| started.elapsed());
+        downloaded_bytes += chunk.len() as u64;
+    }
+    let downloaded_bytes = downloaded_bytes.min(sample_size);
+    let first_byte = first_byte
+        .ok_or_else(|| ClientError::Custom("The download server sent no data".into()))?;
+    let elapsed = started.elapsed();
+    let transfer = elapsed.saturating_sub(first_byte);
+
+    let result = Benchmark {
+        host,
+        version,
+        requested_bytes: sample_size,
+        downloaded_bytes,
+        first_byte_ms: first_byte.as_millis(),
+        transfer_ms: transfer.as_millis(),
+        // a small sample can arrive with the first byte, then the time to it counts
+        bytes_per_second: bytes_per_second(downloaded_bytes, transfer)
+            .or_else(|| bytes_per_second(downloaded_bytes, elapsed)),
+        range_requests,
+    };
+
+    match format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&result) {
+            Ok(json) => println!("{json}"),
+            Err(e) => return Err(ClientError::Custom(e.to_string())),
+        },
+        OutputFormat::Text => {
+            println!("Time to first byte: {} ms", result.first_byte_ms);
+            println!(
+                "Downloaded: {} in {:.1?}",
+                pretty_bytes(result.downloaded_bytes),
+                transfer
+            );
+            match result.bytes_per_second {
+                Some(bytes) => println!("Throughput: {}/s", pretty_bytes(bytes)),
+                None => println!("Throughput: too fast to measure"),
+            }
+            if result.range_requests {
+                println!("Range requests: {}", "honored".green());
+            } else {
+                println!(
+                    "Range requests: {}, updates from this server will fail",
+                    "ignored".red()
+                );
+            }
+        },
+    }
+    Ok(())
+}
@@ -0,0 +1,334 @@
+//! `airshipper doctor` checks the common reasons for failing updates in one go, so
+//! its output can be shared when asking for support.
+use super::parse::OutputFormat;
+use crate::{
+    ClientError, Result, channels, logger::pretty_bytes, net, profiles::Profile,
+};
+use colored::Colorize;
+use reqwest::Method;
+use serde::Serialize;
+use std::{future::Future, path::Path, time::Duration};
+
+/// every check gives up after this long, so an unreachable server doesn't stall the run
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// written to the install folder to check that it is writable
+const PROBE_FILE: &str = ".airshipper-doctor";
+/// hint for a failed TLS handshake caused by the certificate pins
+const PINS_CHANGED: &str = "The certificate changed, update `pinned_keys` in the \
+                            launcher configuration if the new one is trusted";
+/// hint for a version fetch which failed as the channel is not available
+const UNKNOWN_CHANNEL: &str =
+    "Select another channel in the settings or via `airshipper config`";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Pass,
+    Fail,
+    /// the check does not apply or depends on a failed check
+    Skip,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    /// what was found, or why the check failed
+    detail: String,
+    /// how to fix a failed check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl Check {
+    /// `None` skips the check because an earlier one failed.
+    fn new(
+        name: &'static str,
+        result: Option<Result<String>>,
+        hint: impl FnOnce(&ClientError) -> String,
+    ) -> Self {
+        let (status, detail, hint) = match result {
+            Some(Ok(detail)) => (Status::Pass, detail, None),
+            Some(Err(e)) => (Status::Fail, e.to_string(), Some(hint(&e))),
+            None => (Status::Skip, "an earlier check failed".to_string(), None),
+        };
+        Self {
+            name,
+            status,
+            detail,
+            hint,
+        }
+    }
+
+    fn skip(name: &'static str, reason: &str) -> Self {
+        Self {
+            name,
+            status: Status::Skip,
+            detail: reason.to_string(),
+            hint: None,
+        }
+    }
+}
+
+/// Prints the result of every check, returns whether none of them failed.
+pub(super) async fn doctor(profile: &Profile, format: OutputFormat) -> Result<bool> {
+    let checks = run_checks(profile).await;
+
+    match format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&checks) {
+            Ok(json) => println!("{json}"),
+            Err(e) => return Err(ClientError::Custom(e.to_string())),
+        },
+        OutputFormat::Text => {
+            for check in &checks {
+                let status = match check.status {
+                    Status::Pass => " OK ".green(),
+                    Status::Fail => "FAIL".red(),
+                    Status::Skip => "SKIP".yellow(),
+                };
+                println!("[{status}] {}: {}", check.name, check.detail);
+                if let Some(hint) = &check.hint {
+                    println!("       {hint}");
+                }
+            }
+        },
+    }
+    Ok(checks.iter().all(|check| check.status != Status::Fail))
+}
+
+async fn run_checks(profile: &Profile) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let dns = with_timeout(resolve(profile)).await;
+    let online = dns.is_ok();
+    checks.push(Check::new("DNS resolution", Some(dns), |_| {
+        "Check your internet connection and DNS settings".to_string()
+    }));
+
+    let tls = if online {
+        Some(with_timeout(handshake(profile)).await)
+    } else {
+        None
+    };
+    let connected = matches!(tls, Some(Ok(_)));
+    checks.push(Check::new("TLS handshake", tls, |e| match e {
+        ClientError::PinnedKeyMismatch(_) => PINS_CHANGED.to_string(),
+        _ => "Check that no firewall or proxy blocks HTTPS connections and that the \
+              system clock is correct"
+            .to_string(),
+    }));
+
+    let version = if connected {
+        Some(with_timeout(channels::latest_version(profile)).await)
+    } else {
+        None
+    };
+    let latest = version.as_ref().and_then(|v| v.as_ref().ok()).cloned();
+    let version = version.map(|v| {
+        v.map(|version| format!("the latest {} version is {version}", profile.channel))
+    });
+    checks.push(Check::new("Version fetch", version, |e| match e {
+        ClientError::UnknownChannel(_) => UNKNOWN_CHANNEL.to_string(),
+        _ => "The download server might be down, try again later or check \
+              `url_templates` in the launcher configuration"
+            .to_string(),
+    }));
+
+    let download = match &latest {
+        Some(version) => {
+            Some(with_timeout(channels::check_download(profile, version)).await)
+        },
+        None => None,
+    };
+    let download_size = download.as_ref().and_then(|d| d.as_ref().ok()).copied();
+    checks.push(Check::new(
+        "Range requests",
+        download.map(|d| {
+            d.map(|size| format!("the {} download supports them", pretty_bytes(size)))
+        }),
+        |_| "Range requests can be blocked by a proxy, try another network".to_string(),
+    ));
+
+    let install_dir = profile.directory();
+    checks.push(Check::new(
+        "Disk space",
+        Some(disk_space(profile, download_size)),
+        |_| {
+            format!(
+                "Free up space on the drive of {}, or lower the free space margin via \
+                 `airshipper config`",
+                install_dir.display()
+            )
+        },
+    ));
+    checks.push(Check::new(
+        "Install folder",
+        Some(writable(&install_dir).await),
+        |_| {
+            format!(
+                "Check the permissions of {}, or install the game elsewhere by setting \
+                 AIRSHIPPER_ROOT",
+                install_dir.display()
+            )
+        },
+    ));
+
+    checks.push(Check::new("System clock", Some(clock()), |_| {
+        "Set the system clock to the correct time, old cache files are kept until then"
+            .to_string()
+    }));
+    checks.push(launch_wrapper(profile));
+    checks.push(nix_patcher(profile));
+    checks
+}
+
+async fn with_timeout<T>(check: impl Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| {
+            Err(ClientError::Network(format!(
+                "no answer within {} seconds",
+                CHECK_TIMEOUT.as_secs()
+            )))
+        })
+}
+
+async fn resolve(profile: &Profile) -> Result<String> {
+    let host = profile
+        .download_host()
+        .ok_or_else(|| ClientError::Custom("the version URL has no host".to_string()))?;
+    let addr = tokio::net::lookup_host((host.as_str(), 443))
+        .await
+        .map_err(|e| ClientError::Network(format!("{host} could not be resolved: {e}")))?
+        .next()
+        .ok_or_else(|| ClientError::Network(format!("{host} has no addresses")))?;
+    Ok(format!("{host} resolved to {}", addr.ip()))
+}
+
+/// Any answer of the download server means the connection and the certificate,
+/// including its pins, are fine.
+async fn handshake(profile: &Profile) -> Result<String> {
+    let url = url::Url::parse(&profile.version_url())?;
+    if url.scheme() != "https" {
+        return Ok("the download server does not use TLS".to_string());
+    }
+    let host = url.host_str().unwrap_or_default();
+    let response =
+        net::download_server_request(Method::HEAD, &format!("https://{host}/"), profile)
+            .send()
+            .await?;
+    Ok(format!("{host} answered with {}", response.status()))
+}
+
+/// The download is compressed, so a fresh install needs even more than this reports.
+fn disk_space(profile: &Profile, download_size: Option<u64>) -> Result<String> {
+    let available = crate::fs::available_space(&profile.directory())?;
+    let Some(size) = download_size else {
+        return Ok(format!("{} available", pretty_bytes(available)));
+    };
+    let needed = profile.min_free_space_margin.required_space(size);
+    if available < needed {
+        Err(ClientError::NotEnoughSpace {
+            needed: pretty_bytes(needed),
+            available: pretty_bytes(available),
+        })
+    } else {
+        Ok(format!(
+            "{} available, the download and the free space margin need {}",
+            pretty_bytes(available),
+            pretty_bytes(needed)
+        ))
+    }
+}
+
+async fn writable(install_dir: &Path) -> Result<String> {
+    let probe = install_dir.join(PROBE_FILE);
+    tokio::fs::create_dir_all(install_dir).await?;
+    tokio::fs::write(&probe, b"").await?;
+    tokio::fs::remove_file(&probe).await?;
+    Ok(format!("{} is writable", install_dir.display()))
+}
+
+/// A wrong clock shows in cache files which were modified in the future.
+fn clock() -> Result<String> {
+    let cached = crate::update::cached_versions()?;
+    match crate::update::clock_skew(&cached, std::time::SystemTime::now()) {
+        Some(skew) => Err(ClientError::Custom(format!(
+            "cache files were modified {:.1} days in the future",
+            skew.as_secs_f64() / 86400.0
+        ))),
+        None => Ok("no cache files from the future".to_string()),
+    }
+}
+
+fn launch_wrapper(profile: &Profile) -> Check {
+    const NAME: &str = "Launch wrapper";
+
+    if profile.launch_wrapper.trim().is_empty() {
+        return Check::skip(NAME, "no launch wrapper is set");
+    }
+    Check::new(
+        NAME,
+        Some(profile.launch_command().map(|command| {
+            let command: Vec<_> =
+                command.iter().map(|arg| arg.to_string_lossy()).collect();
+            format!("starts the game with `{}`", command.join(" "))
+        })),
+        |_| {
+            "Install the program of the launch wrapper or change it via `airshipper \
+             config`"
+                .to_string()
+        },
+    )
+}
+
+#[cfg(unix)]
+fn nix_patcher(profile: &Profile) -> Check {
+    const NAME: &str = "NixOS patcher";
+
+    match crate::nix::is_nixos() {
+        Ok(false) => Check::skip(NAME, "not running on NixOS"),
+        _ if profile.disable_nix_patching => Check::skip(NAME, "patching is disabled"),
+        result => Check::new(
+            NAME,
+            Some(
+                result
+                    .and_then(|_| crate::nix::check_patchers(&profile.executables))
+                    .map(|()| "the patchers are configured".to_string()),
+            ),
+            |_| {
+                "Install Airshipper through its Nix package, or disable NixOS patching \
+                 via `airshipper config` if you run the game in an FHS environment"
+                    .to_string()
+            },
+        ),
+    }
+}
+
+#[cfg(not(unix))]
+fn nix_patcher(_: &Profile) -> Check {
+    Check::skip("NixOS patcher", "not running on NixOS")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checks_without_result_are_skipped() {
+        let check = Check::new("Version fetch", None, |_| unreachable!());
+        assert_eq!(check.status, Status::Skip);
+        assert_eq!(check.hint, None);
+
+        let check = Check::new(
+            "Version fetch",
+            Some(Err(ClientError::UnknownChannel("nightly".to_string()))),
+            |_| "Select another channel".to_string(),
+        );
+        assert_eq!(check.status, Status::Fail);
+        assert_eq!(check.hint.as_deref(), Some("Select another channel"));
+
+        let json = serde_json::to_value(&check).unwrap();
+        assert_eq!(json["status"], "fail");
+    }
+}
@@ -1,20 +1,106 @@
 use crate::{
-    Result, fs, gui, io,
-    logger::{self, pretty_bytes},
-    profiles::{Profile, parse_env_vars},
+    Result, channels, fs, gui, io,
+    logger::{self, pretty_bytes, pretty_time},
+    profiles::{
+        ConsoleMode, HttpVersion, Profile, ProgressMetric, PromptAnswer, UiScale,
+        UiTheme, UrlTemplates, parse_env_vars, validate_launch_wrapper,
+    },
 };
-use parse::Action;
+use parse::{
+    Action, CacheAction, ConfigAction, ConfigKey, ManifestFormat, OutputFormat,
+    SlotAction,
+};
+mod benchmark;
+mod doctor;
 mod parse;
+mod progress;
 use iced::futures::stream::StreamExt;
+use std::{
+    sync::{
+        LazyLock, Mutex,
+        mpsc::{self, Receiver, RecvTimeoutError},
+    },
+    time::Duration,
+};
 
-use crate::{BASE_PATH, error::ClientError, profiles::LogLevel};
+use crate::{
+    BASE_PATH, error::ClientError, net::supported_server_api_version, profiles::LogLevel,
+};
 pub use parse::CmdLine;
 use tracing::level_filters::LevelFilter;
 
+/// Stable exit codes of the CLI, so scripts can branch on the outcome of a run.
+/// Keep in sync with the exit codes listed in the `--help` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Success, the game is up-to-date
+    Success = 0,
+    /// An unspecified error occurred
+    Error = 1,
+    /// An update has been downloaded and applied
+    UpdateApplied = 2,
+    /// An update is available but has been declined
+    UpdateDeclined = 3,
+    /// The download server could not be reached
+    Offline = 4,
+    /// The downloaded game files failed verification
+    VerificationFailed = 5,
+    /// An update is available, reported by `airshipper check`
+    UpdateAvailable = 6,
+    /// The update was cancelled with Ctrl+C, like the shell reports SIGINT
+    Cancelled = 130,
+}
+
+impl From<&ClientError> for ExitCode {
+    fn from(err: &ClientError) -> Self {
+        match err {
+            ClientError::Network(_) => ExitCode::Offline,
+            ClientError::Verification { .. } => ExitCode::VerificationFailed,
+            _ => ExitCode::Error,
+        }
+    }
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        std::process::ExitCode::from(code as u8)
+    }
+}
+
+/// Outcome of an update run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UpdateOutcome {
+    UpToDate,
+    Applied,
+    Declined,
+    Cancelled,
+}
+
+impl From<UpdateOutcome> for ExitCode {
+    fn from(outcome: UpdateOutcome) -> Self {
+        match outcome {
+            UpdateOutcome::UpToDate => ExitCode::Success,
+            UpdateOutcome::Applied => ExitCode::UpdateApplied,
+            UpdateOutcome::Declined => ExitCode::UpdateDeclined,
+            UpdateOutcome::Cancelled => ExitCode::Cancelled,
+        }
+    }
+}
+
 /// Process command line arguments and optionally starts GUI
-pub fn process() -> Result<()> {
+pub fn process() -> Result<ExitCode> {
     let mut cmd = CmdLine::new();
 
+    if let Some(path) = &cmd.config {
+        fs::set_savedstate_file(path.clone())?;
+    }
+
+    // handled before setting up logging, so nothing else ends up in the output
+    if let Some(Action::Version { format }) = cmd.action {
+        version(format)?;
+        return Ok(ExitCode::Success);
+    }
+
     let level = match cmd.debug {
         0 => LevelFilter::INFO,
         1 => LevelFilter::DEBUG,
@@ -24,9 +110,18 @@ pub fn process() -> Result<()> {
 
     let log = fs::log_path_file();
     let _guard = logger::init(Some((log.0, log.1)), level);
+    fs::log_dir_fallbacks();
 
     tracing::debug!("Running on {}", std::env::consts::OS);
     tracing::debug!("Base Path: {}", fs::base_path());
+    tracing::debug!("Config Path: {}", fs::config_path());
+    if cmd.config.is_some() && !fs::savedstate_file().exists() {
+        tracing::warn!(
+            "{} does not exist yet, starting with the default configuration. It is \
+             created on the first save.",
+            fs::savedstate_file().display()
+        );
+    }
     tracing::debug!("Log file: {}", fs::log_file().display());
     #[cfg(windows)]
     tracing::debug!("Cache Path: {}", fs::get_cache_path().display());
@@ -35,15 +130,19 @@ pub fn process() -> Result<()> {
 
     if cmd.force_reset {
         std::fs::remove_dir_all(BASE_PATH.as_path())?;
+        let _ = std::fs::remove_file(fs::savedstate_file());
     }
 
     // GUI
     if cmd.action.is_none() {
         match gui::run(cmd.clone()) {
-            Ok(_) => return Ok(()),
+            Ok(_) => return Ok(ExitCode::Success),
             Err(_) => {
                 tracing::error!("Failed to start GUI. Falling back to terminal...");
-                cmd.action = Some(Action::Run);
+                cmd.action = Some(Action::Run {
+                    tui: false,
+                    connect: None,
+                });
             },
         }
     }
@@ -54,6 +153,11 @@ pub fn process() -> Result<()> {
         .worker_threads(4)
         .build()?;
 
+    // before the first request, the web clients pick up the pins when they are built
+    let mut profile = Profile::load();
+    crate::net::pinning::pin_download_server(&profile);
+    crate::net::set_http_version(profile.http_version);
+
     // let the user know incase airshipper can be updated.
     #[cfg(windows)]
     if let Ok(Some(release)) = crate::windows::query() {
@@ -64,15 +168,18 @@ pub fn process() -> Result<()> {
     }
 
     rt.block_on(async {
-        let mut profile = Profile::load();
+        if cmd.low_memory {
+            profile.low_memory = true;
+        }
 
         // handle arguments
-        process_arguments(&mut profile, cmd.action.unwrap(), cmd.verbose).await?;
+        let code =
+            process_arguments(&mut profile, cmd.action.unwrap(), cmd.verbose).await?;
 
         // Save state
         profile.save_ref().await?;
 
-        Ok::<(), ClientError>(())
+        Ok::<ExitCode, ClientError>(code)
     })
 }
 
@@ -80,115 +187,260 @@ async fn process_arguments(
     profile: &mut Profile,
     action: Action,
     verbose: u8,
-) -> Result<()> {
+) -> Result<ExitCode> {
     profile.log_level = match verbose {
         0 => LogLevel::Default,
         1 => LogLevel::Debug,
         _ => LogLevel::Trace,
     };
 
-    match action {
-        Action::Update => update(profile, true).await?,
-        Action::Start => start(profile, None).await?,
-        Action::Run => {
-            if let Err(e) = update(profile, false).await {
-                tracing::error!(
-                    ?e,
-                    "Couldn't update the game, starting installed version."
-                );
+    let code = match action {
+        Action::Update { tui } => update(profile, true, tui).await?.into(),
+        Action::Start { safe_mode, connect } => {
+            start(profile, connect, safe_mode).await?;
+            ExitCode::Success
+        },
+        Action::Run { tui, connect } => {
+            let code = match update(profile, false, tui).await {
+                Ok(UpdateOutcome::Cancelled) => return Ok(ExitCode::Cancelled),
+                Ok(outcome) => outcome.into(),
+                Err(e) => {
+                    tracing::error!(
+                        ?e,
+                        "Couldn't update the game, starting installed version."
+                    );
+                    ExitCode::from(&e)
+                },
+            };
+            start(profile, connect, false).await?;
+            code
+        },
+        Action::Config { action: None } => {
+            config(profile).await?;
+            ExitCode::Success
+        },
+        Action::Config {
+            action:
+                Some(ConfigAction::Export {
+                    path,
+                    install_state,
+                    credentials,
+                }),
+        } => {
+            profile.export(&path, install_state, credentials).await?;
+            println!("Exported the configuration to {}", path.display());
+            ExitCode::Success
+        },
+        Action::Config {
+            action: Some(ConfigAction::Import { path }),
+        } => {
+            let imported = profile.import(&path)?;
+            let backup = profile.backup().await?;
+            *profile = imported;
+            println!(
+                "Imported the configuration from {}, the previous one was saved to {}",
+                path.display(),
+                backup.display()
+            );
+            ExitCode::Success
+        },
+        Action::Config {
+            action: Some(ConfigAction::Set { key, value }),
+        } => {
+            use colored::Colorize;
+
+            let value = config_set(profile, key, &value)?;
+            println!("{}: {} = '{value}'", "OK".green(), config_key_name(key));
+            ExitCode::Success
+        },
+        Action::Paths { sizes } => {
+            paths(profile, sizes).await?;
+            ExitCode::Success
+        },
+        Action::Changelog { since, format } => {
+            changelog(profile, since, format).await?;
+            ExitCode::Success
+        },
+        Action::Cache { action } => {
+            cache(action)?;
+            ExitCode::Success
+        },
+        Action::Slot { action } => {
+            slot(profile, action).await?;
+            ExitCode::Success
+        },
+        Action::Rollback { version, list } => {
+            rollback(profile, version, list)?;
+            ExitCode::Success
+        },
+        Action::Check => check(profile).await?,
+        Action::CheckChannel { channel } => {
+            check_channel(profile, channel).await?;
+            ExitCode::Success
+        },
+        Action::Doctor { format } => {
+            if doctor::doctor(profile, format).await? {
+                ExitCode::Success
+            } else {
+                ExitCode::Error
             }
-            start(profile, None).await?
         },
-        Action::Config => config(profile).await?,
+        Action::Benchmark { size, format } => {
+            benchmark::benchmark(profile, size, format).await?;
+            ExitCode::Success
+        },
+        Action::Manifest { path, format } => {
+            manifest(profile, &path, format).await?;
+            ExitCode::Success
+        },
+        Action::Version { .. } => unreachable!("handled before the profile is loaded"),
         #[cfg(windows)]
         Action::Upgrade => {
             tokio::task::block_in_place(upgrade)?;
+            ExitCode::Success
         },
-    }
-    Ok(())
+    };
+    Ok(code)
 }
 
-async fn update(profile: &mut Profile, do_not_ask: bool) -> Result<()> {
+async fn update(
+    profile: &mut Profile,
+    do_not_ask: bool,
+    detailed: bool,
+) -> Result<UpdateOutcome> {
     use crate::update::{Progress, update};
-    use indicatif::{ProgressBar, ProgressStyle};
 
-    let progress_bar = ProgressBar::new(100).with_style(
-        ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] [{bar:40.green/white}] {msg} [{eta}]")
-            .unwrap()
-            .progress_chars("=>-"),
-    );
-    progress_bar.set_message("Evaluating Update");
+    let progress_view = progress::UpdateProgress::new(detailed, profile.progress_metric);
+    progress_view.set_message("Evaluating Update");
 
     tracing::debug!("start updating");
 
     let mut stream = update(profile.clone()).boxed();
+    let mut outcome = UpdateOutcome::UpToDate;
+    // dropping the stream stops the update, already stored files are kept and only
+    // the remaining ones are fetched on the next update
+    let mut interrupt = std::pin::pin!(tokio::signal::ctrl_c());
+    let mut interruptible = true;
+
+    loop {
+        let progress = tokio::select! {
+            progress = stream.next() => match progress {
+                Some(progress) => progress,
+                None => break,
+            },
+            result = &mut interrupt, if interruptible => match result {
+                Ok(()) => {
+                    progress_view.clear();
+                    tracing::info!("Update cancelled.");
+                    return Ok(UpdateOutcome::Cancelled);
+                },
+                Err(e) => {
+                    tracing::warn!(?e, "Failed to listen for Ctrl+C");
+                    interruptible = false;
+                    continue;
+                },
+            },
+        };
 
-    while let Some(progress) = stream.next().await {
         match progress {
-            Progress::ReadyToSync { version } => {
-                tracing::debug!(?version);
+            Progress::ReadyToSync {
+                version,
+                download_bytes,
+            } => {
+                tracing::debug!(?version, download_bytes);
+                // the update might be declined, so the check is recorded right away
+                profile.last_checked = Some(chrono::Utc::now());
 
-                if !do_not_ask {
-                    tracing::info!("Update found, do you want to update? [Y/n]");
-                    if !confirm_action()? {
-                        // No update for you :/
+                let large = profile.is_large_update(download_bytes);
+                if large {
+                    tracing::warn!(
+                        "This update downloads {}, which is above the large update \
+                         threshold of {}.",
+                        pretty_bytes(download_bytes),
+                        pretty_bytes(profile.large_update_threshold.unwrap_or_default())
+                    );
+                }
+                if do_not_ask && large {
+                    tracing::info!("Do you want to download it? [y/N]");
+                    if !confirm_large_update(profile)? {
                         tracing::info!("skipping update.");
-                        break;
+                        return Ok(UpdateOutcome::Declined);
+                    }
+                } else if !do_not_ask {
+                    if profile.installed()
+                        && profile.skipped_version.as_ref() == Some(&version)
+                    {
+                        tracing::info!(
+                            "Skipping update {version}, run `airshipper update` to \
+                             install it."
+                        );
+                        return Ok(UpdateOutcome::Declined);
+                    }
+                    tracing::info!(
+                        "Update found, do you want to update? [Y/n/s] (s skips this \
+                         version)"
+                    );
+                    match confirm_update(profile)? {
+                        Some(true) => {},
+                        Some(false) => {
+                            // No update for you :/
+                            tracing::info!("skipping update.");
+                            return Ok(UpdateOutcome::Declined);
+                        },
+                        None => {
+                            tracing::info!(
+                                "Skipping {version} until a newer version is released."
+                            );
+                            profile.skipped_version = Some(version);
+                            return Ok(UpdateOutcome::Declined);
+                        },
                     }
                 }
+                outcome = UpdateOutcome::Applied;
             },
             Progress::Incomplete {
                 download,
                 unzip,
                 delete,
-            } => {
-                let (step, progress) = match (
-                    download.is_finished(),
-                    unzip.is_finished(),
-                    delete.is_finished(),
-                ) {
-                    (false, _, _) => ("Downloading", &download),
-                    (true, false, _) => ("Unzipping", &unzip),
-                    (true, true, false) => ("Deleting", &delete),
-                    (true, true, true) => ("Finalizing", &unzip),
-                };
-                progress_bar.set_position(progress.percent_complete());
-                progress_bar.set_message(format!(
-                    "{} / {} ({step})",
-                    pretty_bytes(progress.processed_bytes()),
-                    pretty_bytes(progress.total_bytes()),
-                ));
-            },
+                deleted_files,
+            } => progress_view.update(&download, &unzip, &delete, deleted_files),
             Progress::Successful(new_profile) => {
                 tracing::debug!("Updating profile");
-                *profile = new_profile;
+                *profile = *new_profile;
                 // Save state
                 profile.save_ref().await?;
-                return Ok(());
+                return Ok(outcome);
             },
             Progress::Errored(e) => {
                 return Err(e);
             },
             Progress::Offline => {
-                return Err(ClientError::Custom("No internet connection".to_string()));
+                return Err(ClientError::Network("No internet connection".to_string()));
             },
         }
     }
-    Ok(())
+    Ok(outcome)
 }
 
-async fn start(profile: &Profile, game_server_address: Option<String>) -> Result<()> {
+async fn start(
+    profile: &Profile,
+    game_server_address: Option<String>,
+    safe_mode: bool,
+) -> Result<()> {
     if !profile.installed() {
         tracing::info!("Profile is not installed. Install it via `airshipper update`");
         return Ok(());
     }
 
     tracing::info!("Starting...");
+    if safe_mode && let Err(e) = profile.reset_safe_mode_settings() {
+        tracing::warn!(?e, "Failed to reset the safe mode settings");
+    }
     let mut stream = crate::io::stream_process(&mut Profile::start(
         profile,
         game_server_address.as_deref(),
-    ))?
+        safe_mode,
+    )?)?
     .boxed();
 
     while let Some(progress) = stream.next().await {
@@ -213,6 +465,56 @@ async fn config(profile: &mut Profile) -> Result<()> {
         let options = [
             ("Environment variables", profile.env_vars.to_string()),
             ("Graphics backend", profile.wgpu_backend.to_string()),
+            ("Low memory mode", profile.low_memory.to_string()),
+            ("Sync shipped maps", profile.sync_shipped_maps.to_string()),
+            ("Lite launcher UI", profile.lite_ui.to_string()),
+            ("Launch wrapper", profile.launch_wrapper.to_string()),
+            (
+                "Disable NixOS patching",
+                profile.disable_nix_patching.to_string(),
+            ),
+            ("Console window (Windows)", profile.console.to_string()),
+            ("Minimize to tray", profile.minimize_to_tray.to_string()),
+            (
+                "Download URLs",
+                if profile.url_templates == UrlTemplates::default() {
+                    "built-in".to_string()
+                } else {
+                    "custom".to_string()
+                },
+            ),
+            ("Signing command (macOS)", profile.macos_signing.to_string()),
+            (
+                "Free space margin",
+                profile.min_free_space_margin.to_string(),
+            ),
+            ("HTTP version", profile.http_version.to_string()),
+            ("Panel refresh (GUI)", profile.refresh_intervals.to_string()),
+            ("Compact install", profile.compaction.enabled.to_string()),
+            (
+                "Progress in window title",
+                profile.progress_in_title.to_string(),
+            ),
+            (
+                "Compressed file list cache",
+                profile.compact_file_lists.to_string(),
+            ),
+            (
+                "Large update warning",
+                large_update_threshold_string(profile.large_update_threshold),
+            ),
+            ("Serial unzip", profile.serial_unzip.to_string()),
+            ("Executables (Unix)", profile.executables.join(", ")),
+            ("Kept previous versions", profile.keep_versions.to_string()),
+            ("Theme (GUI)", profile.theme.to_string()),
+            ("Zoom (GUI)", profile.ui_scale.to_string()),
+            (
+                "Compressed saved state and caches",
+                profile.compress_stored_files.to_string(),
+            ),
+            ("Progress bar", profile.progress_metric.to_string()),
+            ("Detailed update log", profile.update_log.to_string()),
+            ("Prompt timeout (CLI)", prompt_timeout_string(profile)),
         ];
         for (idx, (k, v)) in options.iter().enumerate() {
             println!("- ({}) {k} = {v}", (idx + 1).to_string().blue());
@@ -256,6 +558,9 @@ async fn config(profile: &mut Profile) -> Result<()> {
                                      '{input}'.",
                                     "OK".green()
                                 );
+                                for warning in profile.env_var_warnings(&input) {
+                                    println!("{}: {warning}", "WARNING".yellow());
+                                }
                                 continue 'main;
                             }
                         }
@@ -295,6 +600,564 @@ async fn config(profile: &mut Profile) -> Result<()> {
                         }
                     }
                 },
+                "3" => {
+                    profile.low_memory = !profile.low_memory;
+                    println!(
+                        "{}: Low memory mode has been set to '{}'.",
+                        "OK".green(),
+                        profile.low_memory
+                    );
+                    continue 'main;
+                },
+                "4" => {
+                    profile.sync_shipped_maps = !profile.sync_shipped_maps;
+                    println!(
+                        "{}: Syncing shipped maps has been set to '{}'.",
+                        "OK".green(),
+                        profile.sync_shipped_maps
+                    );
+                    continue 'main;
+                },
+                "5" => {
+                    profile.lite_ui = !profile.lite_ui;
+                    println!(
+                        "{}: Lite launcher UI has been set to '{}'. It takes effect on \
+                         the next start.",
+                        "OK".green(),
+                        profile.lite_ui
+                    );
+                    continue 'main;
+                },
+                "6" => {
+                    println!(
+                        "Which command should the game be started with? (use 'q' to \
+                         quit)"
+                    );
+                    println!(
+                        "{}",
+                        "Hint: The game is appended to the command unless {game} is \
+                         used, {install_dir} is replaced with the install folder. Leave \
+                         it empty to start the game directly.\nExample: gamemoderun \
+                         mangohud"
+                            .dimmed()
+                    );
+                    loop {
+                        let input = editor
+                            .readline_with_initial("> ", (&profile.launch_wrapper, ""))?;
+                        let input = input.trim();
+                        if input == "q" {
+                            break;
+                        }
+                        if !input.is_empty()
+                            && let Err(e) = validate_launch_wrapper(input)
+                        {
+                            println!("{}: {e}", "ERROR".red());
+                            continue;
+                        }
+                        profile.launch_wrapper = input.to_string();
+                        println!(
+                            "{}: The launch wrapper has been set to '{}'.",
+                            "OK".green(),
+                            profile.launch_wrapper
+                        );
+                        continue 'main;
+                    }
+                },
+                "7" => {
+                    profile.disable_nix_patching = !profile.disable_nix_patching;
+                    println!(
+                        "{}: Disabling NixOS patching has been set to '{}'.",
+                        "OK".green(),
+                        profile.disable_nix_patching
+                    );
+                    continue 'main;
+                },
+                "8" => {
+                    profile.console = match profile.console {
+                        ConsoleMode::Auto => ConsoleMode::Show,
+                        ConsoleMode::Show => ConsoleMode::Hide,
+                        ConsoleMode::Hide => ConsoleMode::Auto,
+                    };
+                    println!(
+                        "{}: The console window has been set to '{}'. It takes effect \
+                         on the next start.",
+                        "OK".green(),
+                        profile.console
+                    );
+                    continue 'main;
+                },
+                "9" => {
+                    profile.minimize_to_tray = !profile.minimize_to_tray;
+                    println!(
+                        "{}: Minimize to tray has been set to '{}'. It takes effect on \
+                         the next start of a launcher built with the `tray` feature.",
+                        "OK".green(),
+                        profile.minimize_to_tray
+                    );
+                    continue 'main;
+                },
+                "10" => {
+                    println!(
+                        "Which URLs should the game be downloaded from? (use 'q' to \
+                         quit)"
+                    );
+                    println!(
+                        "{}",
+                        "Hint: {os}, {arch} and {channel} are replaced in both URLs, \
+                         {version} in the download URL only. Leave a URL empty to use \
+                         the built-in one.\nExample: \
+                         https://example.com/{channel}/{os}-{arch}/{version}.zip"
+                            .dimmed()
+                    );
+                    let mut templates = profile.url_templates.clone();
+                    for (name, template) in [
+                        ("version", &mut templates.version),
+                        ("download", &mut templates.download),
+                    ] {
+                        let input = editor.readline_with_initial(
+                            &format!("{name} > "),
+                            (template.as_deref().unwrap_or_default(), ""),
+                        )?;
+                        if input.trim() == "q" {
+                            continue 'main;
+                        }
+                        *template =
+                            Some(input.trim().to_string()).filter(|t| !t.is_empty());
+                    }
+                    match templates.validate() {
+                        Ok(()) => {
+                            profile.url_templates = templates;
+                            println!(
+                                "{}: The download URLs have been set.",
+                                "OK".green()
+                            );
+                        },
+                        Err(e) => println!("{}: {e}", "ERROR".red()),
+                    }
+                    continue 'main;
+                },
+                "11" => {
+                    println!(
+                        "Which command should sign the game after an update? (use 'q' \
+                         to quit)"
+                    );
+                    println!(
+                        "{}",
+                        "Hint: The path of each game binary is appended, leave it empty \
+                         to disable signing. Only used on macOS.\nExample: codesign \
+                         --force --sign -"
+                            .dimmed()
+                    );
+                    let input = editor.readline_with_initial(
+                        "> ",
+                        (&profile.macos_signing.command, ""),
+                    )?;
+                    if input.trim() == "q" {
+                        continue 'main;
+                    }
+                    profile.macos_signing.command = input.trim().to_string();
+                    if profile.macos_signing.is_enabled() {
+                        println!("Should the update fail if signing fails? [y/N]");
+                        profile.macos_signing.required =
+                            editor.readline("> ")?.trim().eq_ignore_ascii_case("y");
+                    }
+                    println!(
+                        "{}: The signing command has been set to '{}'.",
+                        "OK".green(),
+                        profile.macos_signing
+                    );
+                    continue 'main;
+                },
+                "12" => {
+                    println!(
+                        "How much space should an update leave free? (use 'q' to quit)"
+                    );
+                    println!(
+                        "{}",
+                        "Hint: Either a size or a percentage of the update size. \
+                         Updates need temporary space, a too small margin risks filling \
+                         up the disk close to the end of an update.\nExample: 2 GB or \
+                         10%"
+                        .dimmed()
+                    );
+                    loop {
+                        let input = editor.readline_with_initial(
+                            "> ",
+                            (&profile.min_free_space_margin.to_string(), ""),
+                        )?;
+                        if input.trim() == "q" {
+                            break;
+                        }
+                        match input.parse() {
+                            Ok(margin) => {
+                                profile.min_free_space_margin = margin;
+                                println!(
+                                    "{}: The free space margin has been set to '{}'.",
+                                    "OK".green(),
+                                    profile.min_free_space_margin
+                                );
+                                continue 'main;
+                            },
+                            Err(e) => println!("{}: {e}", "ERROR".red()),
+                        }
+                    }
+                },
+                "13" => {
+                    profile.http_version = match profile.http_version {
+                        HttpVersion::Auto => HttpVersion::Http1,
+                        HttpVersion::Http1 => HttpVersion::Http2,
+                        HttpVersion::Http2 => HttpVersion::Auto,
+                    };
+                    println!(
+                        "{}: The HTTP version has been set to '{}'. It takes effect on \
+                         the next start.",
+                        "OK".green(),
+                        profile.http_version
+                    );
+                    continue 'main;
+                },
+                "14" => {
+                    println!(
+                        "How often should the GUI refresh its panels? (use 'q' to quit)"
+                    );
+                    println!(
+                        "{}",
+                        "Hint: Either 'off' or the intervals in minutes of the news, \
+                         community, announcements and changelog, 0 disables a single \
+                         panel. Refreshes pause during updates.\nExample: 60 60 15 60"
+                            .dimmed()
+                    );
+                    loop {
+                        let input = editor.readline_with_initial(
+                            "> ",
+                            (&profile.refresh_intervals.to_string(), ""),
+                        )?;
+                        if input.trim() == "q" {
+                            break;
+                        }
+                        match input.parse() {
+                            Ok(intervals) => {
+                                profile.refresh_intervals = intervals;
+                                println!(
+                                    "{}: The panel refresh has been set to '{}'.",
+                                    "OK".green(),
+                                    profile.refresh_intervals
+                                );
+                                continue 'main;
+                            },
+                            Err(e) => println!("{}: {e}", "ERROR".red()),
+                        }
+                    }
+                },
+                "15" => {
+                    profile.set_compaction(!profile.compaction.enabled);
+                    println!(
+                        "{}: Compacting the install has been set to '{}'.",
+                        "OK".green(),
+                        profile.compaction.enabled
+                    );
+                    if profile.compaction.enabled {
+                        println!(
+                            "{}",
+                            format!(
+                                "Hint: After every update, files matching {} are \
+                                 removed. Without debug symbols, crashes can't be \
+                                 debugged. The patterns can be changed via `compaction` \
+                                 in the launcher configuration.",
+                                profile.compaction.patterns.join(", ")
+                            )
+                            .dimmed()
+                        );
+                    }
+                    continue 'main;
+                },
+                "16" => {
+                    profile.progress_in_title = !profile.progress_in_title;
+                    println!(
+                        "{}: Showing the update progress in the window title has been \
+                         set to '{}'.",
+                        "OK".green(),
+                        profile.progress_in_title
+                    );
+                    continue 'main;
+                },
+                "17" => {
+                    profile.compact_file_lists = !profile.compact_file_lists;
+                    println!(
+                        "{}: Compressing the cached file lists has been set to '{}'.",
+                        "OK".green(),
+                        profile.compact_file_lists
+                    );
+                    println!(
+                        "{}",
+                        "Hint: Cached file lists are converted on the next update."
+                            .dimmed()
+                    );
+                    continue 'main;
+                },
+                "18" => {
+                    println!(
+                        "From which download size on should updates be confirmed? (use \
+                         'q' to quit)"
+                    );
+                    println!(
+                        "{}",
+                        "Hint: Either 'off' or a size, even `airshipper update` asks \
+                         before downloading more. 0 confirms every update.\nExample: 2 \
+                         GB"
+                        .dimmed()
+                    );
+                    loop {
+                        let input = editor.readline_with_initial(
+                            "> ",
+                            (
+                                &large_update_threshold_string(
+                                    profile.large_update_threshold,
+                                ),
+                                "",
+                            ),
+                        )?;
+                        if input.trim() == "q" {
+                            break;
+                        }
+                        match parse_large_update_threshold(&input) {
+                            Ok(threshold) => {
+                                profile.large_update_threshold = threshold;
+                                println!(
+                                    "{}: The large update warning has been set to '{}'.",
+                                    "OK".green(),
+                                    large_update_threshold_string(threshold)
+                                );
+                                continue 'main;
+                            },
+                            Err(e) => println!("{}: {e}", "ERROR".red()),
+                        }
+                    }
+                },
+                "19" => {
+                    profile.serial_unzip = !profile.serial_unzip;
+                    println!(
+                        "{}: Writing one file at a time has been set to '{}'.",
+                        "OK".green(),
+                        profile.serial_unzip
+                    );
+                    if profile.serial_unzip {
+                        println!(
+                            "{}",
+                            "Hint: Updates write the files in the order of the download \
+                             for a less fragmented install, but take longer. See the \
+                             troubleshooting guide."
+                                .dimmed()
+                        );
+                    }
+                    continue 'main;
+                },
+                "20" => {
+                    println!("Which files should be made executable? (use 'q' to quit)");
+                    println!(
+                        "{}",
+                        "Hint: Paths relative to the install folder, separated by \
+                         commas. They are patched on NixOS and signed on macOS, leave \
+                         it empty for the default.\nExample: veloren-voxygen, \
+                         veloren-server-cli"
+                            .dimmed()
+                    );
+                    let input = editor.readline_with_initial(
+                        "> ",
+                        (&profile.executables.join(", "), ""),
+                    )?;
+                    if input.trim() == "q" {
+                        continue 'main;
+                    }
+                    profile.executables = input
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|file| !file.is_empty())
+                        .map(str::to_owned)
+                        .collect();
+                    if profile.executables.is_empty() {
+                        profile.executables = crate::profiles::default_executables();
+                    }
+                    println!(
+                        "{}: The executables have been set to '{}'.",
+                        "OK".green(),
+                        profile.executables.join(", ")
+                    );
+                    continue 'main;
+                },
+                "21" => {
+                    println!(
+                        "How many previous versions should be kept? (use 'q' to quit)"
+                    );
+                    println!(
+                        "{}",
+                        "Hint: The installed version is copied before every update, so \
+                         `airshipper rollback` can restore it. Every kept version needs \
+                         as much disk space as the game, 0 disables it."
+                            .dimmed()
+                    );
+                    loop {
+                        let input = editor.readline_with_initial(
+                            "> ",
+                            (&profile.keep_versions.to_string(), ""),
+                        )?;
+                        if input.trim() == "q" {
+                            break;
+                        }
+                        match input.trim().parse() {
+                            Ok(keep) => {
+                                profile.keep_versions = keep;
+                                println!(
+                                    "{}: The kept versions have been set to '{keep}'.",
+                                    "OK".green(),
+                                );
+                                continue 'main;
+                            },
+                            Err(_) => println!(
+                                "{}: '{}' is no number",
+                                "ERROR".red(),
+                                input.trim()
+                            ),
+                        }
+                    }
+                },
+                "22" => {
+                    profile.theme = match profile.theme {
+                        UiTheme::Default => UiTheme::HighContrast,
+                        UiTheme::HighContrast => UiTheme::Default,
+                    };
+                    println!(
+                        "{}: The theme has been set to '{}'.",
+                        "OK".green(),
+                        profile.theme
+                    );
+                    continue 'main;
+                },
+                "23" => {
+                    println!("How far should the launcher be zoomed? (use 'q' to quit)");
+                    println!(
+                        "{}",
+                        format!(
+                            "Hint: A percentage between {}% and {}%.\nExample: 125%",
+                            UiScale::MIN,
+                            UiScale::MAX
+                        )
+                        .dimmed()
+                    );
+                    loop {
+                        let input = editor.readline_with_initial(
+                            "> ",
+                            (&profile.ui_scale.to_string(), ""),
+                        )?;
+                        if input.trim() == "q" {
+                            break;
+                        }
+                        match parse_ui_scale(&input) {
+                            Ok(scale) => {
+                                profile.ui_scale = scale;
+                                println!(
+                                    "{}: The zoom has been set to '{}'.",
+                                    "OK".green(),
+                                    profile.ui_scale
+                                );
+                                continue 'main;
+                            },
+                            Err(e) => println!("{}: {e}", "ERROR".red()),
+                        }
+                    }
+                },
+                "24" => {
+                    profile.compress_stored_files = !profile.compress_stored_files;
+                    println!(
+                        "{}: Compressing the saved state and caches has been set to \
+                         '{}'.",
+                        "OK".green(),
+                        profile.compress_stored_files
+                    );
+                    println!(
+                        "{}",
+                        "Hint: Files are converted the next time they are saved."
+                            .dimmed()
+                    );
+                    continue 'main;
+                },
+                "25" => {
+                    profile.progress_metric = match profile.progress_metric {
+                        ProgressMetric::Step => ProgressMetric::Combined,
+                        ProgressMetric::Combined => ProgressMetric::Step,
+                    };
+                    println!(
+                        "{}: The progress bar has been set to '{}'.",
+                        "OK".green(),
+                        profile.progress_metric
+                    );
+                    continue 'main;
+                },
+                "26" => {
+                    profile.update_log = !profile.update_log;
+                    println!(
+                        "{}: The detailed update log has been set to '{}'.",
+                        "OK".green(),
+                        profile.update_log
+                    );
+                    println!(
+                        "{}",
+                        format!(
+                            "Hint: Every update overwrites {} with one JSON object per \
+                             line.",
+                            fs::update_log_file().display()
+                        )
+                        .dimmed()
+                    );
+                    continue 'main;
+                },
+                "27" => {
+                    println!(
+                        "After how many seconds should confirmations take the default \
+                         answer? (use 'q' to quit)"
+                    );
+                    println!(
+                        "{}",
+                        "Hint: Keeps automated runs from waiting forever on an \
+                         unexpected prompt, 'off' waits for an answer."
+                            .dimmed()
+                    );
+                    let current = profile
+                        .prompt_timeout
+                        .map_or_else(|| "off".to_string(), |secs| secs.to_string());
+                    let timeout = loop {
+                        let input = editor.readline_with_initial("> ", (&current, ""))?;
+                        match input.trim() {
+                            "q" => continue 'main,
+                            input => match parse_prompt_timeout(input) {
+                                Ok(timeout) => break timeout,
+                                Err(e) => println!("{}: {e}", "ERROR".red()),
+                            },
+                        }
+                    };
+                    if timeout.is_some() {
+                        println!("What should the default answer be? [yes/no]");
+                        let current = profile.prompt_default.to_string();
+                        loop {
+                            let input =
+                                editor.readline_with_initial("> ", (&current, ""))?;
+                            match input.parse::<PromptAnswer>() {
+                                Ok(answer) => {
+                                    profile.prompt_default = answer;
+                                    break;
+                                },
+                                Err(e) => println!("{}: {e}", "ERROR".red()),
+                            }
+                        }
+                    }
+                    profile.prompt_timeout = timeout;
+                    println!(
+                        "{}: The prompt timeout has been set to '{}'.",
+                        "OK".green(),
+                        prompt_timeout_string(profile)
+                    );
+                    continue 'main;
+                },
                 "q" => break 'main Ok(()),
                 input => println!("{}: Invalid option '{input}'.", "ERROR".red()),
             }
@@ -302,6 +1165,504 @@ async fn config(profile: &mut Profile) -> Result<()> {
     }
 }
 
+/// Changes a setting for `airshipper config set`, validated like in the interactive
+/// menu. Returns the new value.
+fn config_set(profile: &mut Profile, key: ConfigKey, value: &str) -> Result<String> {
+    use colored::Colorize;
+
+    let value = value.trim();
+    let invalid = |msg: String| ClientError::Custom(msg);
+    match key {
+        ConfigKey::EnvVars => {
+            if let Some(e) = parse_env_vars(value).1.first() {
+                return Err(invalid(format!("Invalid environment variables: {e}")));
+            }
+            for warning in profile.env_var_warnings(value) {
+                println!("{}: {warning}", "WARNING".yellow());
+            }
+            profile.env_vars = value.to_string();
+            Ok(profile.env_vars.clone())
+        },
+        ConfigKey::WgpuBackend => {
+            let backend = profile
+                .known_wgpu_backends()
+                .iter()
+                .find(|backend| backend.to_string().eq_ignore_ascii_case(value))
+                .copied()
+                .ok_or_else(|| {
+                    let supported: Vec<_> = profile
+                        .known_wgpu_backends()
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect();
+                    invalid(format!(
+                        "'{value}' is not supported, use one of: {}",
+                        supported.join(", ")
+                    ))
+                })?;
+            profile.wgpu_backend = backend;
+            Ok(backend.to_string())
+        },
+        ConfigKey::LaunchWrapper => {
+            if !value.is_empty() {
+                validate_launch_wrapper(value)?;
+            }
+            profile.launch_wrapper = value.to_string();
+            Ok(profile.launch_wrapper.clone())
+        },
+        ConfigKey::LowMemory => {
+            profile.low_memory = value
+                .parse()
+                .map_err(|_| invalid(format!("'{value}' is neither true nor false")))?;
+            Ok(profile.low_memory.to_string())
+        },
+        ConfigKey::KeepVersions => {
+            profile.keep_versions = value
+                .parse()
+                .map_err(|_| invalid(format!("'{value}' is no number")))?;
+            Ok(profile.keep_versions.to_string())
+        },
+        ConfigKey::Zoom => {
+            profile.ui_scale = parse_ui_scale(value).map_err(invalid)?;
+            Ok(profile.ui_scale.to_string())
+        },
+        ConfigKey::UpdateLog => {
+            profile.update_log = value
+                .parse()
+                .map_err(|_| invalid(format!("'{value}' is neither true nor false")))?;
+            Ok(profile.update_log.to_string())
+        },
+        ConfigKey::MaxBatchGap => {
+            profile.max_batch_gap = match value {
+                "default" => None,
+                size => Some(
+                    size.parse::<bytesize::ByteSize>()
+                        .map_err(|_| invalid(format!("'{size}' is no size like 256 KB")))?
+                        .as_u64(),
+                ),
+            };
+            Ok(profile
+                .max_batch_gap
+                .map_or("default".to_string(), pretty_bytes))
+        },
+        ConfigKey::KeepLogs => {
+            profile.log_retention.max_files = value
+                .parse()
+                .map_err(|_| invalid(format!("'{value}' is no number")))?;
+            Ok(profile.log_retention.max_files.to_string())
+        },
+        ConfigKey::KeepLogDays => {
+            profile.log_retention.max_days = value
+                .parse()
+                .map_err(|_| invalid(format!("'{value}' is no number")))?;
+            Ok(profile.log_retention.max_days.to_string())
+        },
+        ConfigKey::PromptTimeout => {
+            profile.prompt_timeout = parse_prompt_timeout(value).map_err(invalid)?;
+            Ok(profile
+                .prompt_timeout
+                .map_or_else(|| "off".to_string(), |secs| secs.to_string()))
+        },
+        ConfigKey::PromptDefault => {
+            profile.prompt_default = value.parse().map_err(invalid)?;
+            Ok(profile.prompt_default.to_string())
+        },
+        ConfigKey::BrowserCommand => {
+            profile.browser_command = value.trim().to_string();
+            Ok(profile.browser_command.clone())
+        },
+    }
+}
+
+/// Name of `key` on the command line, e.g. `env-vars`
+fn config_key_name(key: ConfigKey) -> String {
+    use clap::ValueEnum;
+
+    key.to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_else(|| format!("{key:?}"))
+}
+
+/// Accepts seconds or `off`, `0` is `off` as well
+fn parse_prompt_timeout(input: &str) -> std::result::Result<Option<u64>, String> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    input
+        .parse::<u64>()
+        .map(|secs| Some(secs).filter(|secs| *secs > 0))
+        .map_err(|_| format!("'{input}' is neither 'off' nor a number of seconds"))
+}
+
+/// e.g. `30s, then no`
+fn prompt_timeout_string(profile: &Profile) -> String {
+    match profile.prompt_timeout {
+        Some(secs) => format!("{secs}s, then {}", profile.prompt_default),
+        None => "off".to_string(),
+    }
+}
+
+/// Accepts a percentage like `125%`, the `%` is optional
+fn parse_ui_scale(input: &str) -> std::result::Result<UiScale, String> {
+    let input = input.trim();
+    let percent: u16 = input
+        .trim_end_matches('%')
+        .trim()
+        .parse()
+        .map_err(|_| format!("'{input}' is no percentage like 125%"))?;
+    if !(UiScale::MIN..=UiScale::MAX).contains(&percent) {
+        return Err(format!(
+            "The zoom has to be between {}% and {}%",
+            UiScale::MIN,
+            UiScale::MAX
+        ));
+    }
+    Ok(UiScale(percent))
+}
+
+/// Accepts `off` or a size like `2 GB`
+fn parse_large_update_threshold(input: &str) -> std::result::Result<Option<u64>, String> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    input
+        .parse::<bytesize::ByteSize>()
+        .map(|size| Some(size.as_u64()))
+        .map_err(|_| format!("'{input}' is neither 'off' nor a size like 2 GB"))
+}
+
+fn large_update_threshold_string(threshold: Option<u64>) -> String {
+    threshold.map_or("off".to_string(), pretty_bytes)
+}
+
+fn cache(action: CacheAction) -> Result<()> {
+    let versions = crate::update::cached_versions()?;
+
+    match action {
+        CacheAction::List => {
+            if versions.is_empty() {
+                println!("No versions cached.");
+            }
+            for cached in &versions {
+                let modified = chrono::DateTime::<chrono::Local>::from(cached.modified);
+                println!(
+                    "{}  {}  {}",
+                    cached.version,
+                    pretty_bytes(cached.size),
+                    modified.format("%Y-%m-%d %H:%M")
+                );
+            }
+        },
+        CacheAction::Prune {
+            keep,
+            versions: names,
+        } => {
+            if keep.is_none() && names.is_empty() {
+                return Err(ClientError::Custom(
+                    "Specify the versions to remove or `--keep N`".to_string(),
+                ));
+            }
+            // versions are sorted newest first
+            let to_remove = versions.iter().enumerate().filter(|(idx, cached)| {
+                keep.is_some_and(|keep| *idx >= keep) || names.contains(&cached.version)
+            });
+            let mut freed = 0;
+            for (_, cached) in to_remove {
+                std::fs::remove_file(&cached.path)?;
+                println!("Removed {}", cached.version);
+                freed += cached.size;
+            }
+            println!("Freed {}", pretty_bytes(freed));
+        },
+    }
+    Ok(())
+}
+
+fn rollback(profile: &mut Profile, version: Option<String>, list: bool) -> Result<()> {
+    if list {
+        let kept = crate::rollback::kept_versions(profile)?;
+        if kept.is_empty() {
+            println!("No versions are kept");
+        }
+        for kept in kept {
+            println!(
+                "{}  kept {}",
+                kept.version,
+                pretty_time(Some(&kept.kept_at))
+            );
+        }
+        return Ok(());
+    }
+
+    let restored = tokio::task::block_in_place(|| {
+        crate::rollback::rollback(profile, version.as_deref())
+    })?;
+    println!("Restored version {restored}");
+    println!(
+        "Start it with `airshipper start`, `airshipper update` installs the latest \
+         version again"
+    );
+    Ok(())
+}
+
+async fn slot(profile: &mut Profile, action: SlotAction) -> Result<()> {
+    match action {
+        SlotAction::List => {
+            let active = profile.slot_name().to_owned();
+            for name in profile.slot_names() {
+                let (server, channel, version) =
+                    match profile.slots.iter().find(|slot| slot.name == name) {
+                        Some(slot) => (slot.server, &slot.channel, &slot.version),
+                        None => (profile.server, &profile.channel, &profile.version),
+                    };
+                println!(
+                    "{} {name}  {server}/{channel}  {}",
+                    if name == active { "*" } else { " " },
+                    version.as_deref().unwrap_or("not installed")
+                );
+            }
+        },
+        SlotAction::Switch { name } => {
+            profile.switch_slot(&name)?;
+            profile.reload_wgpu_backends();
+            println!(
+                "Switched to slot {name} ({}/{}), installed in {}",
+                profile.server,
+                profile.channel,
+                profile.directory().display()
+            );
+            if !profile.installed() {
+                println!("The slot is not installed yet, run `airshipper update`");
+            }
+        },
+        SlotAction::Remove { name, keep_files } => {
+            let dir = profile.remove_slot(&name)?;
+            if keep_files {
+                println!(
+                    "Removed slot {name}, its files are kept in {}",
+                    dir.display()
+                );
+            } else {
+                let size = fs::install_size(dir.clone()).await.map(|s| s.total);
+                tokio::fs::remove_dir_all(&dir).await?;
+                println!(
+                    "Removed slot {name} and freed {}",
+                    size.map_or("an unknown amount".to_string(), pretty_bytes)
+                );
+            }
+        },
+    }
+    Ok(())
+}
+
+fn version(format: OutputFormat) -> Result<()> {
+    let profile = Profile::load_saved_state().ok();
+    let game_version = profile.as_ref().and_then(|p| p.version.as_ref());
+    let last_checked = profile.as_ref().and_then(|p| p.last_checked.as_ref());
+    let last_updated = profile.as_ref().and_then(|p| p.last_updated.as_ref());
+    let launcher_version = env!("CARGO_PKG_VERSION");
+    let target = env!("AIRSHIPPER_TARGET");
+    let server_api_version = supported_server_api_version();
+
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::json!({
+                "launcher_version": launcher_version,
+                "game_version": game_version,
+                "last_checked": last_checked,
+                "last_updated": last_updated,
+                "server_api_version": server_api_version,
+                "os": std::env::consts::OS,
+                "arch": std::env::consts::ARCH,
+                "target": target,
+            });
+            println!("{json:#}");
+        },
+        OutputFormat::Text => {
+            println!("Airshipper: {launcher_version}");
+            println!(
+                "Game: {}",
+                game_version.map_or("not installed", String::as_str)
+            );
+            println!("Last checked: {}", pretty_time(last_checked));
+            println!("Last updated: {}", pretty_time(last_updated));
+            println!(
+                "Server API: {}",
+                server_api_version.map_or("any".to_string(), |v| v.to_string())
+            );
+            println!("Target: {target}");
+        },
+    }
+    Ok(())
+}
+
+async fn manifest(
+    profile: &Profile,
+    path: &std::path::Path,
+    format: ManifestFormat,
+) -> Result<()> {
+    if !profile.installed() {
+        return Err(ClientError::Custom(
+            "The game is not installed, install it via `airshipper update`".to_string(),
+        ));
+    }
+    let installed = profile.clone();
+    let manifest =
+        tokio::task::spawn_blocking(move || crate::manifest::manifest(&installed))
+            .await??;
+    let content = match format {
+        ManifestFormat::Json => serde_json::to_string_pretty(&manifest)
+            .map_err(|e| ClientError::Custom(e.to_string()))?,
+        ManifestFormat::Ron => {
+            ron::ser::to_string_pretty(&manifest, ron::ser::PrettyConfig::default())?
+        },
+    };
+    std::fs::write(path, content)?;
+    println!(
+        "Wrote the manifest of {} files to {}, checksum {:08x}",
+        manifest.files.len(),
+        path.display(),
+        manifest.checksum
+    );
+    Ok(())
+}
+
+async fn paths(profile: &Profile, sizes: bool) -> Result<()> {
+    println!("Base path: {}", fs::base_path());
+    println!("Config path: {}", fs::config_path());
+    println!("Profile path: {}", profile.directory().display());
+    if profile.slot.is_some() {
+        println!("Install slot: {}", profile.slot_name());
+    }
+    println!("Cache path: {}", fs::get_cache_path().display());
+    println!("Log file: {}", fs::log_file().display());
+
+    if sizes {
+        let size = fs::install_size(profile.directory()).await?;
+        println!();
+        println!("Install size: {}", pretty_bytes(size.total));
+        for (name, bytes) in &size.entries {
+            println!("  {name}: {}", pretty_bytes(*bytes));
+        }
+    }
+    Ok(())
+}
+
+async fn check(profile: &mut Profile) -> Result<ExitCode> {
+    use crate::update::{QuickCheck, quick_check};
+
+    let code = match quick_check(profile).await? {
+        QuickCheck::UpToDate => {
+            println!(
+                "Up-to-date: {}",
+                profile.version.as_deref().unwrap_or_default()
+            );
+            ExitCode::Success
+        },
+        QuickCheck::Outdated { installed, latest } => {
+            println!(
+                "Outdated: {} installed, {latest} available",
+                installed.as_deref().unwrap_or("nothing")
+            );
+            ExitCode::UpdateAvailable
+        },
+        QuickCheck::Modified => {
+            println!(
+                "Outdated: the game files changed since the last update, run \
+                 `airshipper update` to verify them"
+            );
+            ExitCode::UpdateAvailable
+        },
+        QuickCheck::Offline => {
+            println!("Offline: the download server could not be reached");
+            return Ok(ExitCode::Offline);
+        },
+    };
+    profile.last_checked = Some(chrono::Utc::now());
+    Ok(code)
+}
+
+async fn check_channel(profile: &Profile, channel: String) -> Result<()> {
+    let channel = channels::Channel(channel.to_lowercase());
+    println!(
+        "Checking channel {} on {}",
+        channel.0,
+        profile
+            .download_host()
+            .as_deref()
+            .unwrap_or(profile.server.url())
+    );
+
+    let check = channels::check(profile, channel).await?;
+    println!("Version: {}", check.version);
+    println!("Download size: {}", pretty_bytes(check.size));
+    println!("The channel can be used");
+    Ok(())
+}
+
+async fn changelog(
+    profile: &Profile,
+    since: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    use crate::gui::components::ChangelogPanelComponent;
+
+    let since = since
+        .map(|v| semver::Version::parse(v.trim_start_matches('v')))
+        .transpose()
+        .map_err(|e| ClientError::Custom(format!("Invalid version: {e}")))?;
+
+    let changelog = ChangelogPanelComponent::fetch(profile.channel.clone())
+        .await?
+        .unwrap_or_default();
+    let versions: Vec<_> = changelog
+        .versions
+        .into_iter()
+        .filter(|v| {
+            since
+                .as_ref()
+                .is_none_or(|since| is_newer(&v.version, since))
+        })
+        .collect();
+
+    match format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&versions) {
+            Ok(json) => println!("{json}"),
+            Err(e) => return Err(ClientError::Custom(e.to_string())),
+        },
+        OutputFormat::Text => {
+            for version in &versions {
+                match &version.date {
+                    Some(date) => println!("v{} ({date})", version.version),
+                    None => println!("{}", version.version),
+                }
+                for note in &version.notes {
+                    println!("{note}");
+                }
+                for (section, lines) in &version.sections {
+                    println!();
+                    println!("{section}");
+                    for line in lines {
+                        println!("  - {line}");
+                    }
+                }
+                println!();
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Whether a changelog version is newer than `since`. Unreleased changes are always
+/// newer, unparseable versions never are.
+fn is_newer(version: &str, since: &semver::Version) -> bool {
+    version == "Unreleased"
+        || semver::Version::parse(version.trim_start_matches('v'))
+            .is_ok_and(|version| &version > since)
+}
+
 #[cfg(windows)]
 fn upgrade() -> Result<()> {
     match crate::windows::query()? {
@@ -314,18 +1675,290 @@ fn upgrade() -> Result<()> {
     Ok(())
 }
 
-/// Will read from stdin for confirmation
-/// NOTE: no input = true
-/// Temporary...
-pub fn confirm_action() -> Result<bool> {
-    let mut buffer = String::new();
-    let _ = std::io::stdin().read_line(&mut buffer)?;
-    buffer = buffer.to_lowercase();
+/// Will read from stdin for confirmation, returns `None` if the update should be
+/// skipped until a newer version is released.
+/// NOTE: no input = true, no answer within the prompt timeout = `prompt_default`
+fn confirm_update(profile: &Profile) -> Result<Option<bool>> {
+    let Some(answer) = read_answer(profile)? else {
+        return Ok(Some(profile.prompt_default == PromptAnswer::Yes));
+    };
+
+    match answer.trim().to_lowercase().as_str() {
+        "s" | "skip" => Ok(None),
+        answer => Ok(Some(answer.is_empty() || answer.starts_with('y'))),
+    }
+}
+
+/// Will read from stdin for confirmation of a large update.
+/// NOTE: no input = false, so unattended updates don't download it. No answer within
+/// the prompt timeout = `prompt_default`
+fn confirm_large_update(profile: &Profile) -> Result<bool> {
+    let Some(answer) = read_answer(profile)? else {
+        return Ok(profile.prompt_default == PromptAnswer::Yes);
+    };
+
+    Ok(answer.trim().to_lowercase().starts_with('y'))
+}
+
+/// Reads an answer from stdin, `None` if there was none within the `prompt_timeout`
+/// of `profile`. Without a timeout it blocks until a line arrives.
+fn read_answer(profile: &Profile) -> Result<Option<String>> {
+    let Some(secs) = profile.prompt_timeout else {
+        let mut buffer = String::new();
+        let _ = std::io::stdin().read_line(&mut buffer)?;
+        return Ok(Some(buffer));
+    };
+
+    match read_line_timeout(Duration::from_secs(secs)) {
+        Some(line) => Ok(Some(line?)),
+        None => {
+            tracing::warn!(
+                "No answer within {secs} seconds, answering '{}'",
+                profile.prompt_default
+            );
+            Ok(None)
+        },
+    }
+}
+
+/// Reads a line from stdin, `None` if none was entered within `timeout`. The lines
+/// are read by a thread which keeps waiting after a timeout, so it is shared by all
+/// prompts and a late answer goes to the next one.
+fn read_line_timeout(timeout: Duration) -> Option<std::io::Result<String>> {
+    static LINES: LazyLock<Mutex<Receiver<std::io::Result<String>>>> =
+        LazyLock::new(|| {
+            Mutex::new(read_lines(std::io::BufReader::new(std::io::stdin())))
+        });
+
+    let lines = LINES.lock().unwrap_or_else(|e| e.into_inner());
+    recv_line(&lines, timeout)
+}
+
+/// Sends the lines of `input` from a thread, until the end of the input or an error.
+fn read_lines(
+    mut input: impl std::io::BufRead + Send + 'static,
+) -> Receiver<std::io::Result<String>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        loop {
+            let mut buffer = String::new();
+            let line = input.read_line(&mut buffer).map(|_| buffer);
+            let last = !matches!(&line, Ok(line) if !line.is_empty());
+            if sender.send(line).is_err() || last {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn recv_line(
+    lines: &Receiver<std::io::Result<String>>,
+    timeout: Duration,
+) -> Option<std::io::Result<String>> {
+    match lines.recv_timeout(timeout) {
+        Ok(line) => Some(line),
+        Err(RecvTimeoutError::Timeout) => None,
+        // like `read_line` at the end of the input
+        Err(RecvTimeoutError::Disconnected) => Some(Ok(String::new())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_line_timeout() {
+        let timeout = Duration::from_millis(50);
+        let (sender, lines) = mpsc::channel();
+        assert!(recv_line(&lines, timeout).is_none());
+
+        // an answer after the timeout goes to the next prompt
+        sender.send(Ok("yes\n".to_owned())).unwrap();
+        assert_eq!(recv_line(&lines, timeout).unwrap().unwrap(), "yes\n");
+
+        drop(sender);
+        assert_eq!(recv_line(&lines, timeout).unwrap().unwrap(), "");
+
+        let lines = read_lines(std::io::Cursor::new("no\n"));
+        assert_eq!(recv_line(&lines, timeout).unwrap().unwrap(), "no\n");
+        // the end of the input is an empty line, every time
+        assert_eq!(recv_line(&lines, timeout).unwrap().unwrap(), "");
+        assert_eq!(recv_line(&lines, timeout).unwrap().unwrap(), "");
+    }
+
+    #[test]
+    fn test_config_set() {
+        use crate::profiles::WgpuBackend;
+
+        let mut profile = Profile::default();
+        profile.supported_wgpu_backends = vec![WgpuBackend::Vulkan, WgpuBackend::Auto];
+
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::EnvVars, "FOO=BAR").unwrap(),
+            "FOO=BAR"
+        );
+        assert!(config_set(&mut profile, ConfigKey::EnvVars, "FOO").is_err());
+        assert_eq!(profile.env_vars, "FOO=BAR");
+
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::WgpuBackend, "vulkan").unwrap(),
+            "Vulkan"
+        );
+        assert!(config_set(&mut profile, ConfigKey::WgpuBackend, "dx12").is_err());
+        assert_eq!(profile.wgpu_backend, WgpuBackend::Vulkan);
+        // before the game was asked, e.g. when it is not installed yet
+        profile.supported_wgpu_backends.clear();
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::WgpuBackend, "auto").unwrap(),
+            "Auto"
+        );
+        assert!(config_set(&mut profile, ConfigKey::WgpuBackend, "none").is_err());
+
+        assert!(config_set(&mut profile, ConfigKey::LowMemory, "yes").is_err());
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::LowMemory, "true").unwrap(),
+            "true"
+        );
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::Zoom, "125%").unwrap(),
+            "125%"
+        );
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::UpdateLog, "true").unwrap(),
+            "true"
+        );
+        assert!(profile.update_log);
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::MaxBatchGap, "256 KB").unwrap(),
+            pretty_bytes(256_000)
+        );
+        assert_eq!(profile.max_batch_gap, Some(256_000));
+        assert!(config_set(&mut profile, ConfigKey::MaxBatchGap, "far").is_err());
+        config_set(&mut profile, ConfigKey::MaxBatchGap, "default").unwrap();
+        assert_eq!(profile.max_batch_gap, None);
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::KeepLogs, "10").unwrap(),
+            "10"
+        );
+        assert!(config_set(&mut profile, ConfigKey::KeepLogDays, "-1").is_err());
+        assert_eq!(profile.log_retention.max_files, 10);
+        config_set(&mut profile, ConfigKey::BrowserCommand, " firefox {url} ").unwrap();
+        assert_eq!(profile.browser_command, "firefox {url}");
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::PromptTimeout, "30").unwrap(),
+            "30"
+        );
+        assert_eq!(profile.prompt_timeout, Some(30));
+        config_set(&mut profile, ConfigKey::PromptTimeout, "0").unwrap();
+        assert_eq!(profile.prompt_timeout, None);
+        assert!(config_set(&mut profile, ConfigKey::PromptTimeout, "soon").is_err());
+        assert_eq!(
+            config_set(&mut profile, ConfigKey::PromptDefault, "Y").unwrap(),
+            "yes"
+        );
+        assert!(config_set(&mut profile, ConfigKey::PromptDefault, "maybe").is_err());
+        assert_eq!(config_key_name(ConfigKey::WgpuBackend), "wgpu-backend");
+    }
+
+    #[test]
+    fn test_parse_ui_scale() {
+        assert_eq!(parse_ui_scale("125%"), Ok(UiScale(125)));
+        assert_eq!(parse_ui_scale(" 90 "), Ok(UiScale(90)));
+        assert!(parse_ui_scale("300%").is_err());
+        assert!(parse_ui_scale("big").is_err());
+    }
+
+    #[test]
+    fn test_large_update_threshold() {
+        assert_eq!(parse_large_update_threshold("Off"), Ok(None));
+        assert_eq!(parse_large_update_threshold("0"), Ok(Some(0)));
+        assert_eq!(
+            parse_large_update_threshold("2 GB"),
+            Ok(Some(2_000_000_000))
+        );
+        assert!(parse_large_update_threshold("huge").is_err());
+    }
 
-    if buffer.trim().is_empty() || buffer.starts_with('y') {
-        return Ok(true);
-    } else if buffer.starts_with('n') {
-        return Ok(false);
+    #[test]
+    fn test_changelog_since() {
+        let since = semver::Version::parse("0.16.0").unwrap();
+        assert!(is_newer("Unreleased", &since));
+        assert!(is_newer("0.17.0", &since));
+        assert!(is_newer("v0.16.1", &since));
+        assert!(!is_newer("0.16.0", &since));
+        assert!(!is_newer("0.15.0", &since));
+        assert!(!is_newer("not a version", &since));
+    }
+
+    #[test]
+    fn test_connect_address() {
+        use clap::Parser;
+
+        let cmd = CmdLine::try_parse_from([
+            "airshipper",
+            "start",
+            "--connect",
+            "localhost:14004",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cmd.action,
+            Some(Action::Start { connect: Some(address), .. }) if address == "localhost:14004"
+        ));
+        assert!(
+            CmdLine::try_parse_from(["airshipper", "run", "--connect", "localhost:port"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_check_channel() {
+        use clap::Parser;
+
+        let cmd =
+            CmdLine::try_parse_from(["airshipper", "check-channel", "nightly"]).unwrap();
+        assert!(matches!(
+            cmd.action,
+            Some(Action::CheckChannel { channel }) if channel == "nightly"
+        ));
+        assert!(CmdLine::try_parse_from(["airshipper", "check-channel"]).is_err());
+    }
+
+    #[test]
+    fn test_tui_flag() {
+        use clap::Parser;
+
+        let cmd = CmdLine::try_parse_from(["airshipper", "update", "--tui"]).unwrap();
+        assert!(matches!(cmd.action, Some(Action::Update { tui: true })));
+        let cmd = CmdLine::try_parse_from(["airshipper", "run"]).unwrap();
+        assert!(matches!(cmd.action, Some(Action::Run { tui: false, .. })));
+    }
+
+    #[test]
+    fn test_benchmark_size() {
+        use clap::Parser;
+
+        let cmd = CmdLine::try_parse_from(["airshipper", "benchmark"]).unwrap();
+        assert!(matches!(
+            cmd.action,
+            Some(Action::Benchmark {
+                size: 10_000_000,
+                format: OutputFormat::Text
+            })
+        ));
+        let cmd = CmdLine::try_parse_from(["airshipper", "benchmark", "--size", "2MiB"])
+            .unwrap();
+        assert!(matches!(
+            cmd.action,
+            Some(Action::Benchmark {
+                size: 2_097_152,
+                ..
+            })
+        ));
+        assert!(
+            CmdLine::try_parse_from(["airshipper", "benchmark", "--size", "0"]).is_err()
+        );
     }
-    Ok(false)
 }
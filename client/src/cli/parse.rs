@@ -1,10 +1,30 @@
 //! This module parses command line arguments and returns a parsed struct on which
 //! the GUI/CLI can act upon.
-use clap::{ArgAction::Count, Parser, Subcommand, crate_authors, crate_version};
+#[cfg(windows)]
+use crate::profiles::ConsoleMode;
+use clap::{
+    ArgAction::Count, Parser, Subcommand, ValueEnum, crate_authors, crate_version,
+};
+use std::path::PathBuf;
+
+const EXIT_CODES_HELP: &str = "Exit codes:
+    0  Success, the game is up-to-date
+    1  An unspecified error occurred
+    2  An update has been downloaded and applied
+    3  An update is available but has been declined
+    4  The download server could not be reached
+    5  The downloaded game files failed verification
+    6  An update is available (`airshipper check`)
+  130  The update was cancelled with Ctrl+C";
 
 /// Provides automatic updates for the voxel RPG Veloren. ( <https://veloren.net> )
 #[derive(Parser, Debug, Default, Clone)]
-#[command(name = "Airshipper", version = crate_version!(), author = crate_authors!())]
+#[command(
+    name = "Airshipper",
+    version = crate_version!(),
+    author = crate_authors!(),
+    after_help = EXIT_CODES_HELP
+)]
 pub struct CmdLine {
     #[command(subcommand)]
     pub action: Option<Action>,
@@ -14,29 +34,273 @@ pub struct CmdLine {
     /// Set the logging verbosity for Airshipper (d = DEBUG, dd = TRACE)
     #[arg(short, long, action = Count, global = true)]
     pub debug: u8,
+    /// Load and save the launcher configuration from this file instead of the default
+    /// one
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
     /// Force a reset of all user data on startup
     #[arg(long, global = true)]
     pub force_reset: bool,
+    /// Reduce the memory usage while downloading updates at the cost of speed
+    #[arg(long, global = true)]
+    pub low_memory: bool,
+    /// Use a lighter launcher UI for low-end hardware, text and edges will look
+    /// rougher
+    #[arg(long, global = true)]
+    pub lite_ui: bool,
+    /// Always show the console window on Windows. By default it is only shown when
+    /// Airshipper is started from a console
+    #[arg(long, global = true, conflicts_with = "hide_console")]
+    pub show_console: bool,
+    /// Always hide the console window on Windows
+    #[arg(long, global = true)]
+    pub hide_console: bool,
 }
 
 #[derive(Debug, Clone, Subcommand)]
 pub enum Action {
     /// Starts the game without updating.
-    Start,
+    Start {
+        /// Ignore the graphics settings and start with the game's defaults
+        #[arg(long)]
+        safe_mode: bool,
+        /// Connect to this server once the game started, passed to the game as
+        /// `--server`
+        #[arg(long, value_name = "HOST:PORT", value_parser = parse_server_address)]
+        connect: Option<String>,
+    },
     /// Only updates the game.
-    Update,
+    Update {
+        /// Show the progress of every update step with its speed and ETA
+        #[arg(long)]
+        tui: bool,
+    },
     /// Update and start the game.
-    Run,
+    Run {
+        /// Show the progress of every update step with its speed and ETA
+        #[arg(long)]
+        tui: bool,
+        /// Connect to this server once the game started, passed to the game as
+        /// `--server`
+        #[arg(long, value_name = "HOST:PORT", value_parser = parse_server_address)]
+        connect: Option<String>,
+    },
     /// Use the CLI to configure profiles.
-    Config,
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+    /// Print the paths used by Airshipper.
+    Paths {
+        /// Also report the size of the installation
+        #[arg(long)]
+        sizes: bool,
+    },
+    /// Print the changelog of the selected channel.
+    Changelog {
+        /// Only show versions newer than this one, e.g. `0.16.0`
+        #[arg(long, value_name = "VERSION")]
+        since: Option<String>,
+        /// Output format of the changelog
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Manage the cached remote file lists of game versions.
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Manage the install slots, e.g. to keep a test build next to the regular game.
+    Slot {
+        #[command(subcommand)]
+        action: SlotAction,
+    },
+    /// Restore a previous game version kept before an update, see `keep_versions` in
+    /// `airshipper config`. The replaced version is kept in its place.
+    Rollback {
+        /// Version to restore, the newest kept one by default
+        version: Option<String>,
+        /// Only list the kept versions
+        #[arg(long, conflicts_with = "version")]
+        list: bool,
+    },
+    /// Quickly check whether the game is up-to-date without hashing any files. This
+    /// is no integrity check, `update` verifies every file.
+    Check,
+    /// Check that a channel can be downloaded before switching to it.
+    CheckChannel {
+        /// Name of the channel, e.g. `nightly`
+        #[arg(value_name = "TAG")]
+        channel: String,
+    },
+    /// Check the connection to the download server and the install folder, with
+    /// hints on how to fix failed checks. Exits with 1 if a check failed.
+    Doctor {
+        /// Output format of the checks
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Measure the download speed from the download server without installing
+    /// anything.
+    Benchmark {
+        /// How much of the latest game archive to download, e.g. `50 MB`
+        #[arg(long, value_name = "SIZE", default_value = "10 MB", value_parser = parse_sample_size)]
+        size: u64,
+        /// Output format of the results
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Write a manifest of the installed game files with their sizes and CRC32s, e.g.
+    /// to verify an install or compare two of them. User data and maps which aren't
+    /// synced are left out.
+    Manifest {
+        /// File to write the manifest to
+        path: PathBuf,
+        /// Format of the manifest
+        #[arg(long, value_enum, default_value_t = ManifestFormat::Json)]
+        format: ManifestFormat,
+    },
+    /// Print the versions of Airshipper and the installed game.
+    Version {
+        /// Output format of the versions
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
     /// Update the Launcher if possible.
     #[cfg(windows)]
     Upgrade,
 }
 
+#[derive(Debug, Clone, Subcommand)]
+pub enum CacheAction {
+    /// List the cached versions with their sizes.
+    List,
+    /// Remove cached versions.
+    Prune {
+        /// Only keep the newest N versions
+        #[arg(long, value_name = "N")]
+        keep: Option<usize>,
+        /// Versions to remove
+        versions: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum SlotAction {
+    /// List the install slots with their channels and versions.
+    List,
+    /// Update and start the game from this slot from now on. A new slot is created
+    /// with the server and channel of the current one and installed on the next
+    /// update.
+    Switch {
+        /// Name of the slot, `default` is the regular install
+        name: String,
+    },
+    /// Remove an inactive slot and its game files.
+    Remove {
+        /// Name of the slot
+        name: String,
+        /// Only forget the slot and leave its files on the disk
+        #[arg(long)]
+        keep_files: bool,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigAction {
+    /// Save the configuration to a file, e.g. to carry it over to a reinstalled OS.
+    Export {
+        /// File to save the configuration to
+        path: PathBuf,
+        /// Also save the state of the installed game, like its version. Only useful
+        /// if the game files are carried over as well
+        #[arg(long)]
+        install_state: bool,
+        /// Also save the download credentials, which are stored in plain text
+        #[arg(long)]
+        credentials: bool,
+    },
+    /// Replace the configuration with one saved by `config export`. The current one
+    /// is backed up first.
+    Import {
+        /// File saved by `config export`
+        path: PathBuf,
+    },
+    /// Change a single setting without the interactive menu, e.g. in provisioning
+    /// scripts. Prints the new value.
+    Set { key: ConfigKey, value: String },
+}
+
+/// Settings which can be changed by `config set`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConfigKey {
+    /// Comma separated pairs like `FOO=BAR,BAZ=BIZ`, passed to the game
+    EnvVars,
+    /// Graphics backend of the game, one of the backends it supports like `vulkan`
+    WgpuBackend,
+    /// Command the game is started with like `gamemoderun`, empty to start it directly
+    LaunchWrapper,
+    /// `true` or `false`
+    LowMemory,
+    /// How many previous versions are kept for `airshipper rollback`
+    KeepVersions,
+    /// Zoom of the launcher like `125%`
+    Zoom,
+    /// `true` or `false`, writes a detailed log of the next updates
+    UpdateLog,
+    /// Largest gap between files downloaded with one request like `256 KB`, or
+    /// `default`
+    MaxBatchGap,
+    /// How many rotated launcher logs are kept, `0` keeps all
+    KeepLogs,
+    /// How many days rotated launcher logs are kept, `0` keeps them regardless
+    KeepLogDays,
+    /// Command links are opened with like `firefox`, empty for the system default
+    BrowserCommand,
+    /// Seconds until CLI confirmations take the default answer, or `off`
+    PromptTimeout,
+    /// `yes` or `no`, the answer of CLI confirmations which timed out
+    PromptDefault,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ManifestFormat {
+    Json,
+    Ron,
+}
+
+fn parse_server_address(address: &str) -> Result<String, String> {
+    crate::net::validate_server_address(address)
+        .map(|()| address.to_string())
+        .map_err(str::to_string)
+}
+
+fn parse_sample_size(size: &str) -> Result<u64, String> {
+    match size.parse::<bytesize::ByteSize>() {
+        Ok(size) if size.as_u64() > 0 => Ok(size.as_u64()),
+        _ => Err(format!("'{size}' is no size like 10 MB")),
+    }
+}
+
 impl CmdLine {
     /// Parses command line for arguments and returns itself
     pub(crate) fn new() -> Self {
         CmdLine::parse()
     }
+
+    /// Console visibility requested on the command line, if any
+    #[cfg(windows)]
+    pub(crate) fn console_mode(&self) -> Option<ConsoleMode> {
+        match (self.show_console, self.hide_console) {
+            (true, _) => Some(ConsoleMode::Show),
+            (_, true) => Some(ConsoleMode::Hide),
+            _ => None,
+        }
+    }
 }
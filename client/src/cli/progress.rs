@@ -0,0 +1,175 @@
+//! Progress output of `airshipper update`, either a single bar or a detailed view
+//! with one bar per update step.
+use crate::{
+    logger::pretty_bytes,
+    profiles::ProgressMetric,
+    update::{combined_percent, deleted_files_text, percent_complete},
+};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use remozipsy::ProgressDetails;
+use std::{borrow::Cow, io::IsTerminal};
+
+pub(super) enum UpdateProgress {
+    /// a single bar, for the current step or the whole update
+    Plain(ProgressBar, ProgressMetric),
+    /// the overall progress and one bar per step, each with its speed and ETA
+    Detailed {
+        _multi: MultiProgress,
+        overall: ProgressBar,
+        download: ProgressBar,
+        unzip: ProgressBar,
+        delete: ProgressBar,
+    },
+}
+
+impl UpdateProgress {
+    /// The detailed view redraws lines in place, so it falls back to the plain bar
+    /// if the output isn't a terminal.
+    pub(super) fn new(detailed: bool, metric: ProgressMetric) -> Self {
+        // indicatif draws to stderr
+        if detailed && std::io::stderr().is_terminal() {
+            Self::detailed()
+        } else {
+            if detailed {
+                tracing::info!(
+                    "Not running in a terminal, showing a single progress bar"
+                );
+            }
+            Self::plain(metric)
+        }
+    }
+
+    fn plain(metric: ProgressMetric) -> Self {
+        Self::Plain(
+            ProgressBar::new(100).with_style(
+                ProgressStyle::default_bar()
+                    .template("[{elapsed_precise}] [{bar:40.green/white}] {msg} [{eta}]")
+                    .unwrap()
+                    .progress_chars("=>-"),
+            ),
+            metric,
+        )
+    }
+
+    fn detailed() -> Self {
+        let multi = MultiProgress::new();
+        let bar = |template: &str, name: &'static str| {
+            multi.add(
+                ProgressBar::new(0)
+                    .with_style(
+                        ProgressStyle::default_bar()
+                            .template(template)
+                            .unwrap()
+                            .progress_chars("=>-"),
+                    )
+                    .with_prefix(name),
+            )
+        };
+        let step = |name| {
+            bar(
+                "{prefix:>8} [{bar:40.cyan/white}] {bytes}/{total_bytes} \
+                 {binary_bytes_per_sec} ETA {eta} {msg}",
+                name,
+            )
+        };
+
+        Self::Detailed {
+            overall: bar(
+                "{prefix:>8} [{bar:40.green/white}] {percent:>3}% [{elapsed_precise}] \
+                 {msg}",
+                "Overall",
+            ),
+            download: step("Download"),
+            unzip: step("Unzip"),
+            delete: step("Delete"),
+            _multi: multi,
+        }
+    }
+
+    pub(super) fn set_message(&self, msg: impl Into<Cow<'static, str>>) {
+        match self {
+            Self::Plain(bar, _) => bar.set_message(msg),
+            Self::Detailed { overall, .. } => overall.set_message(msg),
+        }
+    }
+
+    pub(super) fn update(
+        &self,
+        download: &ProgressDetails,
+        unzip: &ProgressDetails,
+        delete: &ProgressDetails,
+        deleted_files: u64,
+    ) {
+        let (step, progress) = match (
+            download.is_finished(),
+            unzip.is_finished(),
+            delete.is_finished(),
+        ) {
+            (false, _, _) => ("Downloading", download),
+            (true, false, _) => ("Unzipping", unzip),
+            (true, true, false) => ("Deleting", delete),
+            (true, true, true) => ("Finalizing", unzip),
+        };
+        let files = deleted_files_text(delete, deleted_files);
+
+        match self {
+            Self::Plain(bar, metric) => {
+                bar.set_position(match metric {
+                    ProgressMetric::Step => percent_complete(progress),
+                    ProgressMetric::Combined => combined_percent(download, unzip),
+                });
+                bar.set_message(if step == "Deleting" {
+                    format!("{files} ({step})")
+                } else {
+                    format!(
+                        "{} / {} ({step})",
+                        pretty_bytes(progress.processed_bytes()),
+                        pretty_bytes(progress.total_bytes()),
+                    )
+                });
+            },
+            Self::Detailed {
+                overall,
+                download: download_bar,
+                unzip: unzip_bar,
+                delete: delete_bar,
+                ..
+            } => {
+                for (bar, progress) in [
+                    (download_bar, download),
+                    (unzip_bar, unzip),
+                    (delete_bar, delete),
+                ] {
+                    bar.set_length(progress.total_bytes());
+                    bar.set_position(progress.processed_bytes());
+                }
+                delete_bar.set_message(files);
+
+                let steps = [download, unzip, delete];
+                let total: u64 = steps.iter().map(|p| p.total_bytes()).sum();
+                let processed: u64 = steps.iter().map(|p| p.processed_bytes()).sum();
+                overall.set_length(total.max(1));
+                overall.set_position(if total == 0 { 1 } else { processed });
+                overall.set_message(step);
+            },
+        }
+    }
+
+    /// Removes the bars, e.g. before reporting that the update was cancelled.
+    pub(super) fn clear(&self) {
+        match self {
+            Self::Plain(bar, _) => bar.finish_and_clear(),
+            Self::Detailed {
+                overall,
+                download,
+                unzip,
+                delete,
+                ..
+            } => {
+                for bar in [overall, download, unzip, delete] {
+                    bar.finish_and_clear();
+                }
+            },
+        }
+    }
+}
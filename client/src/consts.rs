@@ -14,13 +14,21 @@ pub const LOGS_DIR: &str = "userdata\\voxygen\\logs";
 #[cfg(unix)]
 pub const LOGS_DIR: &str = "userdata/voxygen/logs";
 
+#[cfg(windows)]
+pub const SAFE_MODE_CONFIG_DIR: &str = "userdata\\voxygen-safe-mode";
+
+#[cfg(unix)]
+pub const SAFE_MODE_CONFIG_DIR: &str = "userdata/voxygen-safe-mode";
+
 //#[cfg(windows)]
 //pub const SERVER_CLI_FILE: &str = "veloren-server-cli.exe";
 #[cfg(unix)]
 pub const SERVER_CLI_FILE: &str = "veloren-server-cli";
 
 pub const SAVED_STATE_FILE: &str = "airshipper_state.ron";
+pub const EXPORT_FILE: &str = "airshipper_settings.ron";
 pub const LOG_FILE: &str = "airshipper.log";
+pub const UPDATE_LOG_FILE: &str = "airshipper-update.jsonl";
 
 // Networking
 
@@ -6,6 +6,8 @@ pub enum ClientError {
     Io(String),
     #[error("Error while performing network operations: {0}")]
     Network(String),
+    #[error("The server answered with an error: {0}")]
+    Http(String),
     #[error("FATAL: Failed to start GUI! Error: {0}")]
     Iced(String),
     #[error("Failed to save/load ron data: {0}")]
@@ -24,6 +26,51 @@ pub enum ClientError {
     Task(String),
     #[error("Error while updating the game: {0}")]
     GameUpdate(String),
+    #[error(
+        "Downloaded game files failed verification: the download server lists the CRC32 \
+         {remote:08x}, the downloaded file has {calculated:08x}"
+    )]
+    Verification { remote: u32, calculated: u32 },
+    #[error("The channel '{0}' is not available on the download server")]
+    UnknownChannel(String),
+    #[error("The download server is not supported: {0}")]
+    IncompatibleServer(String),
+    #[error(
+        "{0} returned a web page instead of the expected file. A captive portal, like \
+         the login page of a public Wi-Fi, or a misconfigured proxy or mirror might be \
+         in the way"
+    )]
+    HtmlResponse(String),
+    #[error(
+        "Not enough disk space: the update needs {needed} including the free space \
+         margin, {available} are available"
+    )]
+    NotEnoughSpace { needed: String, available: String },
+    #[error(
+        "Invalid launch wrapper, change it in the settings or via `airshipper config`: \
+         {0}"
+    )]
+    LaunchWrapper(String),
+    #[error("The game keeps crashing, not restarting it automatically: {0}")]
+    CrashLoop(String),
+    #[error(
+        "The certificate of {0} does not match any of the pinned keys, refusing to \
+         connect"
+    )]
+    PinnedKeyMismatch(String),
+    #[cfg(unix)]
+    #[error(
+        "Running on NixOS, but {0} is not set. Install Airshipper through its Nix \
+         package (see https://gitlab.com/veloren/airshipper#for-nixos-users) or disable \
+         NixOS patching via `airshipper config` if you run the game in an FHS environment"
+    )]
+    NixPatcherMissing(String),
+    #[cfg(target_os = "macos")]
+    #[error(
+        "Failed to sign the game, check the signing command via `airshipper config` or \
+         don't require signing to start the game unsigned: {0}"
+    )]
+    MacosSigning(String),
 
     #[cfg(windows)]
     #[error("FATAL: Failed to update airshipper! Error: {0}")]
@@ -36,6 +83,49 @@ pub enum ClientError {
     Custom(String),
 }
 
+impl ClientError {
+    /// Short, non-technical reason for the error which can be shown to users.
+    pub fn user_friendly(&self) -> &'static str {
+        match self {
+            ClientError::Io(e) | ClientError::GameUpdate(e) if is_disk_full(e) => {
+                "Disk full"
+            },
+            ClientError::Network(_) => "No internet connection",
+            ClientError::Http(_) => "Server error",
+            ClientError::Io(_) => "Could not access the game files",
+            ClientError::GameUpdate(_) => "Server error",
+            ClientError::Verification { .. } => "Downloaded files are corrupted",
+            ClientError::UnknownChannel(_) => "Unknown channel",
+            ClientError::IncompatibleServer(_) => "Download server not supported",
+            ClientError::HtmlResponse(_) => "Got a web page instead of the game",
+            ClientError::NotEnoughSpace { .. } => "Not enough disk space",
+            ClientError::LaunchWrapper(_) => "Invalid launch wrapper",
+            ClientError::CrashLoop(_) => "The game keeps crashing",
+            ClientError::PinnedKeyMismatch(_) => "Untrusted download server",
+            #[cfg(unix)]
+            ClientError::NixPatcherMissing(_) => "NixOS patcher not configured",
+            #[cfg(target_os = "macos")]
+            ClientError::MacosSigning(_) => "Signing the game failed",
+            _ => "Something went wrong",
+        }
+    }
+}
+
+/// The errors are only kept as text, so the OS error codes are looked for. They mean
+/// different things on every OS, e.g. 39 is ENOTEMPTY on Linux.
+fn is_disk_full(err: &str) -> bool {
+    // ENOSPC
+    #[cfg(unix)]
+    const DISK_FULL: &[&str] = &["os error 28"];
+    // ERROR_DISK_FULL and ERROR_HANDLE_DISK_FULL
+    #[cfg(windows)]
+    const DISK_FULL: &[&str] = &["os error 112", "os error 39"];
+    #[cfg(not(any(unix, windows)))]
+    const DISK_FULL: &[&str] = &[];
+
+    DISK_FULL.iter().any(|code| err.contains(code))
+}
+
 macro_rules! impl_from {
     ($foreign:ty, $local:expr) => {
         impl From<$foreign> for ClientError {
@@ -46,7 +136,6 @@ macro_rules! impl_from {
     };
 }
 impl_from!(std::io::Error, ClientError::Io);
-impl_from!(reqwest::Error, ClientError::Network);
 impl_from!(iced::Error, ClientError::Iced);
 impl_from!(ron::Error, ClientError::Ron);
 impl_from!(ron::de::SpannedError, ClientError::Ron);
@@ -56,10 +145,48 @@ impl_from!(url::ParseError, ClientError::UrlParse);
 impl_from!(rustyline::error::ReadlineError, ClientError::Readline);
 impl_from!(image::error::ImageError, ClientError::Image);
 impl_from!(tokio::task::JoinError, ClientError::Task);
-impl_from!(remozipsy::Error<
-    <remozipsy::reqwest::ReqwestRemoteZip<reqwest::Client> as remozipsy::RemoteZip>::Error,
-    <remozipsy::tokio::TokioLocalStorage as remozipsy::FileSystem>::Error,
->, ClientError::GameUpdate);
+// a download which can't reach the server is reported like any other offline request
+impl
+    From<
+        remozipsy::Error<
+            remozipsy::reqwest::ReqwestRemoteZipError,
+            remozipsy::tokio::TokioLocalStorageError,
+        >,
+    > for ClientError
+{
+    fn from(
+        err: remozipsy::Error<
+            remozipsy::reqwest::ReqwestRemoteZipError,
+            remozipsy::tokio::TokioLocalStorageError,
+        >,
+    ) -> Self {
+        use remozipsy::{RemoteFetchError, reqwest::ReqwestRemoteZipError};
+
+        match &err {
+            remozipsy::Error::Remote(
+                ReqwestRemoteZipError::Reqwest(e)
+                | ReqwestRemoteZipError::RemoteFetch(RemoteFetchError::Fetch(e)),
+            ) if is_offline(e) => ClientError::Network(err.to_string()),
+            _ => ClientError::GameUpdate(err.to_string()),
+        }
+    }
+}
+// certificate pinning failures must not look like being offline, neither must error
+// responses of a server which was reached
+impl From<reqwest::Error> for ClientError {
+    fn from(err: reqwest::Error) -> Self {
+        match crate::net::pinning::pin_mismatch(&err) {
+            Some(host) => ClientError::PinnedKeyMismatch(host),
+            None if is_offline(&err) => ClientError::Network(err.to_string()),
+            None => ClientError::Http(err.to_string()),
+        }
+    }
+}
+
+/// The server could not be reached or did not answer in time.
+fn is_offline(err: &reqwest::Error) -> bool {
+    err.is_connect() || err.is_timeout()
+}
 #[cfg(windows)]
 impl_from!(self_update::errors::Error, ClientError::SelfUpdate);
 #[cfg(windows)]
@@ -86,3 +213,65 @@ pub fn panic_hook() {
         default_hook(panic_info);
     }));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_friendly() {
+        #[cfg(unix)]
+        let disk_full = "No space left on device (os error 28)".to_string();
+        #[cfg(windows)]
+        let disk_full =
+            "There is not enough space on the disk. (os error 112)".to_string();
+        assert_eq!(
+            ClientError::Io(disk_full.clone()).user_friendly(),
+            "Disk full"
+        );
+        assert_eq!(
+            ClientError::GameUpdate(disk_full).user_friendly(),
+            "Disk full"
+        );
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            ClientError::Io("Directory not empty (os error 39)".to_string())
+                .user_friendly(),
+            "Could not access the game files"
+        );
+        assert_eq!(
+            ClientError::Network("timed out".to_string()).user_friendly(),
+            "No internet connection"
+        );
+        assert_eq!(
+            ClientError::GameUpdate("status 500".to_string()).user_friendly(),
+            "Server error"
+        );
+        let mismatch = ClientError::Verification {
+            remote: 0xdeadbeef,
+            calculated: 0x1234,
+        };
+        assert_eq!(mismatch.user_friendly(), "Downloaded files are corrupted");
+        assert!(
+            mismatch
+                .to_string()
+                .ends_with("CRC32 deadbeef, the downloaded file has 00001234")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reqwest_errors_by_kind() {
+        // nothing listens on the port once the listener is dropped
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+        let unreachable = reqwest::get(&url).await.unwrap_err();
+        assert!(matches!(
+            ClientError::from(unreachable),
+            ClientError::Network(_)
+        ));
+
+        let invalid = reqwest::get("http://").await.unwrap_err();
+        assert!(matches!(ClientError::from(invalid), ClientError::Http(_)));
+    }
+}
@@ -0,0 +1,316 @@
+//! Fetches and parses the news and community showcase feeds into plain posts,
+//! independent of the GUI. Images are fetched separately, so consumers which don't
+//! show them can skip the downloads.
+//!
+//! Airshipper is only built as a binary, so this is no library API: other crates
+//! can't depend on it. It keeps the feeds usable by the CLI and other frontends of
+//! the launcher without pulling in iced.
+use crate::{Result, net};
+use reqwest::{
+    StatusCode,
+    header::{ETAG, IF_RANGE, LAST_MODIFIED, RANGE},
+};
+use rss::Channel;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Only the latest posts of a feed are kept.
+pub const MAX_POSTS: usize = 15;
+/// appended to the name of an image while it is downloaded, see [`fetch_image`]
+pub const PARTIAL_IMAGE_SUFFIX: &str = ".part";
+/// appended to the name of a partial image for the file with its ETag or
+/// Last-Modified, a download is only continued if the image is still the same
+pub const IMAGE_VALIDATOR_SUFFIX: &str = ".validator";
+
+/// The posts of an RSS feed and its etag, which changes with the feed.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Feed {
+    pub posts: Vec<FeedPost>,
+    pub etag: String,
+}
+
+/// A single post of an RSS feed, its description stripped of HTML and markdown.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeedPost {
+    pub title: String,
+    pub description: String,
+    pub url: String,
+    pub image_url: Option<String>,
+}
+
+/// Downloads and parses the feed at `url`.
+pub async fn fetch_feed(url: &str) -> Result<Feed> {
+    let response = net::query(url).await?;
+    let etag = net::get_etag(&response);
+    let posts = parse_feed(&response.body)?;
+    Ok(Feed { posts, etag })
+}
+
+/// Parses the latest [`MAX_POSTS`] posts of an RSS feed.
+pub fn parse_feed(xml: &[u8]) -> Result<Vec<FeedPost>> {
+    let channel = Channel::read_from(xml)?;
+    Ok(channel
+        .items()
+        .iter()
+        .take(MAX_POSTS)
+        .map(FeedPost::from)
+        .collect())
+}
+
+/// Downloads and decodes the image of a post, see [`FeedPost::image_url`]. The bytes
+/// are stored in `partial` while they arrive, so an interrupted download continues
+/// where it stopped if the image didn't change, see [`IMAGE_VALIDATOR_SUFFIX`]. If the
+/// continued image can't be decoded anyway, it is downloaded again from the start.
+pub async fn fetch_image(url: &str, partial: &Path) -> Result<image::DynamicImage> {
+    let _permits = net::image_fetch_permit().await;
+    let resumed = download_image(url, partial, true).await?;
+    let image = match image::load_from_memory(&resumed.bytes) {
+        Err(e) if resumed.resumed => {
+            tracing::debug!(?e, url, "Continued image is invalid, downloading it again");
+            let bytes = download_image(url, partial, false).await?.bytes;
+            image::load_from_memory(&bytes)
+        },
+        image => image,
+    };
+    let _ = tokio::fs::remove_file(partial).await;
+    let _ = tokio::fs::remove_file(validator_path(partial)).await;
+    Ok(image?)
+}
+
+fn validator_path(partial: &Path) -> PathBuf {
+    let mut name = partial.as_os_str().to_owned();
+    name.push(IMAGE_VALIDATOR_SUFFIX);
+    PathBuf::from(name)
+}
+
+struct DownloadedImage {
+    bytes: Vec<u8>,
+    /// whether the bytes of an earlier download were continued
+    resumed: bool,
+}
+
+async fn download_image(
+    url: &str,
+    partial: &Path,
+    resume: bool,
+) -> Result<DownloadedImage> {
+    let validator_path = validator_path(partial);
+    // without a validator the image might have changed, so it isn't continued
+    let validator = match resume {
+        true => tokio::fs::read_to_string(&validator_path).await.ok(),
+        false => None,
+    };
+    let mut bytes = match validator {
+        Some(_) => tokio::fs::read(partial).await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let mut request = net::WEB_CLIENT.get(url);
+    if let Some(validator) = validator.filter(|_| !bytes.is_empty()) {
+        // the server sends the whole image instead if it changed
+        request = request
+            .header(RANGE, format!("bytes={}-", bytes.len()))
+            .header(IF_RANGE, validator);
+    }
+    let mut response = request.send().await?;
+    // the earlier download already got every byte
+    if !bytes.is_empty() && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(DownloadedImage {
+            bytes,
+            resumed: true,
+        });
+    }
+    response = response.error_for_status()?;
+    // servers which ignore the range send the whole image
+    let resumed = !bytes.is_empty() && response.status() == StatusCode::PARTIAL_CONTENT;
+    if !resumed {
+        bytes.clear();
+        // weak ETags can't be used in If-Range
+        let validator = [ETAG, LAST_MODIFIED].into_iter().find_map(|header| {
+            let value = response.headers().get(header)?.to_str().ok()?;
+            (!value.starts_with("W/")).then(|| value.to_string())
+        });
+        match validator {
+            Some(validator) => tokio::fs::write(&validator_path, validator).await?,
+            None => {
+                let _ = tokio::fs::remove_file(&validator_path).await;
+            },
+        }
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(partial)
+        .await?;
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+        bytes.extend_from_slice(&chunk);
+    }
+    file.flush().await?;
+    Ok(DownloadedImage { bytes, resumed })
+}
+
+impl FeedPost {
+    fn process_description(desc: Option<&str>) -> String {
+        match desc {
+            Some(desc) => {
+                let wrapped_html = html2text::from_read(desc.as_bytes(), 400);
+                if let Ok(html) = wrapped_html {
+                    let stripped_html = html
+                        .lines()
+                        .take(3)
+                        .filter(|x| !x.contains("[banner]"))
+                        .fold(String::new(), |mut output, b| {
+                            use std::fmt::Write;
+                            let _ = writeln!(output, "{b}");
+                            output
+                        });
+                    strip_markdown::strip_markdown(&stripped_html)
+                } else {
+                    "HTML parsing failed.".into()
+                }
+            },
+            None => "No description found.".into(),
+        }
+    }
+}
+
+impl From<&rss::Item> for FeedPost {
+    fn from(item: &rss::Item) -> Self {
+        FeedPost {
+            title: item.title().unwrap_or("Missing title").into(),
+            description: Self::process_description(item.description()),
+            url: item.link().unwrap_or("https://veloren.net").into(),
+            // the attached media of the item is shown as the image of the post
+            image_url: item
+                .enclosure()
+                .map(|enclosure| enclosure.url().to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::test_server::{Response, serve};
+
+    #[test]
+    fn test_parse_feed() {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+<channel>
+<title>Veloren Blog</title>
+<link>https://veloren.net</link>
+<description>Veloren news</description>
+<item>
+<title>This Week In Veloren 200</title>
+<link>https://veloren.net/devblog-200/</link>
+<description>&lt;p&gt;A &lt;strong&gt;big&lt;/strong&gt; week.&lt;/p&gt;</description>
+<enclosure url="https://veloren.net/devblog-200/banner.png" length="0" type="image/png"/>
+</item>
+<item>
+<description>No title</description>
+</item>
+"#,
+        );
+        for i in 0..20 {
+            xml.push_str(&format!("<item><title>Post {i}</title></item>\n"));
+        }
+        xml.push_str("</channel>\n</rss>");
+
+        let posts = parse_feed(xml.as_bytes()).unwrap();
+        assert_eq!(posts.len(), MAX_POSTS);
+        assert_eq!(posts[0].title, "This Week In Veloren 200");
+        assert_eq!(posts[0].description.trim(), "A big week.");
+        assert_eq!(posts[0].url, "https://veloren.net/devblog-200/");
+        assert_eq!(
+            posts[0].image_url.as_deref(),
+            Some("https://veloren.net/devblog-200/banner.png")
+        );
+        assert_eq!(posts[1].title, "Missing title");
+        assert_eq!(posts[1].url, "https://veloren.net");
+        assert_eq!(posts[1].image_url, None);
+        assert_eq!(posts[2].description, "No description found.");
+
+        assert!(parse_feed(b"<html></html>").is_err());
+    }
+
+    /// Serves `image` with the ETag `"v1"`, only the requested range if there is one
+    /// and the If-Range matches.
+    async fn image_server(listener: tokio::net::TcpListener, image: Vec<u8>) {
+        serve(listener, |request| {
+            let unchanged = request
+                .lines()
+                .find_map(|line| line.strip_prefix("if-range: "))
+                .is_none_or(|etag| etag.trim() == "\"v1\"");
+            let start = request
+                .lines()
+                .find_map(|line| line.strip_prefix("range: bytes="))
+                .and_then(|range| range.trim().trim_end_matches('-').parse().ok())
+                .filter(|_| unchanged);
+            let headers = "ETag: \"v1\"\r\n".to_owned();
+            match start {
+                Some(start) => Response {
+                    status: "206 Partial Content",
+                    headers,
+                    body: image[start..].to_vec(),
+                },
+                None => Response {
+                    headers,
+                    ..Response::ok(image.clone())
+                },
+            }
+        })
+        .await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_resume_image() {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::from_pixel(64, 64, image::Rgba([255, 0, 0, 255]))
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        let png = png.into_inner();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/banner.png", listener.local_addr().unwrap());
+        let server = tokio::spawn(image_server(listener, png.clone()));
+        let partial = std::env::temp_dir()
+            .join(format!("airshipper-image-{}.png.part", std::process::id()));
+
+        let validator = validator_path(&partial);
+
+        // a truncated download is only continued if the image is still the same
+        let truncated = &png[..png.len() / 2];
+        std::fs::write(&partial, truncated).unwrap();
+        let restarted = download_image(&url, &partial, true).await.unwrap();
+        assert!(!restarted.resumed);
+        assert_eq!(restarted.bytes, png);
+        assert_eq!(std::fs::read_to_string(&validator).unwrap(), "\"v1\"");
+        std::fs::write(&partial, truncated).unwrap();
+        let resumed = download_image(&url, &partial, true).await.unwrap();
+        assert!(resumed.resumed);
+        assert_eq!(resumed.bytes, png);
+        assert_eq!(std::fs::read(&partial).unwrap(), png);
+        std::fs::write(&partial, truncated).unwrap();
+        std::fs::write(&validator, "\"v0\"").unwrap();
+        let changed = download_image(&url, &partial, true).await.unwrap();
+        assert!(!changed.resumed);
+        assert_eq!(changed.bytes, png);
+
+        let image = fetch_image(&url, &partial).await.unwrap();
+        assert_eq!(image.width(), 64);
+        assert!(!partial.exists());
+        assert!(!validator.exists());
+
+        // bytes which don't belong to the image are discarded
+        std::fs::write(&partial, b"not an image").unwrap();
+        let image = fetch_image(&url, &partial).await.unwrap();
+        assert_eq!(image.height(), 64);
+        assert!(!partial.exists());
+        server.abort();
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     Result,
     assets::{POPPINS_MEDIUM_FONT, UP_RIGHT_ARROW_ICON},
-    consts::{AIRSHIPPER_RELEASE_URL, SUPPORTED_SERVER_API_VERSION},
+    consts::AIRSHIPPER_RELEASE_URL,
     gui::{
         style::{button::ButtonStyle, container::ContainerStyle, text::TextStyle},
         views::default::{DefaultViewMessage, Interaction},
@@ -9,17 +9,24 @@ use crate::{
     },
     net,
 };
+use chrono::{DateTime, Utc};
 use iced::{
     Alignment, Command, Length,
     alignment::Vertical,
     widget::{button, column, container, image, image::Handle, row, text},
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use tracing::debug;
 
+/// how often the maintenance countdown is refreshed, it shows whole minutes
+pub const MAINTENANCE_COUNTDOWN_REFRESH: Duration = Duration::from_secs(15);
+
 #[derive(Clone, Debug)]
 pub enum AnnouncementPanelMessage {
     FetchAnnouncement(Result<AnnouncementPanelComponent>),
+    /// refreshes the maintenance countdown
+    Tick,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +34,64 @@ pub struct AnnouncementPanelComponent {
     pub announcement_message: Option<String>,
     pub announcement_last_change: chrono::DateTime<chrono::Utc>,
     pub api_version: Option<u32>,
+    pub maintenance: Option<Maintenance>,
+}
+
+/// The announcement as served by the download server
+#[derive(Deserialize)]
+struct Announcement {
+    message: Option<String>,
+    last_change: chrono::DateTime<chrono::Utc>,
+    /// only sent by servers which announce a maintenance
+    #[serde(default)]
+    maintenance: Option<Maintenance>,
+}
+
+/// A downtime announced by the server operators, shown as a banner with a countdown
+/// until it starts and ends.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Maintenance {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// whether the launch button is disabled during the maintenance, the game can
+    /// still be launched anyway, e.g. to play offline
+    #[serde(default)]
+    pub disable_launch: bool,
+}
+
+impl Maintenance {
+    /// Text of the banner at `now`, `None` once the maintenance is over.
+    fn banner_text(&self, now: DateTime<Utc>) -> Option<String> {
+        let text = if now < self.start {
+            format!("Maintenance starts in {}", countdown(self.start - now))
+        } else if now < self.end {
+            format!(
+                "Maintenance in progress, ends in {}",
+                countdown(self.end - now)
+            )
+        } else {
+            return None;
+        };
+        Some(match &self.message {
+            Some(message) => format!("{text}: {message}"),
+            None => text,
+        })
+    }
+}
+
+/// e.g. `2d 3h`, `1h 05m` or `12m`, rounded up to whole minutes
+fn countdown(duration: chrono::Duration) -> String {
+    let minutes = (duration.num_seconds() + 59) / 60;
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else {
+        format!("{minutes}m")
+    }
 }
 
 impl AnnouncementPanelComponent {
@@ -39,24 +104,34 @@ impl AnnouncementPanelComponent {
             version: u32,
         }
 
-        #[derive(Deserialize)]
-        pub struct Announcement {
-            message: Option<String>,
-            last_change: chrono::DateTime<chrono::Utc>,
-        }
-
         debug!("Announcement fetching...");
 
-        let version = net::query(api_version_url).await?.json::<Version>().await?;
-        let announcement = net::query(announcement_url)
-            .await?
-            .json::<Announcement>()
-            .await?;
+        let version = net::query(api_version_url).await?.json::<Version>()?;
+        let announcement = net::query(announcement_url).await?.json::<Announcement>()?;
 
         Ok(AnnouncementPanelComponent {
             announcement_message: announcement.message,
             announcement_last_change: announcement.last_change,
             api_version: Some(version.version),
+            maintenance: announcement.maintenance,
+        })
+    }
+
+    /// Whether a maintenance is announced which hasn't ended yet, its countdown has to
+    /// be refreshed.
+    pub fn maintenance_pending(&self) -> bool {
+        self.maintenance
+            .as_ref()
+            .is_some_and(|maintenance| Utc::now() < maintenance.end)
+    }
+
+    /// Whether the launch button is disabled by an ongoing maintenance.
+    pub fn launch_disabled(&self) -> bool {
+        let now = Utc::now();
+        self.maintenance.as_ref().is_some_and(|maintenance| {
+            maintenance.disable_launch
+                && maintenance.start <= now
+                && now < maintenance.end
         })
     }
 
@@ -75,17 +150,52 @@ impl AnnouncementPanelComponent {
                     None
                 },
             },
+            // the view reads the time itself
+            AnnouncementPanelMessage::Tick => None,
         }
     }
 
+    fn maintenance_banner(&self) -> Option<Element<'_, DefaultViewMessage>> {
+        let maintenance = self.maintenance.as_ref()?;
+        let banner_text = maintenance.banner_text(Utc::now())?;
+        let start: chrono::DateTime<chrono::Local> = maintenance.start.into();
+        let end: chrono::DateTime<chrono::Local> = maintenance.end.into();
+        let window = format!(
+            "From {} to {}",
+            start.format("%Y-%m-%d %H:%M"),
+            end.format("%Y-%m-%d %H:%M")
+        );
+
+        Some(
+            container(
+                column![
+                    text(banner_text)
+                        .size(14)
+                        .style(TextStyle::TomatoRed)
+                        .font(POPPINS_MEDIUM_FONT),
+                    text(window).size(12).style(TextStyle::LightGrey),
+                ]
+                .spacing(2),
+            )
+            .width(Length::Fill)
+            .padding([8, 16])
+            .style(ContainerStyle::Maintenance)
+            .into(),
+        )
+    }
+
     pub fn view(&self) -> Element<'_, DefaultViewMessage> {
-        let update = match self.api_version {
-            Some(version) => SUPPORTED_SERVER_API_VERSION != version,
-            None => false,
+        let update = match (self.api_version, net::supported_server_api_version()) {
+            (Some(version), Some(supported)) => supported != version,
+            _ => false,
         };
+        let mut col = column![];
+        if let Some(banner) = self.maintenance_banner() {
+            col = col.push(banner);
+        }
         let rowtext = match (update, &self.announcement_message) {
             (false, None) => {
-                return row![].into();
+                return col.into();
             },
             (true, None) => {
                 "Airshipper is outdated, please update to the latest release!".to_string()
@@ -142,7 +252,7 @@ impl AnnouncementPanelComponent {
         ]]
         .height(Length::Fixed(50.0));
 
-        let col = column![].push(
+        col = col.push(
             container(top_row)
                 .width(Length::Fill)
                 .style(ContainerStyle::Announcement),
@@ -152,3 +262,52 @@ impl AnnouncementPanelComponent {
         announcement_container.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maintenance() {
+        let announcement: Announcement = serde_json::from_str(
+            r#"{"message":null,"last_change":"2026-10-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(announcement.maintenance, None);
+
+        let announcement: Announcement = serde_json::from_str(
+            r#"{"message": "Update", "last_change": "2026-10-01T00:00:00Z",
+            "maintenance": {
+                "start": "2026-10-01T12:00:00Z",
+                "end": "2026-10-01T14:00:00Z"
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(announcement.message.as_deref(), Some("Update"));
+        let maintenance = announcement.maintenance.unwrap();
+        assert!(!maintenance.disable_launch);
+
+        let at = |time: &str| -> DateTime<Utc> {
+            format!("2026-10-01T{time}Z").parse().unwrap()
+        };
+        assert_eq!(
+            maintenance.banner_text(at("09:30:00")).unwrap(),
+            "Maintenance starts in 2h 30m"
+        );
+        assert_eq!(
+            maintenance.banner_text(at("13:59:30")).unwrap(),
+            "Maintenance in progress, ends in 1m"
+        );
+        assert_eq!(maintenance.banner_text(at("14:00:00")), None);
+
+        let maintenance = Maintenance {
+            message: Some("Moving to a new host".to_string()),
+            ..maintenance
+        };
+        assert_eq!(
+            maintenance.banner_text(at("12:00:00")).unwrap(),
+            "Maintenance in progress, ends in 2h 00m: Moving to a new host"
+        );
+        assert_eq!(countdown(chrono::Duration::hours(50)), "2d 2h");
+    }
+}
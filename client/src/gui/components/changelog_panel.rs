@@ -8,6 +8,7 @@ use crate::{
     consts,
     consts::GITLAB_MERGED_MR_URL,
     gui::{
+        components::{CACHE_FAILED_TOAST, FETCH_FAILED_TOAST, ToastSeverity, toast},
         style::{
             button::{BrowserButtonStyle, ButtonStyle},
             container::ContainerStyle,
@@ -27,10 +28,6 @@ use iced::{
     },
 };
 use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
-use ron::{
-    de::from_str,
-    ser::{PrettyConfig, to_string_pretty},
-};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
@@ -39,7 +36,9 @@ pub enum ChangelogPanelMessage {
     ScrollPositionChanged(f32),
     LoadChangelog(Result<ChangelogPanelComponent>, Channel),
     UpdateChangelog(Result<Option<ChangelogPanelComponent>>),
-    SaveChangelog,
+    SaveChangelog(Result<()>),
+    /// fetches the changelog again if its etag changed
+    Refresh(Channel),
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +49,9 @@ pub struct ChangelogPanelComponent {
     pub etag: String,
     #[serde(skip, default = "default_display_count")]
     pub display_count: usize,
+    /// the changelog could not be refreshed, the cached one is shown instead
+    #[serde(skip)]
+    pub offline: bool,
 }
 
 pub fn default_display_count() -> usize {
@@ -58,14 +60,14 @@ pub fn default_display_count() -> usize {
 
 impl ChangelogPanelComponent {
     #[allow(clippy::while_let_on_iterator)]
-    async fn fetch(channel: Channel) -> Result<Option<Self>> {
+    pub(crate) async fn fetch(channel: Channel) -> Result<Option<Self>> {
         let mut versions: Vec<ChangelogVersion> = Vec::new();
 
         let changelog =
             net::query(consts::CHANGELOG_URL.replace("{tag}", &channel.0)).await?;
         let etag = net::get_etag(&changelog);
 
-        let changelog_text = changelog.text().await?;
+        let changelog_text = changelog.text();
         let options = Options::empty();
         let mut parser = Parser::new_ext(changelog_text.as_str(), options).peekable();
 
@@ -213,6 +215,7 @@ impl ChangelogPanelComponent {
             etag,
             versions,
             display_count: 2,
+            offline: false,
         }))
     }
 
@@ -245,20 +248,14 @@ impl ChangelogPanelComponent {
     }
 
     pub async fn load_changelog() -> Result<Self> {
-        Ok(from_str(
-            &tokio::fs::read_to_string(&Self::cache_file()).await?,
-        )?)
+        let path = Self::cache_file();
+        crate::fs::read_ron_async(&path).await
     }
 
-    async fn save_changelog(self) {
-        match to_string_pretty(&self, PrettyConfig::default()) {
-            Ok(ron_string) => {
-                if let Err(e) = tokio::fs::write(Self::cache_file(), ron_string).await {
-                    tracing::warn!(?e, "Could not cache changelog");
-                };
-            },
-            Err(e) => tracing::warn!(?e, "Could not serialize changelog for caching"),
-        }
+    async fn save_changelog(self) -> Result<()> {
+        let data = crate::fs::encode_stored(&self, crate::fs::compress_stored_files())?;
+        tokio::fs::write(Self::cache_file(), data).await?;
+        Ok(())
     }
 
     pub fn update(
@@ -287,22 +284,44 @@ impl ChangelogPanelComponent {
                     }))
                 },
             },
+            ChangelogPanelMessage::Refresh(channel) => Some(Command::perform(
+                Self::update_changelog(self.etag.clone(), channel),
+                |update| {
+                    DefaultViewMessage::ChangelogPanel(
+                        ChangelogPanelMessage::UpdateChangelog(update),
+                    )
+                },
+            )),
             ChangelogPanelMessage::UpdateChangelog(result) => match result {
                 Ok(Some(changelog)) => {
+                    // keep the versions which were scrolled into view on a refresh
+                    let display_count = self.display_count;
                     *self = changelog;
-                    Some(Command::perform(Self::save_changelog(self.clone()), |_| {
-                        DefaultViewMessage::ChangelogPanel(
-                            ChangelogPanelMessage::SaveChangelog,
-                        )
-                    }))
+                    self.display_count = display_count.max(self.display_count);
+                    Some(Command::perform(
+                        Self::save_changelog(self.clone()),
+                        |result| {
+                            DefaultViewMessage::ChangelogPanel(
+                                ChangelogPanelMessage::SaveChangelog(result),
+                            )
+                        },
+                    ))
+                },
+                Ok(None) => {
+                    self.offline = false;
+                    None
                 },
-                Ok(None) => None,
                 Err(e) => {
                     tracing::trace!("Failed to update changelog: {}", e);
-                    None
+                    self.offline = true;
+                    Some(toast(ToastSeverity::Warning, FETCH_FAILED_TOAST))
                 },
             },
-            ChangelogPanelMessage::SaveChangelog => None,
+            ChangelogPanelMessage::SaveChangelog(Ok(())) => None,
+            ChangelogPanelMessage::SaveChangelog(Err(e)) => {
+                tracing::warn!(?e, "Could not cache changelog");
+                Some(toast(ToastSeverity::Warning, CACHE_FAILED_TOAST))
+            },
             ChangelogPanelMessage::ScrollPositionChanged(pos) => {
                 if pos > 0.9 && self.display_count < self.versions.len() {
                     self.display_count += 1;
@@ -330,10 +349,14 @@ impl ChangelogPanelComponent {
                 )
                 .push(
                     container(
-                        text("Latest Patch Notes")
-                            .style(TextStyle::Dark)
-                            .size(14)
-                            .font(POPPINS_MEDIUM_FONT),
+                        text(if self.offline {
+                            "Latest Patch Notes (offline)"
+                        } else {
+                            "Latest Patch Notes"
+                        })
+                        .style(TextStyle::Dark)
+                        .size(14)
+                        .font(POPPINS_MEDIUM_FONT),
                     )
                     .padding([3, 0, 0, 0])
                     .width(Length::Fill)
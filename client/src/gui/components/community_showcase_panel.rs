@@ -6,7 +6,7 @@ use crate::{
             RssFeedComponent, RssFeedComponentMessage, RssFeedData, RssFeedUpdateStatus,
             RssPost,
         },
-        style::{button::ButtonStyle, container::ContainerStyle},
+        style::{button::ButtonStyle, container::ContainerStyle, text::TextStyle},
         views::default::{DefaultViewMessage, Interaction},
         widget::*,
     },
@@ -27,6 +27,8 @@ pub struct CommunityShowcaseComponent {
     posts: Vec<CommunityPost>,
     etag: String,
     offset: usize,
+    #[serde(skip)]
+    offline: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -56,6 +58,10 @@ impl RssFeedComponent for CommunityShowcaseComponent {
         self.etag = rss_feed.etag;
     }
 
+    fn etag(&self) -> &str {
+        &self.etag
+    }
+
     fn posts(&self) -> Vec<RssPost> {
         self.posts.iter().map(|x| x.rss_post.clone()).collect()
     }
@@ -75,6 +81,10 @@ impl RssFeedComponent for CommunityShowcaseComponent {
         // see different posts even if they never click the next/prev buttons.
         self.posts.shuffle(&mut rng());
     }
+
+    fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
 }
 
 impl CommunityShowcaseComponent {
@@ -146,10 +156,19 @@ impl CommunityShowcaseComponent {
                 .push(next_button)
         };
 
+        let mut content = column![];
+        if self.offline {
+            content = content.push(
+                text("Offline, showing cached posts")
+                    .size(10)
+                    .style(TextStyle::LightGrey),
+            );
+        }
+
         column![]
             .push(heading_with_rule("Community Showcase"))
             .push(
-                container(column![].push(current_post).push(button_row))
+                container(content.push(current_post).push(button_row))
                     .width(Length::Fill)
                     .padding([10, 20]),
             )
@@ -186,7 +205,7 @@ impl CommunityPost {
                 .gap(5),
             )
         } else {
-            container(text("Loading..."))
+            container(text(post.image_placeholder()))
                 .align_x(Horizontal::Center)
                 .align_y(Vertical::Center)
                 .style(ContainerStyle::LoadingBlogPost)
@@ -1,4 +1,5 @@
 use crate::{
+    ClientError,
     assets::{DOWNLOAD_ICON, POPPINS_BOLD_FONT, POPPINS_MEDIUM_FONT, SETTINGS_ICON},
     gui::{
         custom_widgets::heading_with_rule,
@@ -17,9 +18,9 @@ use crate::{
         widget::*,
     },
     io::ProcessUpdate,
-    logger::{pretty_bytes, redirect_voxygen_log},
-    profiles::Profile,
-    update::{Progress, State},
+    logger::{pretty_bytes, pretty_time, redirect_voxygen_log},
+    profiles::{CrashGuard, Profile, ProgressMetric},
+    update::{GUI_STEP_DELAY, Progress, State},
 };
 use iced::{
     Alignment, Command, Length,
@@ -30,7 +31,11 @@ use iced::{
     },
 };
 use std::{
-    sync::Arc,
+    collections::VecDeque,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
     time::{Duration, Instant},
 };
 use tokio::sync::Mutex;
@@ -38,13 +43,22 @@ use tokio::sync::Mutex;
 use crate::gui::style::container::ContainerStyle;
 use tracing::debug;
 
+/// how many lines of game output are kept to be shown when the game keeps crashing
+const CRASH_LOG_LINES: usize = 10;
+
 #[derive(Debug, Clone)]
 pub enum GamePanelMessage {
     ProcessUpdate(ProcessUpdate),
-    DownloadProgress(Option<Progress>),
+    /// progress of the update run with the given number, see
+    /// [`GamePanelComponent::update_run`]
+    DownloadProgress(u64, Option<Progress>),
     PlayPressed,
+    SafeModePressed,
     ServerBrowserServerChanged(Option<String>),
     StartUpdate,
+    CopyErrorPressed,
+    SkipVersionPressed,
+    InstallSkippedPressed,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -61,9 +75,11 @@ pub enum GamePanelState {
         btnstate: DownloadButtonState,
     },
     ReadyToPlay,
-    Playing(Profile),
+    /// the profile the game was started with and whether it runs in safe mode
+    Playing(Box<Profile>, bool),
     Offline(bool),
-    Retry,
+    /// contains the error which made the last attempt fail, if any
+    Retry(Option<ClientError>),
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +87,35 @@ pub struct GamePanelComponent {
     state: GamePanelState,
     download_progress: Option<Progress>,
     selected_server_browser_address: Option<String>,
+    /// when the game recently exited with an error, used to detect crash loops
+    recent_crashes: Vec<Instant>,
+    /// the last lines of output of the running game
+    last_log_lines: VecDeque<String>,
+    /// counts the started updates. Starting an update, e.g. after switching the install
+    /// slot or channel, cancels the running one: its progress is ignored, which drops
+    /// its statemachine, and its result is never written into the profile.
+    update_run: u64,
+    steps: UpdateSteps,
+}
+
+/// lets the step of a cancelled update finish before the next update starts, so two
+/// updates never write into the same install at once
+#[derive(Debug, Clone, Default)]
+struct UpdateSteps {
+    /// the latest [`GamePanelComponent::update_run`], steps of older runs stop early
+    latest: Arc<AtomicU64>,
+    /// held while a step runs
+    running: Arc<Mutex<()>>,
+}
+
+impl UpdateSteps {
+    fn start(&self, run: u64) {
+        self.latest.store(run, Ordering::SeqCst);
+    }
+
+    fn cancelled(&self, run: u64) -> bool {
+        self.latest.load(Ordering::SeqCst) != run
+    }
 }
 
 impl std::fmt::Debug for GamePanelState {
@@ -78,9 +123,9 @@ impl std::fmt::Debug for GamePanelState {
         match self {
             GamePanelState::Updating { .. } => write!(f, "GamePanelState::Updating"),
             GamePanelState::ReadyToPlay => write!(f, "GamePanelState::ReadyToPlay"),
-            GamePanelState::Playing(_) => write!(f, "GamePanelState::Playing"),
+            GamePanelState::Playing(..) => write!(f, "GamePanelState::Playing"),
             GamePanelState::Offline(_) => write!(f, "GamePanelState::Offline"),
-            GamePanelState::Retry => write!(f, "GamePanelState::Retry"),
+            GamePanelState::Retry(_) => write!(f, "GamePanelState::Retry"),
         }
     }
 }
@@ -91,6 +136,10 @@ impl Default for GamePanelComponent {
             state: GamePanelState::ReadyToPlay,
             download_progress: None,
             selected_server_browser_address: None,
+            recent_crashes: Vec::new(),
+            last_log_lines: VecDeque::with_capacity(CRASH_LOG_LINES),
+            update_run: 0,
+            steps: UpdateSteps::default(),
         }
     }
 }
@@ -98,16 +147,78 @@ impl Default for GamePanelComponent {
 impl GamePanelComponent {
     pub fn subscription(&self) -> iced::Subscription<GamePanelMessage> {
         match &self.state {
-            GamePanelState::Playing(profile) => subscriptions::process::stream(
-                profile.clone(),
-                self.selected_server_browser_address.clone(),
-            )
-            .map(GamePanelMessage::ProcessUpdate),
+            GamePanelState::Playing(profile, safe_mode) => {
+                subscriptions::process::stream(
+                    Profile::clone(profile),
+                    self.selected_server_browser_address.clone(),
+                    *safe_mode,
+                )
+                .map(GamePanelMessage::ProcessUpdate)
+            },
             _ => iced::Subscription::none(),
         }
     }
 
+    /// address of the server selected in the server browser, if any
+    pub fn selected_server(&self) -> Option<&str> {
+        self.selected_server_browser_address.as_deref()
+    }
+
+    /// whether an update is being downloaded or applied
+    pub fn is_updating(&self) -> bool {
+        matches!(self.state, GamePanelState::Updating {
+            btnstate: DownloadButtonState::InProgress,
+            ..
+        })
+    }
+
+    /// advances the update by a few steps, the state to continue with is put into
+    /// `astate` unless the update finished or was cancelled
+    async fn step(
+        run: u64,
+        state: State,
+        steps: UpdateSteps,
+        astate: Arc<Mutex<Option<State>>>,
+    ) -> Option<Progress> {
+        // wait for the step of a cancelled update to stop writing into the install
+        let _running = steps.running.lock().await;
+        let start_time = Instant::now();
+        let mut last_progress = None;
+        let mut lstate = state;
+        // ICED is really slow, so we have to do multiple steps
+        loop {
+            if steps.cancelled(run) {
+                // dropping the statemachine aborts its download tasks
+                return None;
+            }
+            tokio::time::sleep(GUI_STEP_DELAY).await;
+            match lstate.progress().await {
+                Some((progress, state)) => {
+                    lstate = state;
+                    last_progress = Some(progress);
+                    if matches!(last_progress, Some(Progress::ReadyToSync { .. })) {
+                        // wait for user input!
+                        break;
+                    }
+                },
+                None => {
+                    return last_progress;
+                },
+            }
+            // one step at a time, so every progress event is observable
+            if cfg!(feature = "deterministic")
+                || start_time.elapsed() >= Duration::from_millis(30)
+            {
+                break;
+            }
+        }
+        *astate.lock().await = Some(lstate);
+        last_progress
+    }
+
     fn trigger_next_state(
+        run: u64,
+        steps: UpdateSteps,
         state: State,
         empty_arc_state: Arc<Mutex<Option<State>>>,
         dstate: DownloadButtonState,
@@ -118,35 +229,10 @@ impl GamePanelComponent {
                 btnstate: dstate.clone(),
             }),
             Some(Command::perform(
-                async move {
-                    let start_time = Instant::now();
-                    let mut last_progress = None;
-                    let mut lstate = state;
-                    // ICED is really slow, so we have to do multiple steps
-                    while start_time.elapsed() < Duration::from_millis(30) {
-                        match lstate.progress().await {
-                            Some((progress, state)) => {
-                                lstate = state;
-                                last_progress = Some(progress);
-                                if matches!(
-                                    last_progress,
-                                    Some(Progress::ReadyToSync { .. })
-                                ) {
-                                    // wait for user input!
-                                    break;
-                                }
-                            },
-                            None => {
-                                return last_progress;
-                            },
-                        }
-                    }
-                    *empty_arc_state.lock().await = Some(lstate);
-                    last_progress
-                },
-                |progress| {
+                Self::step(run, state, steps, empty_arc_state),
+                move |progress| {
                     DefaultViewMessage::GamePanel(GamePanelMessage::DownloadProgress(
-                        progress,
+                        run, progress,
                     ))
                 },
             )),
@@ -160,10 +246,14 @@ impl GamePanelComponent {
     ) -> Option<Command<DefaultViewMessage>> {
         let (next_state, command) = match msg {
             GamePanelMessage::PlayPressed => match &self.state {
-                GamePanelState::ReadyToPlay => {
-                    (Some(GamePanelState::Playing(active_profile.clone())), None)
-                },
-                GamePanelState::Retry => (
+                GamePanelState::ReadyToPlay => (
+                    Some(GamePanelState::Playing(
+                        Box::new(active_profile.clone()),
+                        false,
+                    )),
+                    None,
+                ),
+                GamePanelState::Retry(_) => (
                     None,
                     Some(Command::perform(async {}, |_| {
                         DefaultViewMessage::GamePanel(GamePanelMessage::StartUpdate)
@@ -172,9 +262,13 @@ impl GamePanelComponent {
                 GamePanelState::Offline(available) => {
                     match available {
                         // Play offline
-                        true => {
-                            (Some(GamePanelState::Playing(active_profile.clone())), None)
-                        },
+                        true => (
+                            Some(GamePanelState::Playing(
+                                Box::new(active_profile.clone()),
+                                false,
+                            )),
+                            None,
+                        ),
                         // Retry
                         false => {
                             // The game has never been downloaded so the only option is to
@@ -198,6 +292,8 @@ impl GamePanelComponent {
                         l.take().expect("impossible, should always be filled")
                     };
                     Self::trigger_next_state(
+                        self.update_run,
+                        self.steps.clone(),
                         state,
                         astate.clone(),
                         DownloadButtonState::InProgress,
@@ -207,17 +303,67 @@ impl GamePanelComponent {
                     (None, None)
                 },
             },
+            GamePanelMessage::SafeModePressed => match &self.state {
+                GamePanelState::ReadyToPlay | GamePanelState::Offline(true) => (
+                    Some(GamePanelState::Playing(
+                        Box::new(active_profile.clone()),
+                        true,
+                    )),
+                    None,
+                ),
+                _ => (None, None),
+            },
             GamePanelMessage::StartUpdate => {
+                if self.is_updating() {
+                    tracing::info!("Cancelling the running update to start a new one");
+                }
+                self.update_run += 1;
+                self.steps.start(self.update_run);
                 let state = State::ToBeEvaluated(active_profile.clone());
 
                 let astate = Arc::new(Mutex::new(None));
-                Self::trigger_next_state(state, astate, DownloadButtonState::Checking)
+                Self::trigger_next_state(
+                    self.update_run,
+                    self.steps.clone(),
+                    state,
+                    astate,
+                    DownloadButtonState::Checking,
+                )
+            },
+            GamePanelMessage::DownloadProgress(run, _) if run != self.update_run => {
+                tracing::debug!(run, "Dropping the progress of a cancelled update");
+                (None, None)
+            },
+            GamePanelMessage::DownloadProgress(
+                _,
+                Some(Progress::Successful(profile)),
+            ) if !profile.same_install(active_profile) => {
+                // the install was switched without starting a new update
+                tracing::info!(
+                    "The install changed during the update, checking it again"
+                );
+                (
+                    None,
+                    Some(Command::perform(async {}, |_| {
+                        DefaultViewMessage::GamePanel(GamePanelMessage::StartUpdate)
+                    })),
+                )
             },
-            GamePanelMessage::DownloadProgress(progress) => {
+            GamePanelMessage::DownloadProgress(_, progress) => {
+                // the profile of an update waiting for confirmation is only returned
+                // once it finished, so the check is recorded right away
+                let checked = || {
+                    let mut profile = active_profile.clone();
+                    profile.last_checked = Some(chrono::Utc::now());
+                    Command::perform(
+                        async { Action::UpdateProfile(Box::new(profile)) },
+                        DefaultViewMessage::Action,
+                    )
+                };
                 let next = match &progress {
                     Some(Progress::Errored(e)) => {
                         tracing::error!("Download failed with: {e}");
-                        (Some(GamePanelState::Retry), None)
+                        (Some(GamePanelState::Retry(Some(e.clone()))), None)
                     },
                     Some(Progress::Successful(profile)) => {
                         let profile = profile.clone();
@@ -242,6 +388,8 @@ impl GamePanelComponent {
                             };
                             match state {
                                 Some(state) => Self::trigger_next_state(
+                                    self.update_run,
+                                    self.steps.clone(),
                                     state,
                                     astate.clone(),
                                     btnstate.clone(),
@@ -256,8 +404,23 @@ impl GamePanelComponent {
                             (None, None)
                         }
                     },
-                    Some(Progress::ReadyToSync { version }) => {
-                        tracing::debug!(?version, "Need to confirm the update");
+                    Some(Progress::ReadyToSync { version, .. })
+                        if active_profile.installed()
+                            && active_profile.skipped_version.as_ref()
+                                == Some(version) =>
+                    {
+                        tracing::debug!(?version, "Update was skipped");
+                        (Some(GamePanelState::ReadyToPlay), Some(checked()))
+                    },
+                    Some(Progress::ReadyToSync {
+                        version,
+                        download_bytes,
+                    }) => {
+                        tracing::debug!(
+                            ?version,
+                            download_bytes,
+                            "Need to confirm the update"
+                        );
                         (
                             if let GamePanelState::Updating { astate, .. } = &self.state {
                                 Some(GamePanelState::Updating {
@@ -267,7 +430,7 @@ impl GamePanelComponent {
                             } else {
                                 None
                             },
-                            None,
+                            Some(checked()),
                         )
                     },
                     None => (None, None),
@@ -279,30 +442,102 @@ impl GamePanelComponent {
             // voxygen output to Airshipper's log output
             GamePanelMessage::ProcessUpdate(update) => match update {
                 ProcessUpdate::Line(msg) => {
-                    redirect_voxygen_log(&msg);
+                    redirect_voxygen_log(&msg, &active_profile.log_rules);
+                    if self.last_log_lines.len() == CRASH_LOG_LINES {
+                        self.last_log_lines.pop_front();
+                    }
+                    self.last_log_lines.push_back(msg);
                     (None, None)
                 },
                 ProcessUpdate::Exit(code) => {
                     debug!("Veloren exited with {}", code);
-                    (
-                        Some(GamePanelState::Retry),
-                        Some(Command::perform(async {}, |_| {
-                            DefaultViewMessage::GamePanel(GamePanelMessage::StartUpdate)
-                        })),
-                    )
+                    let last_log_lines = std::mem::take(&mut self.last_log_lines);
+                    if !code.success()
+                        && record_crash(
+                            &mut self.recent_crashes,
+                            Instant::now(),
+                            &active_profile.crash_guard,
+                        )
+                    {
+                        tracing::warn!(
+                            "Veloren crashed {} times in a row, not restarting it",
+                            active_profile.crash_guard.max_crashes
+                        );
+                        let last_log_lines = Vec::from(last_log_lines).join("\n");
+                        let err = ClientError::CrashLoop(format!(
+                            "Veloren exited with {code}, last output:\n{last_log_lines}"
+                        ));
+                        (Some(GamePanelState::Retry(Some(err))), None)
+                    } else {
+                        (
+                            Some(GamePanelState::Retry(None)),
+                            Some(Command::perform(async {}, |_| {
+                                DefaultViewMessage::GamePanel(
+                                    GamePanelMessage::StartUpdate,
+                                )
+                            })),
+                        )
+                    }
                 },
                 ProcessUpdate::Error(err) => {
                     tracing::error!(
                         "Failed to receive an update from Veloren process! {}",
                         err
                     );
-                    (Some(GamePanelState::Retry), None)
+                    (
+                        Some(GamePanelState::Retry(Some(ClientError::Io(err)))),
+                        None,
+                    )
                 },
             },
             GamePanelMessage::ServerBrowserServerChanged(server_address) => {
                 self.selected_server_browser_address = server_address;
                 (None, None)
             },
+            GamePanelMessage::CopyErrorPressed => match &self.state {
+                GamePanelState::Retry(Some(e)) => {
+                    (None, Some(iced::clipboard::write(e.to_string())))
+                },
+                _ => (None, None),
+            },
+            GamePanelMessage::SkipVersionPressed => match &self.download_progress {
+                Some(Progress::ReadyToSync { version, .. })
+                    if self.update_skippable(active_profile) =>
+                {
+                    let mut profile = active_profile.clone();
+                    profile.skipped_version = Some(version.clone());
+                    (
+                        Some(GamePanelState::ReadyToPlay),
+                        Some(Command::perform(
+                            async { Action::UpdateProfile(Box::new(profile)) },
+                            DefaultViewMessage::Action,
+                        )),
+                    )
+                },
+                _ => (None, None),
+            },
+            GamePanelMessage::InstallSkippedPressed => {
+                let mut profile = active_profile.clone();
+                profile.skipped_version = None;
+                let state = State::ToBeEvaluated(profile.clone());
+                self.update_run += 1;
+                self.steps.start(self.update_run);
+                let (next_state, update) = Self::trigger_next_state(
+                    self.update_run,
+                    self.steps.clone(),
+                    state,
+                    Arc::new(Mutex::new(None)),
+                    DownloadButtonState::Checking,
+                );
+                let save = Command::perform(
+                    async { Action::UpdateProfile(Box::new(profile)) },
+                    DefaultViewMessage::Action,
+                );
+                (
+                    next_state,
+                    Some(Command::batch(update.into_iter().chain([save]))),
+                )
+            },
         };
 
         if let Some(state) = next_state {
@@ -312,13 +547,117 @@ impl GamePanelComponent {
         command
     }
 
-    pub fn view(&self, active_profile: &Profile) -> Element<'_, DefaultViewMessage> {
+    /// Whether the update waiting for confirmation can be skipped, which requires an
+    /// installed version to play instead
+    fn update_skippable(&self, active_profile: &Profile) -> bool {
+        active_profile.installed()
+            && matches!(
+                &self.state,
+                GamePanelState::Updating { btnstate, .. }
+                    if *btnstate == DownloadButtonState::WaitForConfirm
+            )
+            && matches!(self.download_progress, Some(Progress::ReadyToSync { .. }))
+    }
+
+    /// Size of the update waiting for confirmation if it exceeds the large update
+    /// threshold of the profile
+    fn large_update(&self, active_profile: &Profile) -> Option<u64> {
+        match (&self.state, &self.download_progress) {
+            (
+                GamePanelState::Updating {
+                    btnstate: DownloadButtonState::WaitForConfirm,
+                    ..
+                },
+                Some(Progress::ReadyToSync { download_bytes, .. }),
+            ) if active_profile.is_large_update(*download_bytes) => Some(*download_bytes),
+            _ => None,
+        }
+    }
+
+    /// Overall progress of a running update, e.g. `Downloading 43%`
+    pub fn update_progress(&self) -> Option<String> {
+        let GamePanelState::Updating {
+            btnstate: DownloadButtonState::InProgress,
+            ..
+        } = &self.state
+        else {
+            return None;
+        };
+        let Some(Progress::Incomplete {
+            download,
+            unzip,
+            delete,
+            ..
+        }) = &self.download_progress
+        else {
+            return None;
+        };
+
+        let steps = [download, unzip, delete];
+        let total: u64 = steps.iter().map(|p| p.total_bytes()).sum();
+        let processed: u64 = steps.iter().map(|p| p.processed_bytes()).sum();
+        let step = if download.is_finished() {
+            "Installing"
+        } else {
+            "Downloading"
+        };
+        Some(format!(
+            "{step} {}%",
+            (processed * 100).checked_div(total).unwrap_or(100)
+        ))
+    }
+
+    /// Short description of what the launcher is doing, e.g. for the tray icon
+    #[cfg(feature = "tray")]
+    pub fn status(&self) -> String {
+        match &self.state {
+            GamePanelState::Updating { btnstate, .. }
+                if *btnstate == DownloadButtonState::WaitForConfirm =>
+            {
+                "Update available".to_string()
+            },
+            GamePanelState::Updating { .. } => match &self.download_progress {
+                Some(Progress::Incomplete {
+                    download, unzip, ..
+                }) => match download.is_finished() {
+                    false => format!(
+                        "Downloading ({}%)",
+                        crate::update::percent_complete(download)
+                    ),
+                    true => format!(
+                        "Installing ({}%)",
+                        crate::update::percent_complete(unzip)
+                    ),
+                },
+                _ => "Checking for updates".to_string(),
+            },
+            GamePanelState::ReadyToPlay => "Ready to play".to_string(),
+            GamePanelState::Playing(..) => "Playing".to_string(),
+            GamePanelState::Offline(_) => "Offline".to_string(),
+            GamePanelState::Retry(Some(e)) => e.user_friendly().to_string(),
+            GamePanelState::Retry(None) => "Checking for updates".to_string(),
+        }
+    }
+
+    /// `maintenance` disables the launch button, see [`AnnouncementPanelComponent`]
+    ///
+    /// [`AnnouncementPanelComponent`]: crate::gui::components::AnnouncementPanelComponent
+    pub fn view(
+        &self,
+        active_profile: &Profile,
+        maintenance: bool,
+    ) -> Element<'_, DefaultViewMessage> {
         // TODO: Improve this with actual game version / date (requires changes to
         // Airshipper Server)
         let mut version_string = "Pre-Alpha".to_owned();
         if let Some(version) = &active_profile.version {
             version_string.push_str(format!(" ({})", &version[..7]).as_str())
         }
+        let version_dates = format!(
+            "Last checked for updates: {}\nLast updated: {}",
+            pretty_time(active_profile.last_checked.as_ref()),
+            pretty_time(active_profile.last_updated.as_ref()),
+        );
 
         column![]
             .push(heading_with_rule::<DefaultViewMessage>("Game Version"))
@@ -328,7 +667,15 @@ impl GamePanelComponent {
                         .height(Length::Fixed(30.0))
                         .push(
                             container(
-                                text(version_string).size(12).style(TextStyle::LightGrey),
+                                tooltip(
+                                    text(version_string)
+                                        .size(12)
+                                        .style(TextStyle::LightGrey),
+                                    text(version_dates).size(14),
+                                    Position::Bottom,
+                                )
+                                .style(ContainerStyle::Tooltip)
+                                .gap(5),
                             )
                             .align_y(Vertical::Bottom)
                             .width(Length::Fill)
@@ -356,7 +703,7 @@ impl GamePanelComponent {
                 .padding([0, 20]),
             )
             .push(
-                container(self.download_area())
+                container(self.download_area(active_profile, maintenance))
                     .width(Length::Fill)
                     .padding([10, 20, 20, 20]),
             )
@@ -370,9 +717,9 @@ impl GamePanelComponent {
         let same = match &self.state {
             Updating { .. } => matches!(state, Updating { .. }),
             ReadyToPlay => matches!(state, ReadyToPlay),
-            Playing(_) => matches!(state, Playing(_)),
+            Playing(..) => matches!(state, Playing(..)),
             Offline(_) => matches!(state, Offline(_)),
-            Retry => matches!(state, Retry),
+            Retry(_) => matches!(state, Retry(_)),
         };
         if !same {
             debug!("GamePanel state: {:?} -> {:?}", self.state, state);
@@ -380,50 +727,83 @@ impl GamePanelComponent {
         self.state = state;
     }
 
-    fn download_area(&self) -> Element<'_, DefaultViewMessage> {
+    fn download_area(
+        &self,
+        active_profile: &Profile,
+        maintenance: bool,
+    ) -> Element<'_, DefaultViewMessage> {
         match &self.state {
             GamePanelState::Updating { btnstate, .. }
                 if *btnstate == DownloadButtonState::InProgress =>
             {
                 // When the game is downloading, the download progress bar and related
                 // stats replace the Launch / Update button
-                let (step, percent, total, downloaded, bytes_per_sec, remaining) =
-                    match &self.download_progress {
-                        Some(Progress::Incomplete {
-                            download,
-                            unzip,
-                            delete,
-                        }) => {
-                            let (step, progress) = match (
-                                download.is_finished(),
-                                unzip.is_finished(),
-                                delete.is_finished(),
-                            ) {
-                                (false, _, _) => ("Downloading", &download),
-                                (true, false, _) => ("Unzipping", &unzip),
-                                (true, true, false) => ("Deleting", &delete),
-                                (true, true, true) => ("Finalizing", &unzip),
-                            };
+                let (step, percent, progress_text, bytes_per_sec, remaining) = match &self
+                    .download_progress
+                {
+                    Some(Progress::Incomplete {
+                        download,
+                        unzip,
+                        delete,
+                        deleted_files,
+                    }) => {
+                        let (step, progress) = match (
+                            download.is_finished(),
+                            unzip.is_finished(),
+                            delete.is_finished(),
+                        ) {
+                            (false, _, _) => ("Downloading", &download),
+                            (true, false, _) => ("Unzipping", &unzip),
+                            (true, true, false) => ("Deleting", &delete),
+                            (true, true, true) => ("Finalizing", &unzip),
+                        };
+                        let percent = match active_profile.progress_metric {
+                            ProgressMetric::Step => {
+                                crate::update::percent_complete(progress)
+                            },
+                            ProgressMetric::Combined => {
+                                crate::update::combined_percent(download, unzip)
+                            },
+                        };
+                        // the deletion counts files, it has no rate in MB/s
+                        let (progress_text, bytes_per_sec) = if step == "Deleting" {
+                            (crate::update::deleted_files_text(delete, *deleted_files), 0)
+                        } else {
                             (
-                                step,
-                                progress.percent_complete() as f32,
-                                progress.total_bytes(),
-                                progress.processed_bytes(),
+                                format!(
+                                    "{} / {}",
+                                    pretty_bytes(progress.processed_bytes()),
+                                    pretty_bytes(progress.total_bytes())
+                                ),
                                 progress.bytes_per_sec(),
-                                progress.time_remaining(),
                             )
-                        },
-                        Some(Progress::Successful(_)) => {
-                            ("Successful", 100.0, 0, 0, 0, Duration::from_secs(0))
-                        },
-                        _ => ("Unknown", 0.0, 0, 0, 0, Duration::from_secs(0)),
-                    };
+                        };
+                        (
+                            step,
+                            percent as f32,
+                            progress_text,
+                            bytes_per_sec,
+                            progress.time_remaining(),
+                        )
+                    },
+                    Some(Progress::Successful(_)) => (
+                        "Successful",
+                        100.0,
+                        format!("{} / {}", pretty_bytes(0), pretty_bytes(0)),
+                        0,
+                        Duration::from_secs(0),
+                    ),
+                    _ => (
+                        "Unknown",
+                        0.0,
+                        format!("{} / {}", pretty_bytes(0), pretty_bytes(0)),
+                        0,
+                        Duration::from_secs(0),
+                    ),
+                };
 
                 let download_rate = bytes_per_sec as f32 / 1_000_000.0;
 
-                let progress_text =
-                    format!("{} / {}", pretty_bytes(downloaded), pretty_bytes(total));
-
                 let mut download_stats_row = row![]
                     .push(Image::new(Handle::from_memory(DOWNLOAD_ICON.to_vec())))
                     .push(
@@ -478,7 +858,18 @@ impl GamePanelComponent {
             _ => {
                 // For all other states, the button is shown with different text/styling
                 // dependant on the state
+                // servers picked in the server browser aren't affected by maintenance
+                let launch_disabled = maintenance
+                    && matches!(self.state, GamePanelState::ReadyToPlay)
+                    && self.selected_server_browser_address.is_none();
                 let (button_text, button_style, enabled) = match &self.state {
+                    GamePanelState::ReadyToPlay if launch_disabled => (
+                        "Maintenance",
+                        ButtonStyle::Download(DownloadButtonStyle::Launch(
+                            ButtonState::Disabled,
+                        )),
+                        false,
+                    ),
                     GamePanelState::ReadyToPlay => (
                         "Launch",
                         ButtonStyle::Download(DownloadButtonStyle::Launch(
@@ -519,14 +910,14 @@ impl GamePanelComponent {
                         ),
                         _ => unreachable!(),
                     },
-                    GamePanelState::Retry => (
+                    GamePanelState::Retry(_) => (
                         "Retry",
                         ButtonStyle::Download(DownloadButtonStyle::Update(
                             ButtonState::Enabled,
                         )),
                         true,
                     ),
-                    GamePanelState::Playing(_) => (
+                    GamePanelState::Playing(..) => (
                         "Playing",
                         ButtonStyle::Download(DownloadButtonStyle::Launch(
                             ButtonState::Disabled,
@@ -607,16 +998,230 @@ impl GamePanelComponent {
                     Interaction::ToggleServerBrowser,
                 ));
 
-                container(
-                    row![]
-                        .push(launch_button)
-                        .push(server_browser_button)
-                        .spacing(10),
-                )
-                .width(Length::Fill)
-                .align_y(Vertical::Center)
-                .into()
+                let buttons = row![]
+                    .push(launch_button)
+                    .push(server_browser_button)
+                    .spacing(10);
+
+                let mut col = column![].push(buttons).spacing(5);
+                if let GamePanelState::Retry(Some(e)) = &self.state {
+                    col = col.push(Self::error_details(e));
+                }
+                if let Some(download_bytes) = self.large_update(active_profile) {
+                    col = col.push(
+                        text(format!(
+                            "Large update: {} will be downloaded, press Download to \
+                             continue",
+                            pretty_bytes(download_bytes)
+                        ))
+                        .size(12)
+                        .style(TextStyle::BrightOrange),
+                    );
+                }
+                let link = |label, msg| {
+                    button(text(label).size(12).style(TextStyle::LightGrey))
+                        .on_press(DefaultViewMessage::GamePanel(msg))
+                        .padding(0)
+                        .style(ButtonStyle::Transparent)
+                };
+                // e.g. to play offline while the servers are down
+                if launch_disabled {
+                    col = col.push(link("Launch anyway", GamePanelMessage::PlayPressed));
+                }
+                if self.update_skippable(active_profile) {
+                    col = col.push(link(
+                        "Skip this version",
+                        GamePanelMessage::SkipVersionPressed,
+                    ));
+                }
+                if matches!(self.state, GamePanelState::ReadyToPlay)
+                    && active_profile.skipped_version.is_some()
+                {
+                    col = col.push(link(
+                        "Install the skipped update",
+                        GamePanelMessage::InstallSkippedPressed,
+                    ));
+                }
+                if matches!(
+                    self.state,
+                    GamePanelState::ReadyToPlay | GamePanelState::Offline(true)
+                ) {
+                    col = col.push(
+                        tooltip(
+                            button(
+                                text("Launch in Safe Mode")
+                                    .size(12)
+                                    .style(TextStyle::LightGrey),
+                            )
+                            .on_press(DefaultViewMessage::GamePanel(
+                                GamePanelMessage::SafeModePressed,
+                            ))
+                            .padding(0)
+                            .style(ButtonStyle::Transparent),
+                            text(
+                                "Starts the game with its default graphics settings, \
+                                 use this if the game crashes on startup",
+                            )
+                            .size(14),
+                            Position::Top,
+                        )
+                        .style(ContainerStyle::Tooltip)
+                        .gap(5),
+                    );
+                }
+
+                container(col)
+                    .width(Length::Fill)
+                    .align_y(Vertical::Center)
+                    .into()
             },
         }
     }
+
+    /// Short reason why the last attempt failed, with the full error in a tooltip
+    fn error_details(error: &ClientError) -> Element<'_, DefaultViewMessage> {
+        row![]
+            .push(
+                tooltip(
+                    text(error.user_friendly())
+                        .size(12)
+                        .style(TextStyle::LightGrey),
+                    text(error.to_string()).size(14),
+                    Position::Top,
+                )
+                .style(ContainerStyle::Tooltip)
+                .gap(5),
+            )
+            .push(
+                button(text("Copy error").size(12))
+                    .on_press(DefaultViewMessage::GamePanel(
+                        GamePanelMessage::CopyErrorPressed,
+                    ))
+                    .padding(0)
+                    .style(ButtonStyle::Transparent),
+            )
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .into()
+    }
+}
+
+/// Remembers a crash at `now` and returns whether the game crashed often enough within
+/// the guard's window to stop restarting it. The history is reset once that happens, so
+/// a manual retry starts from scratch. A `max_crashes` of 0 disables the guard.
+fn record_crash(
+    recent_crashes: &mut Vec<Instant>,
+    now: Instant,
+    guard: &CrashGuard,
+) -> bool {
+    let window = Duration::from_secs(guard.window_secs);
+    recent_crashes.retain(|crash| now.duration_since(*crash) < window);
+    recent_crashes.push(now);
+    if guard.max_crashes == 0 || recent_crashes.len() < guard.max_crashes as usize {
+        return false;
+    }
+    recent_crashes.clear();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_crash() {
+        let guard = CrashGuard {
+            max_crashes: 3,
+            window_secs: 60,
+        };
+        let start = Instant::now();
+        let mut crashes = Vec::new();
+        assert!(!record_crash(&mut crashes, start, &guard));
+        assert!(!record_crash(
+            &mut crashes,
+            start + Duration::from_secs(10),
+            &guard
+        ));
+        // the first crash left the window
+        assert!(!record_crash(
+            &mut crashes,
+            start + Duration::from_secs(65),
+            &guard
+        ));
+        assert!(record_crash(
+            &mut crashes,
+            start + Duration::from_secs(69),
+            &guard
+        ));
+        assert!(crashes.is_empty());
+
+        let disabled = CrashGuard {
+            max_crashes: 0,
+            ..guard
+        };
+        assert!((0..10).all(|_| !record_crash(&mut crashes, start, &disabled)));
+    }
+
+    #[test]
+    fn test_switch_install_during_update() {
+        let mut panel = GamePanelComponent::default();
+        let before = Profile::default();
+        assert!(
+            panel
+                .update(GamePanelMessage::StartUpdate, &before)
+                .is_some()
+        );
+
+        // switching the slot starts an update of the new install
+        let mut after = Profile::default();
+        after.slot = Some("staging".to_owned());
+        assert!(
+            panel
+                .update(GamePanelMessage::StartUpdate, &after)
+                .is_some()
+        );
+
+        // the result of the cancelled update is dropped instead of saved
+        let mut updated = before.clone();
+        updated.version = Some("1.0".to_owned());
+        let stale = GamePanelMessage::DownloadProgress(
+            1,
+            Some(Progress::Successful(Box::new(updated.clone()))),
+        );
+        assert!(panel.update(stale, &after).is_none());
+        assert!(matches!(panel.state, GamePanelState::Updating { .. }));
+
+        // an update of another install is checked again instead of saved
+        let current = GamePanelMessage::DownloadProgress(
+            2,
+            Some(Progress::Successful(Box::new(updated))),
+        );
+        assert!(panel.update(current, &after).is_some());
+        assert!(matches!(panel.state, GamePanelState::Updating { .. }));
+        assert!(panel.download_progress.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cancelled_step_finishes_first() {
+        let steps = UpdateSteps::default();
+        steps.start(1);
+        let running = steps.running.clone().lock_owned().await;
+
+        // the new update waits for the running step
+        steps.start(2);
+        let astate = Arc::new(Mutex::new(None));
+        let step = tokio::spawn(GamePanelComponent::step(
+            1,
+            State::ToBeEvaluated(Profile::default()),
+            steps.clone(),
+            astate.clone(),
+        ));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!step.is_finished());
+
+        // and the step of the cancelled update stops without progressing
+        drop(running);
+        assert!(step.await.unwrap().is_none());
+        assert!(astate.lock().await.is_none());
+    }
 }
@@ -6,8 +6,11 @@ mod logo_panel;
 mod news_panel;
 mod server_browser_panel;
 mod settings_panel;
+mod toast_panel;
 
-pub use announcement_panel::{AnnouncementPanelComponent, AnnouncementPanelMessage};
+pub use announcement_panel::{
+    AnnouncementPanelComponent, AnnouncementPanelMessage, MAINTENANCE_COUNTDOWN_REFRESH,
+};
 pub use changelog_panel::{ChangelogPanelComponent, ChangelogPanelMessage};
 pub use community_showcase_panel::{
     CommunityShowcaseComponent, CommunityShowcasePanelMessage,
@@ -19,3 +22,7 @@ pub use server_browser_panel::{
     SERVER_BROWSER_PING_REFRESH, ServerBrowserPanelComponent, ServerBrowserPanelMessage,
 };
 pub use settings_panel::{SettingsPanelComponent, SettingsPanelMessage};
+pub use toast_panel::{
+    CACHE_FAILED_TOAST, FETCH_FAILED_TOAST, TOAST_EXPIRY_CHECK, ToastPanelComponent,
+    ToastPanelMessage, ToastSeverity, toast,
+};
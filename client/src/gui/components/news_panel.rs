@@ -22,6 +22,8 @@ use serde::{Deserialize, Serialize};
 pub struct NewsPanelComponent {
     posts: Vec<NewsPost>,
     etag: String,
+    #[serde(skip)]
+    offline: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -43,6 +45,10 @@ impl RssFeedComponent for NewsPanelComponent {
         self.etag = rss_feed.etag;
     }
 
+    fn etag(&self) -> &str {
+        &self.etag
+    }
+
     fn posts(&self) -> Vec<RssPost> {
         self.posts.iter().map(|x| x.rss_post.clone()).collect()
     }
@@ -54,6 +60,10 @@ impl RssFeedComponent for NewsPanelComponent {
     fn rss_feed_message(message: RssFeedComponentMessage) -> DefaultViewMessage {
         DefaultViewMessage::NewsPanel(NewsPanelMessage::RssUpdate(message))
     }
+
+    fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
 }
 impl NewsPanelComponent {
     // 16:9 Aspect ratio
@@ -75,6 +85,14 @@ impl NewsPanelComponent {
     pub(crate) fn view(&self) -> Element<'_, DefaultViewMessage> {
         let mut news = column![].spacing(20).padding(20);
 
+        if self.offline {
+            news = news.push(
+                text("Offline, showing cached news")
+                    .size(10)
+                    .style(TextStyle::LightGrey),
+            );
+        }
+
         for post in &self.posts {
             news = news.push(post.view());
         }
@@ -104,7 +122,7 @@ impl NewsPost {
             )
         } else {
             container(
-                text("Loading...")
+                text(post.image_placeholder())
                     .size(14)
                     .horizontal_alignment(Horizontal::Center)
                     .vertical_alignment(Vertical::Center)
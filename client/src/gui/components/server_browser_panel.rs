@@ -14,10 +14,14 @@ use crate::{
             container::ContainerStyle,
             text::TextStyle,
         },
-        views::default::{DefaultViewMessage, Interaction},
+        views::{
+            Action,
+            default::{DefaultViewMessage, Interaction},
+        },
         widget::*,
     },
     net,
+    profiles::Profile,
     server_list::fetch_server_list,
 };
 use consts::OFFICIAL_AUTH_SERVER;
@@ -25,8 +29,8 @@ use iced::{
     Alignment, Command, Length,
     alignment::{Horizontal, Vertical},
     widget::{
-        Image, button, column, container, horizontal_rule, image, image::Handle, row,
-        scrollable, text, tooltip, tooltip::Position,
+        Image, button, column, container, horizontal_rule, image, image::Handle,
+        pick_list, row, scrollable, text, text_input, tooltip, tooltip::Position,
     },
 };
 use std::{borrow::Cow, cmp::min, time::Duration};
@@ -36,6 +40,7 @@ use veloren_query_server::{client::QueryClient, proto::ServerInfo as QueryServer
 use veloren_serverbrowser_api::{FieldContent, GameServer};
 
 pub const SERVER_BROWSER_PING_REFRESH: Duration = Duration::from_secs(20);
+const MAX_RECENT_SERVERS: usize = 5;
 
 #[derive(Clone, Debug)]
 pub struct ServerBrowserEntry {
@@ -82,6 +87,9 @@ pub enum ServerBrowserPanelMessage {
         query_client: SkipDebugClone<Option<QueryClient>>,
     },
     SortServers(ServerSortOrder),
+    ManualAddressChanged(String),
+    ManualAddressSubmitted,
+    RecentServerSelected(String),
 }
 
 #[derive(Debug, Default, Clone)]
@@ -90,6 +98,10 @@ pub struct ServerBrowserPanelComponent {
     selected_index: Option<usize>,
     server_list_fetch_error: bool,
     last_sort_ordering: Option<ServerSortOrder>,
+    /// address of a server which isn't listed, e.g. a LAN server
+    manual_address: String,
+    manual_address_error: Option<&'static str>,
+    manual_address_selected: bool,
 }
 
 impl ServerBrowserPanelComponent {
@@ -117,10 +129,14 @@ impl ServerBrowserPanelComponent {
             selected_index: None,
             last_sort_ordering: None,
             server_list_fetch_error,
+            ..Default::default()
         }))
     }
 
-    pub fn view(&self) -> Element<'_, DefaultViewMessage> {
+    pub fn view<'a>(
+        &'a self,
+        active_profile: &'a Profile,
+    ) -> Element<'a, DefaultViewMessage> {
         let top_row = row![].height(Length::Fixed(50.0)).push(
             column![].push(container(
                 row![]
@@ -569,6 +585,8 @@ impl ServerBrowserPanelComponent {
             )
         }
 
+        col = col.push(self.manual_address_view(active_profile));
+
         let server_browser_container = container(col)
             .height(Length::Fill)
             .width(Length::Fill)
@@ -576,13 +594,96 @@ impl ServerBrowserPanelComponent {
         server_browser_container.into()
     }
 
+    fn manual_address_view<'a>(
+        &'a self,
+        active_profile: &'a Profile,
+    ) -> Element<'a, DefaultViewMessage> {
+        let input = row![]
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .push(
+                text_input("Other server, e.g. 192.168.0.2:14004", &self.manual_address)
+                    .on_input(|address| {
+                        DefaultViewMessage::ServerBrowserPanel(
+                            ServerBrowserPanelMessage::ManualAddressChanged(address),
+                        )
+                    })
+                    .on_submit(DefaultViewMessage::ServerBrowserPanel(
+                        ServerBrowserPanelMessage::ManualAddressSubmitted,
+                    ))
+                    .padding(7)
+                    .size(14),
+            )
+            .push(
+                button(text("Select").size(14))
+                    .on_press(DefaultViewMessage::ServerBrowserPanel(
+                        ServerBrowserPanelMessage::ManualAddressSubmitted,
+                    ))
+                    .style(if self.manual_address_selected {
+                        ButtonStyle::ServerListEntry(ServerListEntryButtonState::Selected)
+                    } else {
+                        ButtonStyle::ServerListEntry(
+                            ServerListEntryButtonState::NotSelected,
+                        )
+                    })
+                    .padding([5, 10]),
+            );
+        let input = if active_profile.recent_servers.is_empty() {
+            input
+        } else {
+            let selected = self
+                .manual_address_selected
+                .then(|| self.manual_address.trim().to_owned())
+                .filter(|address| active_profile.recent_servers.contains(address));
+            input.push(
+                pick_list(&active_profile.recent_servers[..], selected, |address| {
+                    DefaultViewMessage::ServerBrowserPanel(
+                        ServerBrowserPanelMessage::RecentServerSelected(address),
+                    )
+                })
+                .placeholder("Recent servers")
+                .text_size(14)
+                .padding([5, 10]),
+            )
+        };
+        let input = input.push(
+            tooltip(
+                button(text("Copy").size(14))
+                    .on_press(DefaultViewMessage::Interaction(
+                        Interaction::CopyServerAddress,
+                    ))
+                    .style(ButtonStyle::ServerListEntry(
+                        ServerListEntryButtonState::NotSelected,
+                    ))
+                    .padding([5, 10]),
+                text("Copy the address of the selected server").size(14),
+                Position::Bottom,
+            )
+            .style(ContainerStyle::Tooltip)
+            .gap(5),
+        );
+
+        let mut col = column![].spacing(5).push(input);
+        if let Some(error) = self.manual_address_error {
+            col = col.push(text(error).size(12).style(TextStyle::TomatoRed));
+        }
+
+        container(col).padding([8, 12]).width(Length::Fill).into()
+    }
+
     pub fn update(
         &mut self,
         msg: ServerBrowserPanelMessage,
+        active_profile: &Profile,
     ) -> Option<Command<DefaultViewMessage>> {
         match msg {
             ServerBrowserPanelMessage::UpdateServerList(result) => match result {
-                Ok(Some(server_browser)) => {
+                Ok(Some(mut server_browser)) => {
+                    // the manually entered address isn't part of the fetched list
+                    server_browser.manual_address =
+                        std::mem::take(&mut self.manual_address);
+                    server_browser.manual_address_error = self.manual_address_error;
+                    server_browser.manual_address_selected = self.manual_address_selected;
                     *self = server_browser;
                     if !self.servers.is_empty() {
                         // Why is there no simple `Command::message` ??
@@ -681,6 +782,7 @@ impl ServerBrowserPanelComponent {
             )),
             ServerBrowserPanelMessage::SelectServerEntry(index) => {
                 self.selected_index = index;
+                self.manual_address_selected = false;
                 let selected_server = index.and_then(|index| {
                     self.servers
                         .get(index)
@@ -698,7 +800,58 @@ impl ServerBrowserPanelComponent {
                 self.last_sort_ordering = Some(order);
                 None
             },
+            ServerBrowserPanelMessage::ManualAddressChanged(address) => {
+                self.manual_address = address;
+                self.manual_address_error = None;
+                if self.manual_address_selected {
+                    self.manual_address_selected = false;
+                    return Some(Command::perform(async {}, |()| {
+                        DefaultViewMessage::GamePanel(
+                            GamePanelMessage::ServerBrowserServerChanged(None),
+                        )
+                    }));
+                }
+                None
+            },
+            ServerBrowserPanelMessage::RecentServerSelected(address) => {
+                self.manual_address = address;
+                self.select_manual_address(active_profile)
+            },
+            ServerBrowserPanelMessage::ManualAddressSubmitted => {
+                self.select_manual_address(active_profile)
+            },
+        }
+    }
+
+    fn select_manual_address(
+        &mut self,
+        active_profile: &Profile,
+    ) -> Option<Command<DefaultViewMessage>> {
+        let address = self.manual_address.trim().to_owned();
+        if let Err(e) = net::validate_server_address(&address) {
+            self.manual_address_error = Some(e);
+            return None;
         }
+        self.manual_address_error = None;
+        self.manual_address_selected = true;
+        self.selected_index = None;
+
+        let mut profile = active_profile.clone();
+        profile.recent_servers.retain(|recent| recent != &address);
+        profile.recent_servers.insert(0, address.clone());
+        profile.recent_servers.truncate(MAX_RECENT_SERVERS);
+
+        Some(Command::batch(vec![
+            Command::perform(
+                async { Action::UpdateProfile(Box::new(profile)) },
+                DefaultViewMessage::Action,
+            ),
+            Command::perform(async {}, move |()| {
+                DefaultViewMessage::GamePanel(
+                    GamePanelMessage::ServerBrowserServerChanged(Some(address)),
+                )
+            }),
+        ]))
     }
 
     fn sort_servers(&mut self, order: ServerSortOrder) {
@@ -1,9 +1,13 @@
 use crate::{
     Result,
     assets::{BOOK_ICON, FOLDER_ICON},
-    channels::{Channel, Channels},
+    channels::{self, Channel, ChannelCheck, Channels, NIGHTLY_CHANNEL, STABLE_CHANNEL},
+    fs::{self, InstallSize},
     gui::{
-        components::GamePanelMessage,
+        components::{
+            ChangelogPanelComponent, ChangelogPanelMessage, GamePanelMessage,
+            ToastSeverity, toast,
+        },
         custom_widgets::heading_with_rule,
         style::{button::ButtonStyle, container::ContainerStyle, text::TextStyle},
         views::{
@@ -12,6 +16,7 @@ use crate::{
         },
         widget::*,
     },
+    logger::pretty_bytes,
     profiles,
     profiles::Profile,
 };
@@ -23,23 +28,50 @@ use iced::{
         text_input, tooltip, tooltip::Position,
     },
 };
+use std::path::PathBuf;
 use tracing::debug;
 
 #[derive(Clone, Debug)]
 pub enum SettingsPanelMessage {
     LogLevelChanged(profiles::LogLevel),
+    ThemeChanged(profiles::UiTheme),
+    UiScaleChanged(profiles::UiScale),
+    ProgressMetricChanged(profiles::ProgressMetric),
     ServerChanged(profiles::Server),
     ChannelChanged(Channel),
+    CustomChannelChanged(String),
+    CustomChannelSubmitted,
+    CustomChannelTestPressed,
+    CustomChannelTested(Channel, Result<ChannelCheck>),
     WgpuBackendChanged(profiles::WgpuBackend),
     EnvVarsChanged(String),
     AssetsOverrideChanged(String),
-    OpenLogsPressed,
+    LaunchWrapperChanged(String),
+    BrowserCommandChanged(String),
     ChannelsLoaded(Result<Channels>),
+    InstallSizeLoaded(Result<InstallSize>),
+    ExportPressed,
+    Exported(Result<PathBuf>),
+    ImportPressed,
+    /// the imported profile and where the previous one was backed up
+    Imported(Result<(Box<Profile>, PathBuf)>),
+    SlotChanged(String),
+    NewSlotChanged(String),
+    NewSlotSubmitted,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct SettingsPanelComponent {
     channels: Channels,
+    custom_channel: String,
+    /// result of testing the entered channel, `None` while the test is running
+    custom_channel_test: Option<Option<Result<ChannelCheck>>>,
+    install_size: Option<InstallSize>,
+    /// outcome of the last export or import
+    config_transfer: Option<Result<String>>,
+    new_slot: String,
+    /// why the entered slot name was rejected
+    new_slot_error: Option<String>,
 }
 
 impl SettingsPanelComponent {
@@ -56,7 +88,7 @@ impl SettingsPanelComponent {
                 let profile2 = profile.clone();
                 Some(Command::batch(vec![
                     Command::perform(
-                        async { Action::UpdateProfile(profile2) },
+                        async { Action::UpdateProfile(Box::new(profile2)) },
                         DefaultViewMessage::Action,
                     ),
                     Command::perform(async {}, |_| {
@@ -65,25 +97,59 @@ impl SettingsPanelComponent {
                 ]))
             },
             SettingsPanelMessage::ChannelChanged(new_channel) => {
-                tracing::debug!("new channel selected {}", new_channel);
-                let mut profile = active_profile.clone();
-                profile.channel = new_channel;
-                let profile2 = profile.clone();
-                Some(Command::batch(vec![
-                    Command::perform(
-                        async { Action::UpdateProfile(profile2) },
-                        DefaultViewMessage::Action,
-                    ),
-                    Command::perform(async {}, |_| {
-                        DefaultViewMessage::GamePanel(GamePanelMessage::StartUpdate)
-                    }),
-                ]))
+                Some(Self::change_channel(active_profile, new_channel))
+            },
+            SettingsPanelMessage::CustomChannelChanged(channel) => {
+                self.custom_channel = channel;
+                self.custom_channel_test = None;
+                None
+            },
+            SettingsPanelMessage::CustomChannelSubmitted => {
+                let channel = self.custom_channel.trim();
+                if channel.is_empty() {
+                    return None;
+                }
+                let new_channel = Channel(channel.to_lowercase());
+                self.custom_channel.clear();
+                self.custom_channel_test = None;
+                Some(Self::change_channel(active_profile, new_channel))
+            },
+            SettingsPanelMessage::CustomChannelTestPressed => {
+                let channel = Channel(self.custom_channel.trim().to_lowercase());
+                if channel.0.is_empty() {
+                    return None;
+                }
+                self.custom_channel_test = Some(None);
+                let profile = active_profile.clone();
+                Some(Command::perform(
+                    async move {
+                        let result = channels::check(&profile, channel.clone()).await;
+                        (channel, result)
+                    },
+                    |(channel, result)| {
+                        DefaultViewMessage::SettingsPanel(
+                            SettingsPanelMessage::CustomChannelTested(channel, result),
+                        )
+                    },
+                ))
+            },
+            SettingsPanelMessage::CustomChannelTested(channel, result) => {
+                // the entered channel changed while testing
+                if channel.0 != self.custom_channel.trim().to_lowercase() {
+                    return None;
+                }
+                match &result {
+                    Ok(check) => debug!(?channel, ?check, "Channel test passed"),
+                    Err(e) => tracing::warn!(?channel, ?e, "Channel test failed"),
+                }
+                self.custom_channel_test = Some(Some(result));
+                None
             },
             SettingsPanelMessage::WgpuBackendChanged(wgpu_backend) => {
                 let mut profile = active_profile.clone();
                 profile.wgpu_backend = wgpu_backend;
                 Some(Command::perform(
-                    async { Action::UpdateProfile(profile) },
+                    async { Action::UpdateProfile(Box::new(profile)) },
                     DefaultViewMessage::Action,
                 ))
             },
@@ -91,21 +157,39 @@ impl SettingsPanelComponent {
                 let mut profile = active_profile.clone();
                 profile.log_level = log_level;
                 Some(Command::perform(
-                    async { Action::UpdateProfile(profile) },
+                    async { Action::UpdateProfile(Box::new(profile)) },
                     DefaultViewMessage::Action,
                 ))
             },
-            SettingsPanelMessage::OpenLogsPressed => {
-                if let Err(e) = opener::open(active_profile.voxygen_logs_path()) {
-                    tracing::error!("Failed to open logs dir: {:?}", e);
-                }
-                None
+            SettingsPanelMessage::ThemeChanged(theme) => {
+                let mut profile = active_profile.clone();
+                profile.theme = theme;
+                Some(Command::perform(
+                    async { Action::UpdateProfile(Box::new(profile)) },
+                    DefaultViewMessage::Action,
+                ))
+            },
+            SettingsPanelMessage::UiScaleChanged(scale) => {
+                let mut profile = active_profile.clone();
+                profile.ui_scale = scale;
+                Some(Command::perform(
+                    async { Action::UpdateProfile(Box::new(profile)) },
+                    DefaultViewMessage::Action,
+                ))
+            },
+            SettingsPanelMessage::ProgressMetricChanged(metric) => {
+                let mut profile = active_profile.clone();
+                profile.progress_metric = metric;
+                Some(Command::perform(
+                    async { Action::UpdateProfile(Box::new(profile)) },
+                    DefaultViewMessage::Action,
+                ))
             },
             SettingsPanelMessage::EnvVarsChanged(vars) => {
                 let mut profile = active_profile.clone();
                 profile.env_vars = vars;
                 Some(Command::perform(
-                    async { Action::UpdateProfile(profile) },
+                    async { Action::UpdateProfile(Box::new(profile)) },
                     DefaultViewMessage::Action,
                 ))
             },
@@ -113,19 +197,192 @@ impl SettingsPanelComponent {
                 let mut profile = active_profile.clone();
                 profile.assets_override = Some(assets);
                 Some(Command::perform(
-                    async { Action::UpdateProfile(profile) },
+                    async { Action::UpdateProfile(Box::new(profile)) },
                     DefaultViewMessage::Action,
                 ))
             },
-            SettingsPanelMessage::ChannelsLoaded(result) => {
-                if let Ok(channels) = result {
+            SettingsPanelMessage::LaunchWrapperChanged(wrapper) => {
+                let mut profile = active_profile.clone();
+                profile.launch_wrapper = wrapper;
+                Some(Command::perform(
+                    async { Action::UpdateProfile(Box::new(profile)) },
+                    DefaultViewMessage::Action,
+                ))
+            },
+            SettingsPanelMessage::BrowserCommandChanged(command) => {
+                let mut profile = active_profile.clone();
+                profile.browser_command = command;
+                Some(Command::perform(
+                    async { Action::UpdateProfile(Box::new(profile)) },
+                    DefaultViewMessage::Action,
+                ))
+            },
+            SettingsPanelMessage::ChannelsLoaded(result) => match result {
+                Ok(channels) => {
                     debug!(?channels, "Fetched available channels:");
                     self.channels = channels;
+                    None
+                },
+                Err(e) => {
+                    tracing::warn!(?e, "Failed to fetch the available channels");
+                    Some(toast(
+                        ToastSeverity::Warning,
+                        "Could not fetch the available channels",
+                    ))
+                },
+            },
+            SettingsPanelMessage::InstallSizeLoaded(result) => match result {
+                Ok(size) => {
+                    self.install_size = Some(size);
+                    None
+                },
+                Err(e) => {
+                    tracing::warn!(?e, "Failed to calculate install size");
+                    Some(toast(
+                        ToastSeverity::Warning,
+                        "Could not calculate the install size",
+                    ))
+                },
+            },
+            SettingsPanelMessage::ExportPressed => {
+                let profile = active_profile.clone();
+                Some(Command::perform(
+                    async move {
+                        let path = fs::export_file();
+                        profile.export(&path, false, false).await.map(|()| path)
+                    },
+                    |result| {
+                        DefaultViewMessage::SettingsPanel(SettingsPanelMessage::Exported(
+                            result,
+                        ))
+                    },
+                ))
+            },
+            SettingsPanelMessage::Exported(result) => {
+                if let Err(e) = &result {
+                    tracing::warn!(?e, "Failed to export the settings");
                 }
-
+                self.config_transfer = Some(
+                    result.map(|path| format!("Settings exported to {}", path.display())),
+                );
+                None
+            },
+            SettingsPanelMessage::ImportPressed => {
+                let profile = active_profile.clone();
+                Some(Command::perform(
+                    async move {
+                        let imported = profile.import(&fs::export_file())?;
+                        let backup = profile.backup().await?;
+                        Ok((Box::new(imported), backup))
+                    },
+                    |result| {
+                        DefaultViewMessage::SettingsPanel(SettingsPanelMessage::Imported(
+                            result,
+                        ))
+                    },
+                ))
+            },
+            SettingsPanelMessage::Imported(result) => match result {
+                Ok((profile, backup)) => {
+                    self.config_transfer = Some(Ok(format!(
+                        "Settings imported, the previous ones were saved to {}",
+                        backup.display()
+                    )));
+                    // the server or channel might have changed
+                    Some(Command::batch(vec![
+                        Command::perform(
+                            async { Action::UpdateProfile(profile) },
+                            DefaultViewMessage::Action,
+                        ),
+                        Command::perform(async {}, |_| {
+                            DefaultViewMessage::GamePanel(GamePanelMessage::StartUpdate)
+                        }),
+                    ]))
+                },
+                Err(e) => {
+                    tracing::warn!(?e, "Failed to import the settings");
+                    self.config_transfer = Some(Err(e));
+                    None
+                },
+            },
+            SettingsPanelMessage::SlotChanged(slot) => {
+                self.change_slot(active_profile, &slot)
+            },
+            SettingsPanelMessage::NewSlotChanged(slot) => {
+                self.new_slot = slot;
+                self.new_slot_error = None;
                 None
             },
+            SettingsPanelMessage::NewSlotSubmitted => {
+                let slot = self.new_slot.trim().to_owned();
+                if let Err(e) = profiles::validate_slot_name(&slot) {
+                    self.new_slot_error = Some(e);
+                    return None;
+                }
+                self.new_slot.clear();
+                self.change_slot(active_profile, &slot)
+            },
+        }
+    }
+
+    fn change_slot(
+        &mut self,
+        active_profile: &Profile,
+        slot: &str,
+    ) -> Option<Command<DefaultViewMessage>> {
+        let mut profile = active_profile.clone();
+        if let Err(e) = profile.switch_slot(slot) {
+            self.new_slot_error = Some(e.to_string());
+            return None;
         }
+        self.install_size = None;
+        let directory = profile.directory();
+        let channel = profile.channel.clone();
+        Some(Command::batch(vec![
+            Command::perform(
+                async { Action::UpdateProfile(Box::new(profile)) },
+                DefaultViewMessage::Action,
+            ),
+            // verifies the slot and installs it if it is new
+            Command::perform(async {}, |_| {
+                DefaultViewMessage::GamePanel(GamePanelMessage::StartUpdate)
+            }),
+            Command::perform(ChangelogPanelComponent::fetch(channel), |update| {
+                DefaultViewMessage::ChangelogPanel(
+                    ChangelogPanelMessage::UpdateChangelog(update),
+                )
+            }),
+            Command::perform(fs::install_size(directory), |size| {
+                DefaultViewMessage::SettingsPanel(
+                    SettingsPanelMessage::InstallSizeLoaded(size),
+                )
+            }),
+        ]))
+    }
+
+    fn change_channel(
+        active_profile: &Profile,
+        new_channel: Channel,
+    ) -> Command<DefaultViewMessage> {
+        tracing::debug!("new channel selected {}", new_channel);
+        let mut profile = active_profile.clone();
+        profile.channel = new_channel.clone();
+        // the installed version belongs to the old channel, so force a re-check
+        profile.version = None;
+        Command::batch(vec![
+            Command::perform(
+                async { Action::UpdateProfile(Box::new(profile)) },
+                DefaultViewMessage::Action,
+            ),
+            Command::perform(async {}, |_| {
+                DefaultViewMessage::GamePanel(GamePanelMessage::StartUpdate)
+            }),
+            Command::perform(ChangelogPanelComponent::fetch(new_channel), |update| {
+                DefaultViewMessage::ChangelogPanel(
+                    ChangelogPanelMessage::UpdateChangelog(update),
+                )
+            }),
+        ])
     }
 
     pub fn view<'a>(
@@ -179,21 +436,7 @@ impl SettingsPanelComponent {
                         container(text("LOG LEVEL").size(10).style(TextStyle::LightGrey))
                             .padding([0, 0, 0, 3]),
                     )
-                    .push(
-                        container(
-                            button(
-                                image(Handle::from_memory(FOLDER_ICON.to_vec()))
-                                    .height(Length::Fixed(15.0))
-                                    .width(Length::Fixed(15.0)),
-                            )
-                            .on_press(DefaultViewMessage::SettingsPanel(
-                                SettingsPanelMessage::OpenLogsPressed,
-                            ))
-                            .padding(0)
-                            .style(ButtonStyle::Transparent),
-                        )
-                        .align_x(Horizontal::Right),
-                    )
+                    .push(folder_button(Interaction::OpenLogsDir))
                     .align_items(Alignment::Center),
             )
             .push(
@@ -252,6 +495,97 @@ impl SettingsPanelComponent {
             )
             .width(Length::FillPortion(1));
 
+        let theme_picker = column![]
+            .spacing(5)
+            .push(
+                container(text("THEME").size(10).style(TextStyle::LightGrey))
+                    .padding([0, 0, 0, 3]),
+            )
+            .push(
+                container(
+                    pick_list(profiles::UI_THEMES, Some(active_profile.theme), |x| {
+                        DefaultViewMessage::SettingsPanel(
+                            SettingsPanelMessage::ThemeChanged(x),
+                        )
+                    })
+                    .text_size(FONT_SIZE)
+                    .padding(PICK_LIST_PADDING)
+                    .width(Length::Fill),
+                )
+                .height(Length::Fixed(30.0)),
+            )
+            .width(Length::FillPortion(1));
+
+        let progress_metric_picker = column![]
+            .spacing(5)
+            .push(
+                container(text("PROGRESS BAR").size(10).style(TextStyle::LightGrey))
+                    .padding([0, 0, 0, 3]),
+            )
+            .push(
+                tooltip(
+                    container(
+                        pick_list(
+                            profiles::PROGRESS_METRICS,
+                            Some(active_profile.progress_metric),
+                            |x| {
+                                DefaultViewMessage::SettingsPanel(
+                                    SettingsPanelMessage::ProgressMetricChanged(x),
+                                )
+                            },
+                        )
+                        .text_size(FONT_SIZE)
+                        .padding(PICK_LIST_PADDING)
+                        .width(Length::Fill),
+                    )
+                    .height(Length::Fixed(30.0)),
+                    text(
+                        "The whole update counts the download and the unzipping of the \
+                         decompressed files together",
+                    )
+                    .size(14),
+                    Position::Bottom,
+                )
+                .style(ContainerStyle::Tooltip)
+                .gap(5),
+            )
+            .width(Length::FillPortion(1));
+
+        let ui_scale_picker = column![]
+            .spacing(5)
+            .push(
+                container(text("ZOOM").size(10).style(TextStyle::LightGrey))
+                    .padding([0, 0, 0, 3]),
+            )
+            .push(
+                tooltip(
+                    container(
+                        pick_list(
+                            profiles::UI_SCALES,
+                            Some(active_profile.ui_scale),
+                            |x| {
+                                DefaultViewMessage::SettingsPanel(
+                                    SettingsPanelMessage::UiScaleChanged(x),
+                                )
+                            },
+                        )
+                        .text_size(FONT_SIZE)
+                        .padding(PICK_LIST_PADDING)
+                        .width(Length::Fill),
+                    )
+                    .height(Length::Fixed(30.0)),
+                    text(
+                        "Zooms text and layout, the window size only adapts on the next \
+                         start",
+                    )
+                    .size(14),
+                    Position::Bottom,
+                )
+                .style(ContainerStyle::Tooltip)
+                .gap(5),
+            )
+            .width(Length::FillPortion(1));
+
         let help_link =
             "https://book.veloren.net/players/env-vars.html#veloren_assets_override"
                 .to_owned();
@@ -301,7 +635,7 @@ impl SettingsPanelComponent {
             .width(Length::Fill);
 
         let help_link = "https://book.veloren.net/players/env-vars.html".to_owned();
-        let env_vars = column![]
+        let mut env_vars = column![]
             .spacing(5)
             .push(
                 row![]
@@ -336,25 +670,78 @@ impl SettingsPanelComponent {
                 .gap(5),
             )
             .width(Length::FillPortion(2));
+        // only a hint, the variables are set anyway
+        let warnings = active_profile.env_var_warnings(&active_profile.env_vars);
+        if !warnings.is_empty() {
+            env_vars = env_vars.push(
+                container(
+                    text(warnings.join("\n"))
+                        .size(10)
+                        .style(TextStyle::BrightOrange),
+                )
+                .padding([0, 0, 0, 3]),
+            );
+        }
 
-        let channel_picker = column![]
+        let launch_wrapper = column![]
             .spacing(5)
             .push(
-                container(text("CHANNEL").size(10).style(TextStyle::LightGrey))
+                container(text("LAUNCH WRAPPER").size(10).style(TextStyle::LightGrey))
                     .padding([0, 0, 0, 3]),
             )
             .push(
                 tooltip(
                     container(
-                        pick_list(
-                            self.channels.names.clone(),
-                            Some(active_profile.channel.clone()),
-                            |x| {
-                                DefaultViewMessage::SettingsPanel(
-                                    SettingsPanelMessage::ChannelChanged(x),
-                                )
-                            },
+                        text_input(
+                            "gamemoderun mangohud",
+                            &active_profile.launch_wrapper,
                         )
+                        .on_input(|wrapper| {
+                            DefaultViewMessage::SettingsPanel(
+                                SettingsPanelMessage::LaunchWrapperChanged(wrapper),
+                            )
+                        })
+                        .padding(PICK_LIST_PADDING)
+                        .size(FONT_SIZE),
+                    )
+                    .height(Length::Fixed(30.0)),
+                    text(
+                        "Command used to start the game, e.g. gamemoderun. {game} and \
+                         {install_dir} are replaced with their paths",
+                    )
+                    .size(14),
+                    Position::Bottom,
+                )
+                .style(ContainerStyle::Tooltip)
+                .gap(5),
+            )
+            .width(Length::FillPortion(1));
+
+        let mut channels = self.channels.names.clone();
+        for name in [STABLE_CHANNEL, NIGHTLY_CHANNEL] {
+            let channel = Channel(name.to_owned());
+            if !channels.contains(&channel) {
+                channels.push(channel);
+            }
+        }
+        if !channels.contains(&active_profile.channel) {
+            channels.push(active_profile.channel.clone());
+        }
+
+        let mut channel_picker = column![]
+            .spacing(5)
+            .push(
+                container(text("CHANNEL").size(10).style(TextStyle::LightGrey))
+                    .padding([0, 0, 0, 3]),
+            )
+            .push(
+                tooltip(
+                    container(
+                        pick_list(channels, Some(active_profile.channel.clone()), |x| {
+                            DefaultViewMessage::SettingsPanel(
+                                SettingsPanelMessage::ChannelChanged(x),
+                            )
+                        })
                         .width(Length::Fill)
                         .text_size(FONT_SIZE)
                         .padding(PICK_LIST_PADDING),
@@ -366,7 +753,252 @@ impl SettingsPanelComponent {
                 .style(ContainerStyle::Tooltip)
                 .gap(5),
             )
+            .push(
+                row![]
+                    .spacing(5)
+                    .align_items(Alignment::Center)
+                    .push(
+                        tooltip(
+                            container(
+                                text_input("Custom channel", &self.custom_channel)
+                                    .on_input(|channel| {
+                                        DefaultViewMessage::SettingsPanel(
+                                            SettingsPanelMessage::CustomChannelChanged(
+                                                channel,
+                                            ),
+                                        )
+                                    })
+                                    .on_submit(DefaultViewMessage::SettingsPanel(
+                                        SettingsPanelMessage::CustomChannelSubmitted,
+                                    ))
+                                    .padding(PICK_LIST_PADDING)
+                                    .size(FONT_SIZE),
+                            )
+                            .height(Length::Fixed(30.0)),
+                            text("Enter a channel name and press enter to switch to it")
+                                .size(14),
+                            Position::Bottom,
+                        )
+                        .style(ContainerStyle::Tooltip)
+                        .gap(5),
+                    )
+                    .push(
+                        tooltip(
+                            button(
+                                text("Test").size(FONT_SIZE).style(TextStyle::LightGrey),
+                            )
+                            .on_press_maybe(
+                                (!self.custom_channel.trim().is_empty()
+                                    && !matches!(self.custom_channel_test, Some(None)))
+                                .then_some(DefaultViewMessage::SettingsPanel(
+                                    SettingsPanelMessage::CustomChannelTestPressed,
+                                )),
+                            )
+                            .padding(0)
+                            .style(ButtonStyle::Transparent),
+                            text(
+                                "Checks that the channel can be downloaded without \
+                                 switching to it",
+                            )
+                            .size(14),
+                            Position::Bottom,
+                        )
+                        .style(ContainerStyle::Tooltip)
+                        .gap(5),
+                    ),
+            )
+            .width(Length::FillPortion(1));
+
+        match &self.custom_channel_test {
+            Some(None) => {
+                channel_picker = channel_picker.push(
+                    text("Testing channel...")
+                        .size(10)
+                        .style(TextStyle::LightGrey),
+                );
+            },
+            Some(Some(Ok(check))) => {
+                channel_picker = channel_picker.push(
+                    text(format!(
+                        "Channel available ({}), download size {}",
+                        check.version.get(..7).unwrap_or(&check.version),
+                        pretty_bytes(check.size)
+                    ))
+                    .size(10)
+                    .style(TextStyle::LightGrey),
+                );
+            },
+            Some(Some(Err(e))) => {
+                channel_picker = channel_picker
+                    .push(text(e.to_string()).size(10).style(TextStyle::TomatoRed));
+            },
+            None => {},
+        }
+
+        if !active_profile.channel.is_stable() {
+            channel_picker = channel_picker.push(
+                text("Non-stable channels may be broken or lose your data")
+                    .size(10)
+                    .style(TextStyle::BrightOrange),
+            );
+        }
+
+        let install_size_text = match &self.install_size {
+            Some(size) => pretty_bytes(size.total),
+            None => "Calculating...".to_string(),
+        };
+        let install_size_details = match &self.install_size {
+            Some(size) => size
+                .entries
+                .iter()
+                .map(|(name, bytes)| format!("{name}: {}", pretty_bytes(*bytes)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => "The install size is being calculated".to_string(),
+        };
+        let install_size = column![]
+            .spacing(5)
+            .push(
+                row![]
+                    .spacing(5)
+                    .push(
+                        container(
+                            text("INSTALL SIZE").size(10).style(TextStyle::LightGrey),
+                        )
+                        .padding([0, 0, 0, 3]),
+                    )
+                    .push(folder_button(Interaction::OpenInstallDir))
+                    .align_items(Alignment::Center),
+            )
+            .push(
+                tooltip(
+                    container(text(install_size_text).size(FONT_SIZE))
+                        .padding([0, 0, 0, 3]),
+                    text(install_size_details).size(14),
+                    Position::Top,
+                )
+                .style(ContainerStyle::Tooltip)
+                .gap(5),
+            )
+            .width(Length::Fill);
+
+        let export_file = fs::export_file();
+        let transfer_button = |label, tip: String, msg| {
+            tooltip(
+                button(text(label).size(FONT_SIZE).style(TextStyle::LightGrey))
+                    .on_press(DefaultViewMessage::SettingsPanel(msg))
+                    .padding(0)
+                    .style(ButtonStyle::Transparent),
+                text(tip).size(14),
+                Position::Top,
+            )
+            .style(ContainerStyle::Tooltip)
+            .gap(5)
+        };
+        let mut config_transfer = column![]
+            .spacing(5)
+            .push(
+                container(text("SETTINGS FILE").size(10).style(TextStyle::LightGrey))
+                    .padding([0, 0, 0, 3]),
+            )
+            .push(
+                row![]
+                    .spacing(10)
+                    .padding([0, 0, 0, 3])
+                    .push(transfer_button(
+                        "Export",
+                        format!("Saves the settings to {}", export_file.display()),
+                        SettingsPanelMessage::ExportPressed,
+                    ))
+                    .push(transfer_button(
+                        "Import",
+                        format!(
+                            "Loads the settings from {}, the current ones are backed up",
+                            export_file.display()
+                        ),
+                        SettingsPanelMessage::ImportPressed,
+                    )),
+            )
+            .width(Length::Fill);
+        match &self.config_transfer {
+            Some(Ok(status)) => {
+                config_transfer = config_transfer
+                    .push(text(status).size(10).style(TextStyle::LightGrey));
+            },
+            Some(Err(e)) => {
+                config_transfer = config_transfer
+                    .push(text(e.to_string()).size(10).style(TextStyle::TomatoRed));
+            },
+            None => {},
+        }
+
+        let mut slot_picker = column![]
+            .spacing(5)
+            .push(
+                container(text("INSTALL SLOT").size(10).style(TextStyle::LightGrey))
+                    .padding([0, 0, 0, 3]),
+            )
+            .push(
+                row![]
+                    .spacing(5)
+                    .push(
+                        tooltip(
+                            container(
+                                pick_list(
+                                    active_profile.slot_names(),
+                                    Some(active_profile.slot_name().to_owned()),
+                                    |x| {
+                                        DefaultViewMessage::SettingsPanel(
+                                            SettingsPanelMessage::SlotChanged(x),
+                                        )
+                                    },
+                                )
+                                .width(Length::Fill)
+                                .text_size(FONT_SIZE)
+                                .padding(PICK_LIST_PADDING),
+                            )
+                            .height(Length::Fixed(30.0)),
+                            text(
+                                "Separate installs with their own server, channel and \
+                                 version. Every slot takes up the full size of the game",
+                            )
+                            .size(14),
+                            Position::Top,
+                        )
+                        .style(ContainerStyle::Tooltip)
+                        .gap(5),
+                    )
+                    .push(
+                        tooltip(
+                            container(
+                                text_input("New slot", &self.new_slot)
+                                    .on_input(|slot| {
+                                        DefaultViewMessage::SettingsPanel(
+                                            SettingsPanelMessage::NewSlotChanged(slot),
+                                        )
+                                    })
+                                    .on_submit(DefaultViewMessage::SettingsPanel(
+                                        SettingsPanelMessage::NewSlotSubmitted,
+                                    ))
+                                    .padding(PICK_LIST_PADDING)
+                                    .size(FONT_SIZE),
+                            )
+                            .height(Length::Fixed(30.0)),
+                            text(
+                                "Enter a name and press enter to create a slot with the \
+                                 current server and channel",
+                            )
+                            .size(14),
+                            Position::Top,
+                        )
+                        .style(ContainerStyle::Tooltip)
+                        .gap(5),
+                    ),
+            )
             .width(Length::FillPortion(1));
+        if let Some(e) = &self.new_slot_error {
+            slot_picker = slot_picker.push(text(e).size(10).style(TextStyle::TomatoRed));
+        }
 
         let first_row = container(
             row![]
@@ -380,14 +1012,78 @@ impl SettingsPanelComponent {
         let second_row =
             container(row![].spacing(10).push(env_vars).push(channel_picker));
 
-        let third_row =
-            container(row![].align_items(Alignment::End).push(assets_override));
+        let browser_command = column![]
+            .spacing(5)
+            .push(
+                container(text("BROWSER").size(10).style(TextStyle::LightGrey))
+                    .padding([0, 0, 0, 3]),
+            )
+            .push(
+                tooltip(
+                    container(
+                        text_input("System default", &active_profile.browser_command)
+                            .on_input(|command| {
+                                DefaultViewMessage::SettingsPanel(
+                                    SettingsPanelMessage::BrowserCommandChanged(command),
+                                )
+                            })
+                            .padding(PICK_LIST_PADDING)
+                            .size(FONT_SIZE),
+                    )
+                    .height(Length::Fixed(30.0)),
+                    text(
+                        "Command links are opened with, e.g. firefox. {url} is replaced \
+                         with the link",
+                    )
+                    .size(14),
+                    Position::Bottom,
+                )
+                .style(ContainerStyle::Tooltip)
+                .gap(5),
+            )
+            .width(Length::FillPortion(1));
+
+        let third_row = container(
+            row![]
+                .spacing(10)
+                .align_items(Alignment::End)
+                .push(assets_override)
+                .push(browser_command),
+        );
+
+        let fourth_row = container(
+            row![]
+                .spacing(10)
+                .align_items(Alignment::End)
+                .push(launch_wrapper)
+                .push(install_size),
+        );
+
+        let fifth_row = container(
+            row![]
+                .spacing(10)
+                .align_items(Alignment::End)
+                .push(config_transfer.width(Length::FillPortion(1)))
+                .push(slot_picker),
+        );
+
+        let sixth_row = container(
+            row![]
+                .spacing(10)
+                .align_items(Alignment::End)
+                .push(theme_picker)
+                .push(ui_scale_picker)
+                .push(progress_metric_picker),
+        );
 
         let col = column![]
             .spacing(10)
             .push(first_row)
             .push(second_row)
-            .push(third_row);
+            .push(third_row)
+            .push(fourth_row)
+            .push(fifth_row)
+            .push(sixth_row);
 
         column![]
             .push(heading_with_rule("Settings"))
@@ -396,6 +1092,21 @@ impl SettingsPanelComponent {
     }
 }
 
+fn folder_button(interaction: Interaction) -> Element<'static, DefaultViewMessage> {
+    container(
+        button(
+            image(Handle::from_memory(FOLDER_ICON.to_vec()))
+                .height(Length::Fixed(15.0))
+                .width(Length::Fixed(15.0)),
+        )
+        .on_press(DefaultViewMessage::Interaction(interaction))
+        .padding(0)
+        .style(ButtonStyle::Transparent),
+    )
+    .align_x(Horizontal::Right)
+    .into()
+}
+
 fn help_link_button(url: String) -> Element<'static, DefaultViewMessage> {
     button(
         Image::new(Handle::from_memory(BOOK_ICON.to_vec()))
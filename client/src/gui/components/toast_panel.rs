@@ -0,0 +1,188 @@
+use crate::gui::{
+    style::{button::ButtonStyle, container::ContainerStyle, text::TextStyle},
+    views::default::DefaultViewMessage,
+    widget::*,
+};
+use iced::{
+    Alignment, Command, Length,
+    widget::{button, column, container, row, text},
+};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// how long a toast is shown before it is dismissed automatically
+const TOAST_DURATION: Duration = Duration::from_secs(8);
+/// how often expired toasts are removed while any are shown
+pub const TOAST_EXPIRY_CHECK: Duration = Duration::from_secs(1);
+/// further toasts are dropped while this many are shown
+const MAX_TOASTS: usize = 3;
+
+/// shown by every panel which could not fetch its content, so being offline only
+/// results in a single toast
+pub const FETCH_FAILED_TOAST: &str =
+    "Could not fetch the latest news, check your internet connection";
+/// shown by every panel which could not cache its content
+pub const CACHE_FAILED_TOAST: &str = "Could not save the news for offline use";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub enum ToastPanelMessage {
+    Dismiss(u64),
+    Expire,
+}
+
+#[derive(Debug, Clone)]
+struct Toast {
+    id: u64,
+    severity: ToastSeverity,
+    text: String,
+    /// label of a button next to the text and the message it sends
+    action: Option<(&'static str, DefaultViewMessage)>,
+    shown_at: Instant,
+}
+
+/// Surfaces non-fatal errors which would otherwise only end up in the log.
+#[derive(Debug, Default, Clone)]
+pub struct ToastPanelComponent {
+    toasts: Vec<Toast>,
+    next_id: u64,
+}
+
+/// Shows a toast, for panels which only return a command from their update.
+pub fn toast(
+    severity: ToastSeverity,
+    text: impl Into<String>,
+) -> Command<DefaultViewMessage> {
+    let text = text.into();
+    Command::perform(async {}, move |_| DefaultViewMessage::Toast(severity, text))
+}
+
+impl ToastPanelComponent {
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// Toasts which are already shown or exceed [`MAX_TOASTS`] are dropped, so a
+    /// failing panel can't flood the launcher.
+    pub fn push(&mut self, severity: ToastSeverity, text: String) {
+        self.push_toast(severity, text, None);
+    }
+
+    /// Shows a toast with a button, e.g. to copy a link which couldn't be opened.
+    pub fn push_with_action(
+        &mut self,
+        severity: ToastSeverity,
+        text: String,
+        label: &'static str,
+        action: DefaultViewMessage,
+    ) {
+        self.push_toast(severity, text, Some((label, action)));
+    }
+
+    fn push_toast(
+        &mut self,
+        severity: ToastSeverity,
+        text: String,
+        action: Option<(&'static str, DefaultViewMessage)>,
+    ) {
+        if self.toasts.iter().any(|toast| toast.text == text) {
+            return;
+        }
+        if self.toasts.len() >= MAX_TOASTS {
+            debug!(?text, "Too many toasts shown, dropping");
+            return;
+        }
+        self.toasts.push(Toast {
+            id: self.next_id,
+            severity,
+            text,
+            action,
+            shown_at: Instant::now(),
+        });
+        self.next_id += 1;
+    }
+
+    pub fn update(
+        &mut self,
+        msg: ToastPanelMessage,
+    ) -> Option<Command<DefaultViewMessage>> {
+        match msg {
+            ToastPanelMessage::Dismiss(id) => self.toasts.retain(|toast| toast.id != id),
+            ToastPanelMessage::Expire => self
+                .toasts
+                .retain(|toast| toast.shown_at.elapsed() < TOAST_DURATION),
+        }
+
+        None
+    }
+
+    pub fn view(&self) -> Element<'_, DefaultViewMessage> {
+        let mut toasts = column![].spacing(5).padding([0, 20, 10, 20]);
+        for toast in &self.toasts {
+            let style = match toast.severity {
+                ToastSeverity::Warning => TextStyle::BrightOrange,
+                ToastSeverity::Error => TextStyle::TomatoRed,
+            };
+            let mut content = row![]
+                .push(
+                    container(text(&toast.text).size(12).style(style))
+                        .width(Length::Fill),
+                )
+                .spacing(10)
+                .align_items(Alignment::Center);
+            if let Some((label, action)) = &toast.action {
+                content = content.push(
+                    button(text(*label).size(10).style(TextStyle::LightGrey))
+                        .on_press(action.clone())
+                        .padding(0)
+                        .style(ButtonStyle::Transparent),
+                );
+            }
+            toasts = toasts.push(
+                container(
+                    content.push(
+                        button(text("Dismiss").size(10).style(TextStyle::LightGrey))
+                            .on_press(DefaultViewMessage::ToastPanel(
+                                ToastPanelMessage::Dismiss(toast.id),
+                            ))
+                            .padding(0)
+                            .style(ButtonStyle::Transparent),
+                    ),
+                )
+                .padding(8)
+                .width(Length::Fill)
+                .style(ContainerStyle::Tooltip),
+            );
+        }
+
+        toasts.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toasts_are_rate_limited() {
+        let mut panel = ToastPanelComponent::default();
+        panel.push(ToastSeverity::Warning, FETCH_FAILED_TOAST.to_string());
+        panel.push(ToastSeverity::Warning, FETCH_FAILED_TOAST.to_string());
+        assert_eq!(panel.toasts.len(), 1);
+
+        for i in 0..10 {
+            panel.push(ToastSeverity::Error, format!("error {i}"));
+        }
+        assert_eq!(panel.toasts.len(), MAX_TOASTS);
+
+        panel.update(ToastPanelMessage::Dismiss(panel.toasts[0].id));
+        assert_eq!(panel.toasts.len(), MAX_TOASTS - 1);
+        panel.update(ToastPanelMessage::Expire);
+        assert_eq!(panel.toasts.len(), MAX_TOASTS - 1);
+    }
+}
@@ -3,6 +3,8 @@ mod custom_widgets;
 mod rss_feed;
 mod style;
 mod subscriptions;
+#[cfg(feature = "tray")]
+mod tray;
 mod views;
 mod widget;
 
@@ -17,10 +19,17 @@ use crate::{
         POPPINS_MEDIUM_FONT_BYTES,
     },
     cli::CmdLine,
-    gui::{style::AirshipperTheme, widget::*},
+    gui::{
+        components::ToastSeverity,
+        style::{AirshipperTheme, container::ContainerStyle},
+        widget::*,
+    },
     profiles::Profile,
 };
-use iced::{Application, Command, Settings, Size, Subscription};
+use iced::{
+    Application, Command, Length, Settings, Size, Subscription,
+    widget::{column, container, text},
+};
 #[cfg(windows)]
 use views::update::{UpdateView, UpdateViewMessage};
 use views::{
@@ -30,7 +39,18 @@ use views::{
 
 /// Starts the GUI and won't return unless an error occurs
 pub fn run(cmd: CmdLine) -> Result<()> {
-    Ok(Airshipper::run(settings(cmd))?)
+    // The renderer can't be changed once the window exists, so the profile has to be
+    // loaded beforehand.
+    let profile = Profile::load();
+    let lite_ui = cmd.lite_ui || profile.lite_ui;
+    if let Some(max) = profile.max_parallel_fetches {
+        crate::net::set_max_parallel_fetches(max);
+    }
+    crate::net::pinning::pin_download_server(&profile);
+    crate::net::set_http_version(profile.http_version);
+    #[cfg(windows)]
+    crate::windows::set_console_visibility(cmd.console_mode().unwrap_or(profile.console));
+    Ok(Airshipper::run(settings(profile, lite_ui))?)
 }
 
 #[derive(Debug, Clone)]
@@ -41,10 +61,18 @@ pub struct Airshipper {
     #[cfg(windows)]
     update_view: UpdateView,
     pub active_profile: Profile,
+    /// shown until the first queried data arrives
+    loading: bool,
 
     // Airshipper update
     #[cfg(windows)]
     update: Option<self_update::update::Release>,
+
+    #[cfg(feature = "tray")]
+    tray: Option<tray::Tray>,
+    /// the window is hidden in the tray
+    #[cfg(feature = "tray")]
+    hidden: bool,
 }
 
 impl Airshipper {
@@ -54,7 +82,15 @@ impl Airshipper {
             default_view: DefaultView::default(),
             #[cfg(windows)]
             update_view: UpdateView::default(),
+            #[cfg(feature = "tray")]
+            tray: active_profile
+                .minimize_to_tray
+                .then(tray::Tray::spawn)
+                .flatten(),
+            #[cfg(feature = "tray")]
+            hidden: false,
             active_profile,
+            loading: true,
             #[cfg(windows)]
             update: None,
         }
@@ -65,33 +101,40 @@ impl Airshipper {
 #[derive(Clone, Debug)]
 pub enum Message {
     Loaded,
-    #[allow(dead_code)]
     Saved(Result<()>),
 
     // Views
     DefaultViewMessage(DefaultViewMessage),
     #[cfg(windows)]
     UpdateViewMessage(UpdateViewMessage),
+
+    #[cfg(feature = "tray")]
+    TrayPoll,
+    #[cfg(feature = "tray")]
+    CloseRequested,
 }
 
 impl Application for Airshipper {
     type Executor = iced::executor::Default;
     type Message = Message;
     type Theme = AirshipperTheme;
-    type Flags = CmdLine;
-
-    fn new(_flags: CmdLine) -> (Self, Command<Message>) {
-        #[cfg(windows)]
-        crate::windows::hide_non_inherited_console();
+    type Flags = Profile;
 
+    fn new(profile: Profile) -> (Self, Command<Message>) {
         (
-            Airshipper::new(Profile::load()),
+            Airshipper::new(profile),
             Command::perform(async {}, |_| Message::Loaded),
         )
     }
 
     fn title(&self) -> String {
-        format!("Airshipper v{}", env!("CARGO_PKG_VERSION"))
+        let title = format!("Airshipper v{}", env!("CARGO_PKG_VERSION"));
+        match self.default_view.update_progress() {
+            Some(progress) if self.active_profile.progress_in_title => {
+                format!("{title} - {progress}")
+            },
+            _ => title,
+        }
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
@@ -102,14 +145,39 @@ impl Application for Airshipper {
                     .update(DefaultViewMessage::Query, &self.active_profile)
                     .map(Message::DefaultViewMessage);
             },
-            Message::Saved(_) => {},
+            Message::Saved(Ok(())) => {},
+            Message::Saved(Err(e)) => {
+                tracing::error!(?e, "Failed to save the launcher configuration");
+                return self
+                    .default_view
+                    .update(
+                        DefaultViewMessage::Toast(
+                            ToastSeverity::Error,
+                            "Could not save the settings".to_string(),
+                        ),
+                        &self.active_profile,
+                    )
+                    .map(Message::DefaultViewMessage);
+            },
 
             // Views
             Message::DefaultViewMessage(msg) => {
+                // the first panel data, even a failed fetch, ends the loading state
+                if matches!(
+                    msg,
+                    DefaultViewMessage::NewsPanel(_)
+                        | DefaultViewMessage::ChangelogPanel(_)
+                        | DefaultViewMessage::AnnouncementPanel(_)
+                        | DefaultViewMessage::CommunityShowcasePanel(_)
+                        | DefaultViewMessage::ServerBrowserPanel(_)
+                ) {
+                    self.loading = false;
+                }
+
                 if let DefaultViewMessage::Action(action) = &msg {
                     match action {
                         Action::UpdateProfile(profile) => {
-                            self.active_profile = profile.clone();
+                            self.active_profile = Profile::clone(profile);
                             self.active_profile.reload_wgpu_backends();
 
                             return Command::perform(
@@ -127,17 +195,39 @@ impl Application for Airshipper {
                     }
                 }
 
-                return self
+                let command = self
                     .default_view
                     .update(msg, &self.active_profile)
                     .map(Message::DefaultViewMessage);
+                #[cfg(feature = "tray")]
+                if let Some(tray) = &mut self.tray {
+                    tray.set_status(self.default_view.status());
+                }
+                return command;
+            },
+            #[cfg(feature = "tray")]
+            Message::TrayPoll => {
+                let actions = self.tray.as_ref().map(tray::Tray::actions);
+                let commands = actions.into_iter().flatten().map(|action| match action {
+                    tray::TrayAction::ToggleWindow => self.set_hidden(!self.hidden),
+                    tray::TrayAction::Quit => iced::window::close(iced::window::Id::MAIN),
+                });
+                return Command::batch(commands.collect::<Vec<_>>());
+            },
+            #[cfg(feature = "tray")]
+            Message::CloseRequested => {
+                // updates keep running while the window is hidden
+                return match self.tray {
+                    Some(_) => self.set_hidden(true),
+                    None => iced::window::close(iced::window::Id::MAIN),
+                };
             },
             #[cfg(windows)]
             Message::UpdateViewMessage(msg) => {
                 if let UpdateViewMessage::Action(action) = &msg {
                     match action {
                         Action::UpdateProfile(profile) => {
-                            self.active_profile = profile.clone();
+                            self.active_profile = Profile::clone(profile);
                             return Command::perform(
                                 Profile::save(self.active_profile.clone()),
                                 Message::Saved,
@@ -164,6 +254,19 @@ impl Application for Airshipper {
         } = self;
 
         match view {
+            View::Default if self.loading => column![]
+                .push(
+                    container(text("Loading...").size(12))
+                        .width(Length::Fill)
+                        .padding([2, 10])
+                        .style(ContainerStyle::Dark),
+                )
+                .push(
+                    default_view
+                        .view(&self.active_profile)
+                        .map(Message::DefaultViewMessage),
+                )
+                .into(),
             View::Default => default_view
                 .view(&self.active_profile)
                 .map(Message::DefaultViewMessage),
@@ -173,28 +276,86 @@ impl Application for Airshipper {
     }
 
     fn theme(&self) -> Self::Theme {
-        AirshipperTheme {}
+        AirshipperTheme {
+            variant: self.active_profile.theme,
+        }
+    }
+
+    /// Zooms text and layout alike and is applied live, the window size only adapts on
+    /// the next start, see [`settings`]
+    fn scale_factor(&self) -> f64 {
+        self.active_profile.ui_scale.factor()
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        match self.view {
+        let subscription = match self.view {
             View::Default => self
                 .default_view
-                .subscription()
+                .subscription(&self.active_profile)
                 .map(Message::DefaultViewMessage),
             #[cfg(windows)]
             View::Update => iced::Subscription::none(),
+        };
+
+        #[cfg(feature = "tray")]
+        let subscription = Subscription::batch([
+            subscription,
+            iced::event::listen_with(|event, _| match event {
+                iced::Event::Window(_, iced::window::Event::CloseRequested) => {
+                    Some(Message::CloseRequested)
+                },
+                _ => None,
+            }),
+            match self.tray {
+                Some(_) => subscriptions::repeat_message::stream(
+                    tray::TRAY_EVENT_INTERVAL,
+                    Message::TrayPoll,
+                ),
+                None => Subscription::none(),
+            },
+        ]);
+
+        subscription
+    }
+}
+
+#[cfg(feature = "tray")]
+impl Airshipper {
+    fn set_hidden(&mut self, hidden: bool) -> Command<Message> {
+        use iced::window::{self, Mode};
+
+        self.hidden = hidden;
+        if hidden {
+            window::change_mode(window::Id::MAIN, Mode::Hidden)
+        } else {
+            Command::batch([
+                window::change_mode(window::Id::MAIN, Mode::Windowed),
+                window::gain_focus(window::Id::MAIN),
+            ])
         }
     }
 }
 
-fn settings(cmd: CmdLine) -> Settings<CmdLine> {
+fn settings(profile: Profile, lite_ui: bool) -> Settings<Profile> {
     use iced::window::{Settings as Window, icon};
     let icon = image::load_from_memory(crate::assets::VELOREN_ICON).unwrap();
 
+    // The software renderer avoids the GPU entirely, which is a lot smoother on weak
+    // integrated GPUs. Respect the user's choice if they picked a backend themselves.
+    if lite_ui && std::env::var_os("ICED_BACKEND").is_none() {
+        tracing::debug!("using lite UI");
+        // SAFETY: setting a variable races with other threads accessing the
+        // environment. Neither iced nor a runtime has started any thread yet, the only
+        // other thread is the log writer of `tracing_appender`, which never touches
+        // the environment.
+        unsafe { std::env::set_var("ICED_BACKEND", "tiny-skia") };
+    }
+
+    // the window is created large enough for the zoomed UI, `scale_factor` zooms it
+    let scale = profile.ui_scale.factor() as f32;
     Settings {
         window: Window {
-            size: Size::new(1050.0, 720.0),
+            size: Size::new(1050.0 * scale, 720.0 * scale),
             resizable: true,
             decorations: true,
             icon: Some(
@@ -202,12 +363,14 @@ fn settings(cmd: CmdLine) -> Settings<CmdLine> {
                     .unwrap(),
             ),
             min_size: Some(Size::new(400.0, 250.0)),
+            // closing hides the window in the tray instead, see `Message::CloseRequested`
+            exit_on_close_request: !(cfg!(feature = "tray") && profile.minimize_to_tray),
             ..Default::default()
         },
-        flags: cmd,
+        flags: profile,
         default_font: crate::assets::POPPINS_FONT,
         default_text_size: 20.0.into(),
-        antialiasing: true,
+        antialiasing: !lite_ui,
         id: Some("airshipper".to_string()),
         fonts: vec![
             #[cfg(feature = "bundled_font")]
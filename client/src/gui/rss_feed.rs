@@ -1,12 +1,16 @@
-use crate::{ClientError, Result, fs, gui::views::default::DefaultViewMessage, net};
+use crate::{
+    ClientError, Result,
+    feeds::{self, FeedPost},
+    fs,
+    gui::{
+        components::{CACHE_FAILED_TOAST, FETCH_FAILED_TOAST, ToastSeverity, toast},
+        views::default::DefaultViewMessage,
+    },
+    net,
+};
 use futures_util::future::join_all;
 use iced::{Command, widget::image::Handle};
 use image::{ExtendedColorType, ImageFormat, imageops::FilterType};
-use ron::{
-    de::from_str,
-    ser::{PrettyConfig, to_string_pretty},
-};
-use rss::Channel;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, warn};
 
@@ -16,13 +20,18 @@ pub enum RssFeedUpdateStatus {
     UpdateFailed(ClientError),
     Loaded(RssFeedData),
     Updated(RssFeedData),
-    Saved,
+    Saved(Result<()>),
 }
 
 #[derive(Clone, Debug)]
 pub enum RssFeedComponentMessage {
     UpdateRssFeed(RssFeedUpdateStatus),
-    ImageFetched { url: String, result: Result<Handle> },
+    ImageFetched {
+        url: String,
+        result: Result<Handle>,
+    },
+    /// fetches the feed again if its etag changed
+    Refresh,
 }
 
 /// Allows a component to handle updates to an RSS feed that it owns
@@ -34,6 +43,9 @@ pub trait RssFeedComponent {
     /// Stores the feed against the component's own state
     fn store_feed(&mut self, rss_feed_data: RssFeedData);
 
+    /// Returns the etag of the stored feed
+    fn etag(&self) -> &str;
+
     /// Returns the posts that the component has previously fetched from the RSS feed
     fn posts(&self) -> Vec<RssPost>;
     fn posts_mut(&mut self) -> Vec<&mut RssPost>;
@@ -44,6 +56,52 @@ pub trait RssFeedComponent {
     /// An optional hook that is called after the RSS feed is updated
     fn after_rss_feed_updated(&mut self) {}
 
+    /// Marks whether the feed could be refreshed, cached posts are shown either way
+    fn set_offline(&mut self, offline: bool);
+
+    /// Fetches the images of all posts which don't have one yet, they should reside in
+    /// the cache unless it has been cleared.
+    fn fetch_missing_images(&self) -> Vec<Command<DefaultViewMessage>> {
+        self.posts()
+            .iter()
+            .filter_map(|post| {
+                // Filter out posts that we already have the image for, or
+                // don't have an image URL
+                let url = post.image_url.as_ref().filter(|_| post.image.is_none())?;
+                let url = url.to_owned();
+                Some(Command::perform(
+                    RssPost::fetch_image(
+                        url.clone(),
+                        Self::NAME,
+                        post.image_cache_name(),
+                        Self::IMAGE_HEIGHT,
+                    ),
+                    move |result| {
+                        Self::rss_feed_message(RssFeedComponentMessage::ImageFetched {
+                            url,
+                            result,
+                        })
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// Fetches the feed unless the etag of the remote one matches the stored feed.
+    fn refresh(&self) -> Command<DefaultViewMessage> {
+        Command::perform(
+            RssFeedData::update_feed(
+                Self::FEED_URL,
+                Self::NAME,
+                Self::IMAGE_HEIGHT,
+                self.etag().to_string(),
+            ),
+            |status| {
+                Self::rss_feed_message(RssFeedComponentMessage::UpdateRssFeed(status))
+            },
+        )
+    }
+
     fn handle_update(
         &mut self,
         msg: RssFeedComponentMessage,
@@ -51,68 +109,41 @@ pub trait RssFeedComponent {
         match msg {
             RssFeedComponentMessage::UpdateRssFeed(status) => match status {
                 RssFeedUpdateStatus::Loaded(feed_data) => {
-                    let etag = feed_data.etag.clone();
                     self.store_feed(feed_data);
+                    // Show the cached posts right away, the refresh happens in the
+                    // background
+                    let mut commands = self.fetch_missing_images();
+                    commands.push(self.refresh());
+                    Some(Command::batch(commands))
+                },
+                RssFeedUpdateStatus::Updated(feed_data) => {
+                    self.set_offline(false);
+                    self.store_feed(feed_data.clone());
+                    self.after_rss_feed_updated();
+
                     Some(Command::perform(
-                        RssFeedData::update_feed(
-                            Self::FEED_URL,
-                            Self::NAME,
-                            Self::IMAGE_HEIGHT,
-                            etag,
-                        ),
-                        move |status| {
+                        feed_data.save_feed(Self::NAME),
+                        |result| {
                             Self::rss_feed_message(
-                                RssFeedComponentMessage::UpdateRssFeed(status),
+                                RssFeedComponentMessage::UpdateRssFeed(
+                                    RssFeedUpdateStatus::Saved(result),
+                                ),
                             )
                         },
                     ))
                 },
-                RssFeedUpdateStatus::Updated(feed_data) => {
-                    self.store_feed(feed_data.clone());
-                    self.after_rss_feed_updated();
-
-                    Some(Command::perform(feed_data.save_feed(Self::NAME), |_| {
-                        Self::rss_feed_message(RssFeedComponentMessage::UpdateRssFeed(
-                            RssFeedUpdateStatus::Saved,
-                        ))
-                    }))
+                RssFeedUpdateStatus::Saved(Ok(())) => None,
+                RssFeedUpdateStatus::Saved(Err(e)) => {
+                    warn!(?e, "Could not cache feed data for feed: {}", Self::NAME);
+                    Some(toast(ToastSeverity::Warning, CACHE_FAILED_TOAST))
                 },
-                RssFeedUpdateStatus::Saved => None,
                 RssFeedUpdateStatus::NoUpdateRequired => {
-                    // On application startup when there's been no rss update since the
-                    // last run the posts will have been de-serialized  without their
-                    // image data (which we don't store in the state ron file) so we need
-                    // to attempt to fetch the image for them, which
-                    // should reside in the cache if it hasn't been
-                    // cleared.
-                    let posts = self.posts();
-                    let commands: Vec<Command<DefaultViewMessage>> = posts
-                        .iter()
-                        .filter_map(|post| {
-                            // Filter out posts that we already have the image for, or
-                            // don't have an image URL
-                            if post.image.is_some() || post.image_url.is_none() {
-                                return None;
-                            }
-                            let url = post.image_url.as_ref().unwrap().to_owned();
-                            Some(Command::perform(
-                                RssPost::fetch_image(
-                                    url.clone(),
-                                    Self::NAME,
-                                    post.image_cache_name(),
-                                    Self::IMAGE_HEIGHT,
-                                ),
-                                move |result| {
-                                    Self::rss_feed_message(
-                                        RssFeedComponentMessage::ImageFetched {
-                                            url,
-                                            result,
-                                        },
-                                    )
-                                },
-                            ))
-                        })
-                        .collect();
+                    self.set_offline(false);
+                    // On application startup the posts will have been de-serialized
+                    // without their image data (which we don't store in the state ron
+                    // file). Images which could not be loaded from the cache earlier
+                    // are downloaded now that we know we're online.
+                    let commands = self.fetch_missing_images();
 
                     self.after_rss_feed_updated();
 
@@ -121,18 +152,20 @@ pub trait RssFeedComponent {
                 },
                 RssFeedUpdateStatus::UpdateFailed(e) => {
                     error!(?e, "Failed to fetch RSS feed");
-                    None
+                    self.set_offline(true);
+                    Some(toast(ToastSeverity::Warning, FETCH_FAILED_TOAST))
                 },
             },
+            RssFeedComponentMessage::Refresh => Some(self.refresh()),
             RssFeedComponentMessage::ImageFetched { result, url } => {
-                if let Ok(handle) = result
-                    && let Some(post) = self
-                        .posts_mut()
-                        .iter_mut()
-                        .filter(|post| post.image_url.is_some())
-                        .find(|post| post.image_url.as_ref().unwrap() == &url)
+                if let Some(post) = self
+                    .posts_mut()
+                    .iter_mut()
+                    .filter(|post| post.image_url.is_some())
+                    .find(|post| post.image_url.as_ref().unwrap() == &url)
                 {
-                    post.image = Some(handle);
+                    post.image_failed = result.is_err();
+                    post.image = result.ok();
                 }
 
                 None
@@ -203,8 +236,12 @@ impl RssFeedData {
         name: &str,
         height: u32,
     ) -> RssFeedUpdateStatus {
-        match tokio::fs::read_to_string(&Self::cache_file(name)).await {
-            Ok(string) => match from_str(&string) {
+        let path = Self::cache_file(name);
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => match fs::decode_stored(&bytes)
+                .map_err(ClientError::from)
+                .and_then(|string| fs::parse_ron(&path, &string))
+            {
                 Ok(feed_data) => return RssFeedUpdateStatus::Loaded(feed_data),
                 Err(e) => tracing::trace!(
                     ?e,
@@ -223,38 +260,29 @@ impl RssFeedData {
         }
     }
 
-    async fn save_feed(self, name: &str) {
-        match to_string_pretty(&self, PrettyConfig::default()) {
-            Ok(ron_string) => {
-                if let Err(e) = tokio::fs::write(Self::cache_file(name), ron_string).await
-                {
-                    tracing::warn!(?e, "Could not cache feed data for feed: {}", name);
-                };
-            },
-            Err(e) => tracing::warn!(
-                ?e,
-                "Could not serialize feed data for caching of feed: {}",
-                name
-            ),
-        }
+    async fn save_feed(self, name: &str) -> Result<()> {
+        let data = fs::encode_stored(&self, fs::compress_stored_files())?;
+        tokio::fs::write(Self::cache_file(name), data).await?;
+        Ok(())
     }
 
     async fn fetch(feed_url: &str, name: &str, height: u32) -> Result<RssFeedData> {
-        use std::io::BufReader;
-
-        let feed_response = net::query(feed_url).await?;
-        let etag = net::get_etag(&feed_response);
-        let feed = Channel::read_from(BufReader::new(&feed_response.bytes().await?[..]))?;
+        let feed = feeds::fetch_feed(feed_url).await?;
 
         let futs = feed
-            .items()
-            .iter()
-            // TODO: Currently we want 15 blog posts and 15 community showcase posts - if this is ever not the case
-            // then this number will need parameterising.
-            .take(15)
-            .map(move |item| async move {
-                let mut post = RssPost::from(item);
-                if let Some(url) = &post.image_url && let Ok(handle) = RssPost::fetch_image(url.to_owned(), name, post.image_cache_name(), height).await {
+            .posts
+            .into_iter()
+            .map(move |post| async move {
+                let mut post = RssPost::from(post);
+                if let Some(url) = &post.image_url
+                    && let Ok(handle) = RssPost::fetch_image(
+                        url.to_owned(),
+                        name,
+                        post.image_cache_name(),
+                        height,
+                    )
+                    .await
+                {
                     post.image = Some(handle);
                 };
                 post
@@ -264,15 +292,25 @@ impl RssFeedData {
 
         if let Ok(dir) = std::fs::read_dir(RssPost::cache_base_path(name)) {
             for file in dir.flatten() {
+                // interrupted downloads of current images are continued later
                 if let Ok(file_name) = file.file_name().into_string()
-                    && !posts.iter().any(|i| i.image_cache_name() == file_name)
+                    && let name = file_name
+                        .strip_suffix(feeds::IMAGE_VALIDATOR_SUFFIX)
+                        .unwrap_or(&file_name)
+                    && let name = name
+                        .strip_suffix(feeds::PARTIAL_IMAGE_SUFFIX)
+                        .unwrap_or(name)
+                    && !posts.iter().any(|i| i.image_cache_name() == name)
                 {
                     std::fs::remove_file(file.path())?;
                 }
             }
         }
 
-        Ok(RssFeedData { posts, etag })
+        Ok(RssFeedData {
+            posts,
+            etag: feed.etag,
+        })
     }
 }
 
@@ -285,6 +323,9 @@ pub struct RssPost {
     pub image_url: Option<String>,
     #[serde(skip)]
     pub image: Option<Handle>,
+    /// the image could neither be loaded from the cache nor downloaded
+    #[serde(skip)]
+    pub image_failed: bool,
 }
 
 impl RssPost {
@@ -304,7 +345,7 @@ impl RssPost {
     ) -> Result<Handle> {
         let cache_base_path = Self::cache_base_path(feed_name);
         std::fs::create_dir_all(&cache_base_path)?;
-        let image_cache_path = cache_base_path.join(image_cache_name);
+        let image_cache_path = cache_base_path.join(&image_cache_name);
 
         if let Ok(cached_bytes) = std::fs::read(&image_cache_path) {
             // Found the image cached locally so use it
@@ -321,53 +362,50 @@ impl RssPost {
             ));
         }
 
-        match crate::net::client::WEB_CLIENT.get(&url).send().await {
-            Ok(response) => match response.bytes().await {
-                Ok(bytes) => match image::load_from_memory(&bytes) {
-                    Ok(image) => {
-                        // Image successfully downloaded, write it to the cache before
-                        // returning it
-                        debug!(
-                            "Caching image from URL {} with path {}",
-                            url,
-                            image_cache_path.to_string_lossy()
-                        );
-                        // Decode the image and resize it to the specified height,
-                        // preserving aspect ratio. Works best if
-                        // said aspect ratio is 16:9 or wider.
-                        let rgba8 =
-                            image.resize(1000, height, FilterType::Nearest).into_rgba8();
-                        image::save_buffer_with_format(
-                            &image_cache_path,
-                            rgba8.as_raw(),
-                            rgba8.width(),
-                            rgba8.height(),
-                            ExtendedColorType::Rgba8,
-                            ImageFormat::Png,
-                        )?;
-                        Ok(Handle::from_pixels(
-                            rgba8.width(),
-                            rgba8.height(),
-                            rgba8.into_raw(),
-                        ))
-                    },
-                    Err(e) => {
-                        error!(?e, ?url, "Failed to decode image");
-                        Err(e.into())
-                    },
-                },
-                Err(e) => {
-                    error!("Failed to fetch bytes of RSS image from URL {}", url);
-                    Err(e.into())
-                },
+        let partial =
+            cache_base_path.join(image_cache_name + feeds::PARTIAL_IMAGE_SUFFIX);
+        match feeds::fetch_image(&url, &partial).await {
+            Ok(image) => {
+                // Image successfully downloaded, write it to the cache before
+                // returning it
+                debug!(
+                    "Caching image from URL {} with path {}",
+                    url,
+                    image_cache_path.to_string_lossy()
+                );
+                // Resize the image to the specified height, preserving aspect ratio.
+                // Works best if said aspect ratio is 16:9 or wider.
+                let rgba8 = image.resize(1000, height, FilterType::Nearest).into_rgba8();
+                image::save_buffer_with_format(
+                    &image_cache_path,
+                    rgba8.as_raw(),
+                    rgba8.width(),
+                    rgba8.height(),
+                    ExtendedColorType::Rgba8,
+                    ImageFormat::Png,
+                )?;
+                Ok(Handle::from_pixels(
+                    rgba8.width(),
+                    rgba8.height(),
+                    rgba8.into_raw(),
+                ))
             },
             Err(e) => {
-                error!("Failed to download RSS image from URL {}", url);
-                Err(e.into())
+                error!(?e, ?url, "Failed to download RSS image");
+                Err(e)
             },
         }
     }
 
+    /// Text shown in place of the image while it isn't available
+    pub fn image_placeholder(&self) -> &'static str {
+        if self.image_failed {
+            "Image unavailable"
+        } else {
+            "Loading..."
+        }
+    }
+
     fn cache_base_path(feed_name: &str) -> std::path::PathBuf {
         fs::get_cache_path().join(format!("{}_images", feed_name))
     }
@@ -380,47 +418,27 @@ impl RssPost {
         }
         self.title.clone()
     }
+}
 
-    fn process_description(desc: Option<&str>) -> String {
-        match desc {
-            Some(desc) => {
-                let wrapped_html = html2text::from_read(desc.as_bytes(), 400);
-                if let Ok(html) = wrapped_html {
-                    let stripped_html = html
-                        .lines()
-                        .take(3)
-                        .filter(|x| !x.contains("[banner]"))
-                        .fold(String::new(), |mut output, b| {
-                            use std::fmt::Write;
-                            let _ = writeln!(output, "{b}");
-                            output
-                        });
-                    strip_markdown::strip_markdown(&stripped_html)
-                } else {
-                    "HTML parsing failed.".into()
-                }
-            },
-            None => "No description found.".into(),
+impl From<FeedPost> for RssPost {
+    fn from(post: FeedPost) -> Self {
+        RssPost {
+            title: post.title,
+            description: post.description,
+            button_url: post.url,
+            image_url: post.image_url,
+            image: None,
+            image_failed: false,
         }
     }
 }
 
-impl From<&rss::Item> for RssPost {
-    fn from(item: &rss::Item) -> Self {
-        let mut post = RssPost {
-            title: item.title().unwrap_or("Missing title").into(),
-            description: Self::process_description(item.description()),
-            button_url: item.link().unwrap_or("https://veloren.net").into(),
-            image_url: None,
-            image: None,
-        };
-
-        // If the RSS item has an enclosure (attached media), store the URL against
-        // the post for display in the RSS feed.
-        if let Some(enclosure) = item.enclosure.as_ref() {
-            post.image_url = Some(enclosure.url.clone());
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        post
+    #[test]
+    fn test_corrupt_feed_is_backed_up() {
+        fs::assert_recovers::<RssFeedData>("feed");
     }
 }
@@ -3,7 +3,7 @@ use crate::gui::style::TOMATO_RED;
 use crate::gui::style::{
     AirshipperTheme, CORNFLOWER_BLUE, DARK_WHITE, DISCORD_BLURPLE, LIGHT_GREY,
     LIME_GREEN, MASTODON_PURPLE, NAVY_BLUE, REDDIT_ORANGE, SLATE, TRANSPARENT_WHITE,
-    TWITCH_PURPLE, VERY_DARK_GREY, YOUTUBE_RED,
+    TWITCH_PURPLE, UiTheme, VERY_DARK_GREY, YOUTUBE_RED, high_contrast,
 };
 use iced::{
     Background, Border, Color, Vector,
@@ -64,6 +64,9 @@ impl button::StyleSheet for AirshipperTheme {
     type Style = ButtonStyle;
 
     fn active(&self, style: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::button(style, false);
+        }
         match style {
             ButtonStyle::Download(download_button_style) => match download_button_style {
                 DownloadButtonStyle::Launch(ButtonState::Enabled) => {
@@ -96,6 +99,9 @@ impl button::StyleSheet for AirshipperTheme {
     }
 
     fn hovered(&self, style: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::button(style, true);
+        }
         match style {
             ButtonStyle::Download(download_button_style) => match download_button_style {
                 DownloadButtonStyle::Launch(ButtonState::Enabled) => {
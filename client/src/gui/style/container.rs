@@ -1,6 +1,7 @@
 use crate::gui::style::{
     AirshipperTheme, BACKGROUND_BLUE, BLOG_POST_BACKGROUND_BLUE, BRIGHT_ORANGE,
-    DARK_WHITE, LIGHT_GREY, LIME_GREEN, MEDIUM_GREY, NAVY_BLUE, VERY_DARK_GREY,
+    DARK_WHITE, LIGHT_GREY, LIME_GREEN, MEDIUM_GREY, NAVY_BLUE, TOMATO_RED, UiTheme,
+    VERY_DARK_GREY, high_contrast,
 };
 use iced::{
     Background, Border, Color,
@@ -13,6 +14,7 @@ pub enum ContainerStyle {
     Default,
     Dark,
     Announcement,
+    Maintenance,
     LoadingBlogPost,
     BlogPost,
     SidePanel,
@@ -26,9 +28,13 @@ impl container::StyleSheet for AirshipperTheme {
     type Style = ContainerStyle;
 
     fn appearance(&self, style: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::container(style);
+        }
         match style {
             ContainerStyle::Default => Appearance::default(),
             ContainerStyle::Announcement => announcement_container_style(),
+            ContainerStyle::Maintenance => maintenance_container_style(),
             ContainerStyle::Dark => dark_container_style(),
             ContainerStyle::LoadingBlogPost => loading_blogpost_container_style(),
             ContainerStyle::BlogPost => blogpost_container_style(),
@@ -57,6 +63,19 @@ fn announcement_container_style() -> Appearance {
     }
 }
 
+fn maintenance_container_style() -> Appearance {
+    Appearance {
+        background: Some(Background::Color(VERY_DARK_GREY)),
+        text_color: Some(Color::WHITE),
+        border: Border {
+            color: TOMATO_RED,
+            width: 2.0,
+            ..Default::default()
+        },
+        ..Appearance::default()
+    }
+}
+
 fn loading_blogpost_container_style() -> Appearance {
     Appearance {
         background: None,
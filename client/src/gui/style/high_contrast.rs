@@ -0,0 +1,184 @@
+//! The high contrast theme: white text on black, yellow for everything which can be
+//! pressed or needs attention, and visible borders instead of background shades.
+use crate::gui::style::{
+    button::{ButtonState, ButtonStyle, DownloadButtonStyle, ServerListEntryButtonState},
+    container::ContainerStyle,
+    text::TextStyle,
+};
+use iced::{Background, Border, Color, application, overlay, widget};
+
+const YELLOW: Color = Color::from_rgb(1.0, 0.84, 0.0);
+const LIGHT_RED: Color = Color::from_rgb(1.0, 0.45, 0.45);
+pub const PLACEHOLDER: Color = Color::from_rgb(0.75, 0.75, 0.75);
+pub const DISABLED: Color = Color::from_rgb(0.6, 0.6, 0.6);
+pub const SELECTION: Color = Color::from_rgb(0.0, 0.35, 0.8);
+const BORDER_WIDTH: f32 = 2.0;
+
+fn border(color: Color, radius: f32) -> Border {
+    Border {
+        color,
+        width: BORDER_WIDTH,
+        radius: radius.into(),
+    }
+}
+
+pub fn application() -> application::Appearance {
+    application::Appearance {
+        background_color: Color::BLACK,
+        text_color: Color::WHITE,
+    }
+}
+
+pub fn button(style: &ButtonStyle, hovered: bool) -> widget::button::Appearance {
+    let (background, text_color, border_color) = match style {
+        ButtonStyle::Download(
+            DownloadButtonStyle::Launch(ButtonState::Disabled)
+            | DownloadButtonStyle::Update(ButtonState::Disabled),
+        ) => (Color::BLACK, DISABLED, DISABLED),
+        ButtonStyle::Download(_) | ButtonStyle::ServerBrowser if hovered => {
+            (Color::WHITE, Color::BLACK, YELLOW)
+        },
+        ButtonStyle::Download(_)
+        | ButtonStyle::ServerBrowser
+        | ButtonStyle::ServerListEntry(ServerListEntryButtonState::Selected) => {
+            (YELLOW, Color::BLACK, Color::WHITE)
+        },
+        // buttons which are only text or an icon get a border once hovered
+        ButtonStyle::NextPrev
+        | ButtonStyle::Transparent
+        | ButtonStyle::Settings
+        | ButtonStyle::ColumnHeading => (
+            Color::BLACK,
+            Color::WHITE,
+            if hovered { YELLOW } else { Color::TRANSPARENT },
+        ),
+        ButtonStyle::AirshipperDownload
+        | ButtonStyle::ServerListEntry(ServerListEntryButtonState::NotSelected)
+        | ButtonStyle::Browser(_) => (
+            Color::BLACK,
+            Color::WHITE,
+            if hovered { YELLOW } else { Color::WHITE },
+        ),
+    };
+    let radius = match style {
+        ButtonStyle::Browser(_) | ButtonStyle::AirshipperDownload => 25.0,
+        ButtonStyle::ServerListEntry(_) | ButtonStyle::ColumnHeading => 0.0,
+        _ => 4.0,
+    };
+    widget::button::Appearance {
+        background: Some(Background::Color(background)),
+        text_color,
+        border: border(border_color, radius),
+        ..widget::button::Appearance::default()
+    }
+}
+
+pub fn container(style: &ContainerStyle) -> widget::container::Appearance {
+    let (background, text_color, border_color) = match style {
+        ContainerStyle::Default | ContainerStyle::ColumnHeading => {
+            return widget::container::Appearance {
+                text_color: Some(Color::WHITE),
+                ..widget::container::Appearance::default()
+            };
+        },
+        ContainerStyle::Announcement => (YELLOW, Color::BLACK, YELLOW),
+        ContainerStyle::Maintenance => (Color::BLACK, Color::WHITE, LIGHT_RED),
+        ContainerStyle::Dark | ContainerStyle::ChangelogHeader => {
+            (Color::BLACK, Color::WHITE, Color::TRANSPARENT)
+        },
+        ContainerStyle::LoadingBlogPost
+        | ContainerStyle::BlogPost
+        | ContainerStyle::SidePanel
+        | ContainerStyle::Tooltip
+        | ContainerStyle::ExtraBrowser => (Color::BLACK, Color::WHITE, Color::WHITE),
+    };
+    let radius = match style {
+        ContainerStyle::ExtraBrowser => 25.0,
+        _ => 0.0,
+    };
+    widget::container::Appearance {
+        background: Some(Background::Color(background)),
+        text_color: Some(text_color),
+        border: border(border_color, radius),
+        ..widget::container::Appearance::default()
+    }
+}
+
+pub fn text(style: TextStyle) -> widget::text::Appearance {
+    let color = match style {
+        TextStyle::Normal | TextStyle::Dark | TextStyle::LightGrey | TextStyle::Lilac => {
+            Color::WHITE
+        },
+        TextStyle::BrightOrange => YELLOW,
+        TextStyle::TomatoRed => LIGHT_RED,
+    };
+    widget::text::Appearance { color: Some(color) }
+}
+
+pub fn rule() -> widget::rule::Appearance {
+    widget::rule::Appearance {
+        width: BORDER_WIDTH as u16,
+        color: Color::WHITE,
+        radius: 0.0.into(),
+        fill_mode: widget::rule::FillMode::Full,
+    }
+}
+
+pub fn progress_bar() -> widget::progress_bar::Appearance {
+    widget::progress_bar::Appearance {
+        background: Background::Color(Color::from_rgb(0.3, 0.3, 0.3)),
+        bar: Background::Color(YELLOW),
+        border_radius: 0.0.into(),
+    }
+}
+
+pub fn pick_list(hovered: bool) -> widget::pick_list::Appearance {
+    widget::pick_list::Appearance {
+        text_color: Color::WHITE,
+        background: Background::Color(Color::BLACK),
+        border: border(if hovered { YELLOW } else { Color::WHITE }, 3.0),
+        handle_color: Color::WHITE,
+        placeholder_color: PLACEHOLDER,
+    }
+}
+
+pub fn menu() -> overlay::menu::Appearance {
+    overlay::menu::Appearance {
+        text_color: Color::WHITE,
+        background: Background::Color(Color::BLACK),
+        selected_background: Background::Color(YELLOW),
+        selected_text_color: Color::BLACK,
+        border: border(Color::WHITE, 0.0),
+    }
+}
+
+pub fn scrollable() -> widget::scrollable::Appearance {
+    widget::scrollable::Appearance {
+        container: widget::container::Appearance::default(),
+        scrollbar: widget::scrollable::Scrollbar {
+            background: Some(Background::Color(Color::BLACK)),
+            border: border(Color::WHITE, 0.0),
+            scroller: widget::scrollable::Scroller {
+                color: Color::WHITE,
+                border: Border::with_radius(5.0),
+            },
+        },
+        gap: None,
+    }
+}
+
+pub fn text_input(focused: bool) -> widget::text_input::Appearance {
+    widget::text_input::Appearance {
+        background: Background::Color(Color::BLACK),
+        border: border(if focused { YELLOW } else { Color::WHITE }, 3.0),
+        icon_color: Color::WHITE,
+    }
+}
+
+pub fn disabled_text_input() -> widget::text_input::Appearance {
+    widget::text_input::Appearance {
+        background: Background::Color(Color::BLACK),
+        border: border(DISABLED, 3.0),
+        icon_color: DISABLED,
+    }
+}
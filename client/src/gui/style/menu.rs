@@ -1,5 +1,6 @@
 use crate::gui::style::{
-    AirshipperTheme, LIGHT_NAVY_BLUE, NAVY_BLUE, pick_list::PickListStyle,
+    AirshipperTheme, LIGHT_NAVY_BLUE, NAVY_BLUE, UiTheme, high_contrast,
+    pick_list::PickListStyle,
 };
 use iced::{Background, Border, Color, overlay, overlay::menu::Appearance};
 
@@ -19,6 +20,9 @@ impl overlay::menu::StyleSheet for AirshipperTheme {
     type Style = MenuStyle;
 
     fn appearance(&self, _: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::menu();
+        }
         Appearance {
             text_color: Color::WHITE,
             background: Background::Color(NAVY_BLUE),
@@ -1,8 +1,10 @@
+use crate::profiles::UiTheme;
 use iced::{Color, application, application::Appearance};
 use lazy_static::lazy_static;
 
 pub mod button;
 pub mod container;
+mod high_contrast;
 pub mod menu;
 pub mod pick_list;
 pub mod progress_bar;
@@ -47,8 +49,17 @@ lazy_static! {
     static ref TWITCH_PURPLE: Color = rgb8(100, 65, 165);
 }
 
+/// The theme selected via [`crate::profiles::Profile::theme`].
+///
+/// Every `StyleSheet` impl in this module styles the default theme and hands the
+/// other variants to their own module, e.g. [`high_contrast`]. To add a theme, add a
+/// variant to [`UiTheme`] and `UI_THEMES`, write a module with an appearance function
+/// for every widget like `high_contrast.rs`, and call it from each `StyleSheet` impl
+/// next to the high contrast one.
 #[derive(Default)]
-pub struct AirshipperTheme {}
+pub struct AirshipperTheme {
+    pub variant: UiTheme,
+}
 
 #[derive(Default)]
 pub enum AirshipperThemeStyle {
@@ -60,6 +71,9 @@ impl application::StyleSheet for AirshipperTheme {
     type Style = AirshipperThemeStyle;
 
     fn appearance(&self, _: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::application();
+        }
         Appearance {
             background_color: Color::BLACK,
             text_color: Color::WHITE,
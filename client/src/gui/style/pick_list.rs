@@ -1,4 +1,6 @@
-use crate::gui::style::{AirshipperTheme, NAVY_BLUE, VERY_DARK_GREY};
+use crate::gui::style::{
+    AirshipperTheme, NAVY_BLUE, UiTheme, VERY_DARK_GREY, high_contrast,
+};
 use iced::{
     Background, Border, Color,
     widget::{
@@ -18,6 +20,9 @@ impl pick_list::StyleSheet for AirshipperTheme {
 
     // TODO: menu from old picklist style?
     fn active(&self, _: &<Self as StyleSheet>::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::pick_list(false);
+        }
         Appearance {
             text_color: Color::WHITE,
             background: Background::Color(NAVY_BLUE),
@@ -35,6 +40,9 @@ impl pick_list::StyleSheet for AirshipperTheme {
     }
 
     fn hovered(&self, style: &<Self as StyleSheet>::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::pick_list(true);
+        }
         self.active(style)
     }
 }
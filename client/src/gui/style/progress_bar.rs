@@ -1,4 +1,6 @@
-use crate::gui::style::{AirshipperTheme, LIME_GREEN, VERY_DARK_GREY};
+use crate::gui::style::{
+    AirshipperTheme, LIME_GREEN, UiTheme, VERY_DARK_GREY, high_contrast,
+};
 use iced::{
     Background,
     widget::{progress_bar, progress_bar::Appearance},
@@ -14,6 +16,9 @@ impl progress_bar::StyleSheet for AirshipperTheme {
     type Style = ProgressBarStyle;
 
     fn appearance(&self, style: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::progress_bar();
+        }
         match style {
             ProgressBarStyle::Default => default_progress_bar_style(),
         }
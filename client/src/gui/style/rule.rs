@@ -1,4 +1,4 @@
-use crate::gui::style::AirshipperTheme;
+use crate::gui::style::{AirshipperTheme, UiTheme, high_contrast};
 use iced::{
     Color,
     widget::{
@@ -17,6 +17,9 @@ impl rule::StyleSheet for AirshipperTheme {
     type Style = RuleStyle;
 
     fn appearance(&self, style: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::rule();
+        }
         match style {
             RuleStyle::Default => default_rule_style(),
         }
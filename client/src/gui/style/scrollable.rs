@@ -1,4 +1,6 @@
-use crate::gui::style::{ALMOST_BLACK, ALMOST_BLACK2, AirshipperTheme};
+use crate::gui::style::{
+    ALMOST_BLACK, ALMOST_BLACK2, AirshipperTheme, UiTheme, high_contrast,
+};
 use iced::{
     Background, Border, Color,
     widget::{
@@ -17,6 +19,9 @@ impl scrollable::StyleSheet for AirshipperTheme {
     type Style = ScrollableStyle;
 
     fn active(&self, _: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::scrollable();
+        }
         Appearance {
             container: container::Appearance::default(),
             scrollbar: Scrollbar {
@@ -40,6 +45,9 @@ impl scrollable::StyleSheet for AirshipperTheme {
     }
 
     fn hovered(&self, _: &Self::Style, _: bool) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::scrollable();
+        }
         Appearance {
             container: container::Appearance::default(),
             scrollbar: Scrollbar {
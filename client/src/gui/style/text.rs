@@ -1,5 +1,6 @@
 use crate::gui::style::{
-    AirshipperTheme, BRIGHT_ORANGE, DARK_WHITE, LIGHT_GREY, LILAC, TOMATO_RED,
+    AirshipperTheme, BRIGHT_ORANGE, DARK_WHITE, LIGHT_GREY, LILAC, TOMATO_RED, UiTheme,
+    high_contrast,
 };
 use iced::{
     Color,
@@ -21,6 +22,9 @@ impl text::StyleSheet for AirshipperTheme {
     type Style = TextStyle;
 
     fn appearance(&self, style: Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::text(style);
+        }
         match style {
             TextStyle::Normal => text_appearance(Color::WHITE),
             TextStyle::Dark => text_appearance(DARK_WHITE),
@@ -1,5 +1,6 @@
 use crate::gui::style::{
     AirshipperTheme, CORNFLOWER_BLUE, DARK_WHITE, LIGHT_GREY, MEDIUM_GREY, NAVY_BLUE,
+    UiTheme, high_contrast,
 };
 use iced::{
     Background, Border, Color,
@@ -16,6 +17,9 @@ impl text_input::StyleSheet for AirshipperTheme {
     type Style = TextInputStyle;
 
     fn active(&self, _: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::text_input(false);
+        }
         Appearance {
             background: Background::Color(NAVY_BLUE),
             border: Border {
@@ -28,26 +32,44 @@ impl text_input::StyleSheet for AirshipperTheme {
     }
 
     fn focused(&self, style: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::text_input(true);
+        }
         self.active(style)
     }
 
     fn placeholder_color(&self, _: &Self::Style) -> Color {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::PLACEHOLDER;
+        }
         MEDIUM_GREY
     }
 
     fn value_color(&self, _: &Self::Style) -> Color {
+        if self.variant == UiTheme::HighContrast {
+            return Color::WHITE;
+        }
         LIGHT_GREY
     }
 
     fn selection_color(&self, _: &Self::Style) -> Color {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::SELECTION;
+        }
         CORNFLOWER_BLUE
     }
 
     fn disabled_color(&self, _style: &Self::Style) -> Color {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::DISABLED;
+        }
         MEDIUM_GREY
     }
 
     fn disabled(&self, _style: &Self::Style) -> Appearance {
+        if self.variant == UiTheme::HighContrast {
+            return high_contrast::disabled_text_input();
+        }
         Appearance {
             background: Background::Color(MEDIUM_GREY),
             border: Border {
@@ -19,16 +19,19 @@ use iced::{
 pub fn stream(
     profile: Profile,
     game_server_address: Option<String>,
+    safe_mode: bool,
 ) -> Subscription<io::ProcessUpdate> {
     Subscription::from_recipe(Process {
         profile,
         game_server_address,
+        safe_mode,
     })
 }
 
 struct Process {
     profile: Profile,
     game_server_address: Option<String>,
+    safe_mode: bool,
 }
 
 impl Recipe for Process {
@@ -41,8 +44,18 @@ impl Recipe for Process {
     }
 
     fn stream(self: Box<Self>, _input: EventStream) -> BoxStream<'static, Self::Output> {
-        let mut cmd = Profile::start(&self.profile, self.game_server_address.as_deref());
-        match io::stream_process(&mut cmd) {
+        if self.safe_mode
+            && let Err(e) = self.profile.reset_safe_mode_settings()
+        {
+            tracing::warn!(?e, "Failed to reset the safe mode settings");
+        }
+        let stream = Profile::start(
+            &self.profile,
+            self.game_server_address.as_deref(),
+            self.safe_mode,
+        )
+        .and_then(|mut cmd| Ok(io::stream_process(&mut cmd)?));
+        match stream {
             Ok(stream) => stream.boxed(),
             Err(err) => {
                 let msg = err.to_string();
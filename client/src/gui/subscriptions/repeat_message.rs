@@ -16,6 +16,15 @@ where
     Subscription::from_recipe(RepeatMessageStream(interval, message))
 }
 
+/// Like [`stream`], but the first message is sent after `interval` instead of right
+/// away. Streams of different messages run independently, even with equal intervals.
+pub fn delayed_stream<M>(interval: Duration, message: M) -> iced::Subscription<M>
+where
+    M: Clone + Hash + Send + Sync + 'static,
+{
+    Subscription::from_recipe(DelayedMessageStream(interval, message))
+}
+
 struct RepeatMessageStream<M>(Duration, M);
 
 impl<M> Recipe for RepeatMessageStream<M>
@@ -39,3 +48,32 @@ where
         ))
     }
 }
+
+struct DelayedMessageStream<M>(Duration, M);
+
+impl<M> Recipe for DelayedMessageStream<M>
+where
+    M: Clone + Hash + Send + Sync + 'static,
+{
+    type Output = M;
+
+    fn hash(&self, state: &mut Hasher) {
+        core::any::TypeId::of::<DelayedMessageStream<M>>().hash(state);
+        self.0.hash(state);
+        self.1.hash(state);
+    }
+
+    fn stream(self: Box<Self>, _input: EventStream) -> BoxStream<'static, Self::Output> {
+        let mut interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + self.0, self.0);
+        // e.g. after the system was suspended, a single message is enough
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        Box::pin(futures::stream::unfold(
+            (interval, self.1.clone()),
+            |(mut interval, message)| async move {
+                interval.tick().await;
+                Some((message.clone(), (interval, message)))
+            },
+        ))
+    }
+}
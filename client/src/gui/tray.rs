@@ -0,0 +1,173 @@
+//! System tray icon which keeps the launcher reachable while its window is hidden,
+//! e.g. while an update is downloaded in the background.
+use std::{
+    sync::mpsc::{self, RecvTimeoutError},
+    time::Duration,
+};
+use tray_icon::{
+    Icon, MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent,
+    menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem},
+};
+
+/// how often the tray thread handles its platform events and status changes
+const TRAY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// how often the GUI looks for clicks on the tray icon or its menu
+pub const TRAY_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    ToggleWindow,
+    Quit,
+}
+
+/// Handle to the tray icon. The icon lives on its own thread, as it needs a platform
+/// event loop which iced does not provide, and is removed once the handle is dropped.
+#[derive(Debug, Clone)]
+pub struct Tray {
+    status: mpsc::Sender<String>,
+    last_status: String,
+    toggle_id: MenuId,
+    quit_id: MenuId,
+}
+
+impl Tray {
+    pub fn spawn() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            // the tray icon would have to be created on the main thread, which is
+            // owned by iced
+            tracing::warn!("The tray icon is not supported on macOS");
+            return None;
+        }
+
+        let (status_tx, status_rx) = mpsc::channel::<String>();
+        let (ids_tx, ids_rx) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("tray".to_string())
+            .spawn(move || run(status_rx, ids_tx));
+        if let Err(e) = thread {
+            tracing::warn!(?e, "Failed to start the tray thread");
+            return None;
+        }
+
+        // fails if the thread gave up on creating the icon
+        let (toggle_id, quit_id) = ids_rx.recv().ok()?;
+        Some(Self {
+            status: status_tx,
+            last_status: String::new(),
+            toggle_id,
+            quit_id,
+        })
+    }
+
+    /// Shows what the launcher is doing in the tray menu and tooltip.
+    pub fn set_status(&mut self, status: String) {
+        if status != self.last_status {
+            let _ = self.status.send(status.clone());
+            self.last_status = status;
+        }
+    }
+
+    /// Clicks on the tray icon or its menu since the last call.
+    pub fn actions(&self) -> Vec<TrayAction> {
+        let menu = MenuEvent::receiver().try_iter().filter_map(|event| {
+            if event.id == self.toggle_id {
+                Some(TrayAction::ToggleWindow)
+            } else if event.id == self.quit_id {
+                Some(TrayAction::Quit)
+            } else {
+                None
+            }
+        });
+        let icon = TrayIconEvent::receiver().try_iter().filter_map(|event| {
+            matches!(event, TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            })
+            .then_some(TrayAction::ToggleWindow)
+        });
+        menu.chain(icon).collect()
+    }
+}
+
+/// Creates the tray icon and keeps it alive until every [`Tray`] handle is dropped.
+fn run(status_rx: mpsc::Receiver<String>, ids_tx: mpsc::Sender<(MenuId, MenuId)>) {
+    #[cfg(target_os = "linux")]
+    if let Err(e) = gtk::init() {
+        tracing::warn!(?e, "Failed to initialize GTK for the tray icon");
+        return;
+    }
+
+    let status = MenuItem::new("Idle", false, None);
+    let toggle = MenuItem::new("Show/Hide", true, None);
+    let quit = MenuItem::new("Quit", true, None);
+    let menu = Menu::new();
+    let tray = menu
+        .append_items(&[&status, &PredefinedMenuItem::separator(), &toggle, &quit])
+        .map_err(|e| e.to_string())
+        .and_then(|()| icon())
+        .and_then(|icon| {
+            TrayIconBuilder::new()
+                .with_menu(Box::new(menu))
+                .with_tooltip("Airshipper")
+                .with_icon(icon)
+                .build()
+                .map_err(|e| e.to_string())
+        });
+    let tray = match tray {
+        Ok(tray) => tray,
+        Err(e) => {
+            tracing::warn!(?e, "Failed to create the tray icon");
+            return;
+        },
+    };
+    let _ = ids_tx.send((toggle.id().clone(), quit.id().clone()));
+
+    loop {
+        pump_events();
+        match status_rx.recv_timeout(TRAY_POLL_INTERVAL) {
+            Ok(text) => {
+                status.set_text(&text);
+                if let Err(e) = tray.set_tooltip(Some(format!("Airshipper: {text}"))) {
+                    tracing::debug!(?e, "Failed to set the tray tooltip");
+                }
+            },
+            Err(RecvTimeoutError::Timeout) => {},
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+fn icon() -> Result<Icon, String> {
+    let icon = image::load_from_memory(crate::assets::VELOREN_ICON)
+        .map_err(|e| e.to_string())?
+        .to_rgba8();
+    let (width, height) = icon.dimensions();
+    Icon::from_rgba(icon.into_raw(), width, height).map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn pump_events() {
+    while gtk::events_pending() {
+        gtk::main_iteration_do(false);
+    }
+}
+
+#[cfg(windows)]
+fn pump_events() {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DispatchMessageW, MSG, PM_REMOVE, PeekMessageW, TranslateMessage,
+    };
+
+    // SAFETY: `msg` is only read after PeekMessageW filled it
+    unsafe {
+        let mut msg: MSG = std::mem::zeroed();
+        while PeekMessageW(&mut msg, std::ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn pump_events() {}
@@ -5,17 +5,18 @@ use crate::{
             AnnouncementPanelComponent, AnnouncementPanelMessage,
             ChangelogPanelComponent, ChangelogPanelMessage, CommunityShowcaseComponent,
             CommunityShowcasePanelMessage, GamePanelComponent, GamePanelMessage,
-            LogoPanelComponent, NewsPanelComponent, NewsPanelMessage,
-            SERVER_BROWSER_PING_REFRESH, ServerBrowserPanelComponent,
+            LogoPanelComponent, MAINTENANCE_COUNTDOWN_REFRESH, NewsPanelComponent,
+            NewsPanelMessage, SERVER_BROWSER_PING_REFRESH, ServerBrowserPanelComponent,
             ServerBrowserPanelMessage, SettingsPanelComponent, SettingsPanelMessage,
+            TOAST_EXPIRY_CHECK, ToastPanelComponent, ToastPanelMessage, ToastSeverity,
         },
-        rss_feed::RssFeedComponentMessage::UpdateRssFeed,
+        rss_feed::RssFeedComponentMessage::{self, UpdateRssFeed},
         style::container::ContainerStyle,
         subscriptions,
         views::Action,
         widget::*,
     },
-    profiles::Profile,
+    profiles::{Profile, RefreshIntervals},
 };
 
 use iced::{
@@ -36,6 +37,7 @@ pub struct DefaultView {
     news_panel_component: NewsPanelComponent,
     settings_panel_component: SettingsPanelComponent,
     server_browser_panel_component: ServerBrowserPanelComponent,
+    toast_panel_component: ToastPanelComponent,
     show_settings: bool,
     show_server_browser: bool,
 }
@@ -45,6 +47,15 @@ pub enum DefaultViewMessage {
     // Messages
     Action(Action),
     Query,
+    /// refreshes a panel in the background, see [`RefreshIntervals`]
+    Refresh(RefreshPanel),
+    /// a non-fatal error which is shown to the user for a few seconds
+    Toast(ToastSeverity, String),
+    /// the clipboard content after copying `expected`, `None` if it is unavailable
+    ClipboardChecked {
+        expected: String,
+        content: Option<String>,
+    },
 
     #[cfg(windows)]
     LauncherUpdate(Result<Option<self_update::update::Release>>),
@@ -60,6 +71,15 @@ pub enum DefaultViewMessage {
     NewsPanel(NewsPanelMessage),
     SettingsPanel(SettingsPanelMessage),
     ServerBrowserPanel(ServerBrowserPanelMessage),
+    ToastPanel(ToastPanelMessage),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RefreshPanel {
+    News,
+    Community,
+    Announcements,
+    Changelog,
 }
 
 #[derive(Debug, Clone)]
@@ -67,12 +87,22 @@ pub enum Interaction {
     SettingsPressed,
     ToggleServerBrowser,
     OpenURL(String),
+    OpenInstallDir,
+    OpenLogsDir,
+    /// copies the address of the server selected in the server browser
+    CopyServerAddress,
+    /// e.g. a link which couldn't be opened
+    CopyText(String),
 }
 
 impl DefaultView {
-    pub fn subscription(&self) -> iced::Subscription<DefaultViewMessage> {
+    pub fn subscription(
+        &self,
+        active_profile: &Profile,
+    ) -> iced::Subscription<DefaultViewMessage> {
         iced::Subscription::batch(
             IntoIterator::into_iter([
+                Some(self.refresh_subscription(&active_profile.refresh_intervals)),
                 Some(
                     self.game_panel_component
                         .subscription()
@@ -86,11 +116,54 @@ impl DefaultView {
                         ),
                     ),
                 ),
+                self.announcement_panel_component
+                    .maintenance_pending()
+                    .then(|| {
+                        subscriptions::repeat_message::stream(
+                            MAINTENANCE_COUNTDOWN_REFRESH,
+                            DefaultViewMessage::AnnouncementPanel(
+                                AnnouncementPanelMessage::Tick,
+                            ),
+                        )
+                    }),
+                (!self.toast_panel_component.is_empty()).then(|| {
+                    subscriptions::repeat_message::stream(
+                        TOAST_EXPIRY_CHECK,
+                        DefaultViewMessage::ToastPanel(ToastPanelMessage::Expire),
+                    )
+                }),
             ])
             .flatten(),
         )
     }
 
+    /// Refreshes the panels periodically, paused while an update takes the bandwidth.
+    fn refresh_subscription(
+        &self,
+        intervals: &RefreshIntervals,
+    ) -> iced::Subscription<DefaultViewMessage> {
+        if !intervals.enabled || self.game_panel_component.is_updating() {
+            return iced::Subscription::none();
+        }
+        iced::Subscription::batch(
+            [
+                (RefreshPanel::News, intervals.news),
+                (RefreshPanel::Community, intervals.community),
+                (RefreshPanel::Announcements, intervals.announcements),
+                (RefreshPanel::Changelog, intervals.changelog),
+            ]
+            .into_iter()
+            .filter(|(_, minutes)| *minutes > 0)
+            .map(|(panel, minutes)| {
+                subscriptions::repeat_message::delayed_stream(
+                    RefreshIntervals::period(minutes),
+                    panel,
+                )
+                .map(DefaultViewMessage::Refresh)
+            }),
+        )
+    }
+
     pub fn view<'a>(
         &'a self,
         active_profile: &'a Profile,
@@ -104,6 +177,7 @@ impl DefaultView {
             game_panel_component,
             settings_panel_component,
             server_browser_panel_component,
+            toast_panel_component,
             ..
         } = self;
 
@@ -116,10 +190,14 @@ impl DefaultView {
         let left = container(
             column![]
                 .push(container(logo_panel_component.view()).height(Length::Fill))
+                .push(toast_panel_component.view())
                 .push(container(left_middle_contents).height(Length::Shrink))
                 .push(
-                    container(game_panel_component.view(active_profile))
-                        .height(Length::Shrink),
+                    container(game_panel_component.view(
+                        active_profile,
+                        announcement_panel_component.launch_disabled(),
+                    ))
+                    .height(Length::Shrink),
                 ),
         )
         .height(Length::Fill)
@@ -148,9 +226,10 @@ impl DefaultView {
 
             main_row = main_row.push(middle).push(right);
         } else {
-            let server_browser = container(server_browser_panel_component.view())
-                .height(Length::Fill)
-                .width(Length::Fill);
+            let server_browser =
+                container(server_browser_panel_component.view(active_profile))
+                    .height(Length::Fill)
+                    .width(Length::Fill);
             main_row = main_row.push(server_browser);
         }
 
@@ -160,6 +239,17 @@ impl DefaultView {
             .into()
     }
 
+    /// Overall progress of a running update, e.g. for the window title
+    pub fn update_progress(&self) -> Option<String> {
+        self.game_panel_component.update_progress()
+    }
+
+    /// Short description of what the launcher is doing, e.g. for the tray icon
+    #[cfg(feature = "tray")]
+    pub fn status(&self) -> String {
+        self.game_panel_component.status()
+    }
+
     pub fn update(
         &mut self,
         msg: DefaultViewMessage,
@@ -169,6 +259,9 @@ impl DefaultView {
             // Messages
             // Will be handled by main view
             DefaultViewMessage::Action(_) => {},
+            DefaultViewMessage::Toast(severity, text) => {
+                self.toast_panel_component.push(severity, text);
+            },
             DefaultViewMessage::Query => {
                 let channel = active_profile.channel.clone();
                 let api_version_url = active_profile.api_version_url();
@@ -232,6 +325,43 @@ impl DefaultView {
                 ]);
             },
 
+            DefaultViewMessage::Refresh(panel) => {
+                tracing::debug!(?panel, "Refreshing panel");
+                return match panel {
+                    RefreshPanel::News => Command::perform(async {}, |_| {
+                        DefaultViewMessage::NewsPanel(NewsPanelMessage::RssUpdate(
+                            RssFeedComponentMessage::Refresh,
+                        ))
+                    }),
+                    RefreshPanel::Community => Command::perform(async {}, |_| {
+                        DefaultViewMessage::CommunityShowcasePanel(
+                            CommunityShowcasePanelMessage::RssUpdate(
+                                RssFeedComponentMessage::Refresh,
+                            ),
+                        )
+                    }),
+                    RefreshPanel::Announcements => Command::perform(
+                        AnnouncementPanelComponent::fetch(
+                            active_profile.api_version_url(),
+                            active_profile.announcement_url(),
+                        ),
+                        |update| {
+                            DefaultViewMessage::AnnouncementPanel(
+                                AnnouncementPanelMessage::FetchAnnouncement(update),
+                            )
+                        },
+                    ),
+                    RefreshPanel::Changelog => {
+                        let channel = active_profile.channel.clone();
+                        Command::perform(async {}, move |_| {
+                            DefaultViewMessage::ChangelogPanel(
+                                ChangelogPanelMessage::Refresh(channel),
+                            )
+                        })
+                    },
+                };
+            },
+
             DefaultViewMessage::GamePanel(msg) => {
                 if let Some(command) =
                     self.game_panel_component.update(msg, active_profile)
@@ -268,7 +398,15 @@ impl DefaultView {
                 }
             },
             DefaultViewMessage::ServerBrowserPanel(msg) => {
-                if let Some(command) = self.server_browser_panel_component.update(msg) {
+                if let Some(command) = self
+                    .server_browser_panel_component
+                    .update(msg, active_profile)
+                {
+                    return command;
+                }
+            },
+            DefaultViewMessage::ToastPanel(msg) => {
+                if let Some(command) = self.toast_panel_component.update(msg) {
                     return command;
                 }
             },
@@ -287,6 +425,17 @@ impl DefaultView {
             DefaultViewMessage::Interaction(interaction) => match interaction {
                 Interaction::SettingsPressed => {
                     self.show_settings = !self.show_settings;
+
+                    if self.show_settings {
+                        return Command::perform(
+                            crate::fs::install_size(active_profile.directory()),
+                            |size| {
+                                DefaultViewMessage::SettingsPanel(
+                                    SettingsPanelMessage::InstallSizeLoaded(size),
+                                )
+                            },
+                        );
+                    }
                 },
                 Interaction::ToggleServerBrowser => {
                     self.show_server_browser = !self.show_server_browser;
@@ -304,16 +453,88 @@ impl DefaultView {
                     }
                 },
                 Interaction::OpenURL(url) => {
-                    if let Err(e) = opener::open(url) {
-                        tracing::error!(
-                            "Failed to open gitlab changelog website: {:?}",
-                            e
+                    if let Err(e) = crate::io::browser::open_url(
+                        &url,
+                        &active_profile.browser_command,
+                    ) {
+                        tracing::error!(?e, ?url, "Failed to open the website");
+                        self.toast_panel_component.push_with_action(
+                            ToastSeverity::Error,
+                            format!(
+                                "Could not open {url}, set a browser in the settings"
+                            ),
+                            "Copy link",
+                            DefaultViewMessage::Interaction(Interaction::CopyText(url)),
                         );
                     }
                 },
+                Interaction::OpenInstallDir => {
+                    self.open_folder(active_profile.directory())
+                },
+                Interaction::OpenLogsDir => {
+                    self.open_folder(active_profile.voxygen_logs_path())
+                },
+                Interaction::CopyServerAddress => {
+                    match self.game_panel_component.selected_server() {
+                        Some(address) => return copy_to_clipboard(address.to_owned()),
+                        None => self.toast_panel_component.push(
+                            ToastSeverity::Warning,
+                            "Select a server to copy its address".to_string(),
+                        ),
+                    }
+                },
+                Interaction::CopyText(text) => return copy_to_clipboard(text),
+            },
+            DefaultViewMessage::ClipboardChecked { expected, content } => {
+                if content.as_ref() != Some(&expected) {
+                    tracing::warn!("The clipboard is not available");
+                    self.toast_panel_component.push(
+                        ToastSeverity::Warning,
+                        format!(
+                            "Could not copy to the clipboard, the address is {expected}"
+                        ),
+                    );
+                }
             },
         }
 
         Command::none()
     }
+
+    fn open_folder(&mut self, path: std::path::PathBuf) {
+        if !path.exists() {
+            // e.g. the logs folder before the game was started for the first time
+            tracing::warn!(?path, "Folder does not exist yet");
+            self.toast_panel_component.push(
+                ToastSeverity::Warning,
+                format!("{} does not exist yet", path.display()),
+            );
+            return;
+        }
+        if let Err(e) = opener::open(&path) {
+            tracing::error!(?e, ?path, "Failed to open folder");
+            #[cfg(target_os = "linux")]
+            tracing::info!(
+                "No file manager seems to be configured. Install one or set a default \
+                 with `xdg-mime default <file-manager>.desktop inode/directory`, the \
+                 folder is located at {}",
+                path.display()
+            );
+            self.toast_panel_component.push(
+                ToastSeverity::Error,
+                format!("Could not open {}", path.display()),
+            );
+        }
+    }
+}
+
+/// Copies `text` and reads it back, as iced doesn't report whether writing worked.
+fn copy_to_clipboard(text: String) -> Command<DefaultViewMessage> {
+    Command::batch([
+        iced::clipboard::write(text.clone()),
+        iced::clipboard::read(move |content| DefaultViewMessage::ClipboardChecked {
+            expected: text.clone(),
+            content,
+        }),
+    ])
 }
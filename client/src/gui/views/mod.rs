@@ -15,7 +15,7 @@ pub enum View {
 /// An action requested by the current view
 #[derive(Debug, Clone)]
 pub enum Action {
-    UpdateProfile(Profile),
+    UpdateProfile(Box<Profile>),
     #[cfg(windows)] // for now
     SwitchView(View),
     #[cfg(windows)]
@@ -0,0 +1,147 @@
+use crate::{ClientError, Result};
+use std::process::Command;
+
+/// replaced with the link in the browser command, appended if it is missing
+const BROWSER_URL: &str = "{url}";
+
+/// Opens `url` in a browser. A configured `browser_command` is tried first, then the
+/// system default via opener and, on Linux, `xdg-open` directly, as some setups have
+/// no default browser set but still a working `xdg-open`. Fails only if all of them
+/// failed.
+pub fn open_url(url: &str, browser_command: &str) -> Result<()> {
+    let mut errors = Vec::new();
+
+    if let Some(command) = browser_args(browser_command, url) {
+        match spawn(&command) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                tracing::warn!(?e, ?command, "Failed to start the browser command");
+                errors.push(format!("{}: {e}", command[0]));
+            },
+        }
+    }
+
+    match opener::open(url) {
+        Ok(()) => return Ok(()),
+        Err(e) => {
+            tracing::debug!(?e, "Failed to open the default browser");
+            errors.push(e.to_string());
+        },
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    match spawn(&["xdg-open".to_string(), url.to_string()]) {
+        Ok(()) => return Ok(()),
+        Err(e) => errors.push(format!("xdg-open: {e}")),
+    }
+
+    Err(ClientError::Opener(errors.join(", ")))
+}
+
+/// The program and arguments of `browser_command` to open `url`, `None` if no command
+/// is set. Arguments are quoted like in a shell, see [`split_args`].
+fn browser_args(browser_command: &str, url: &str) -> Option<Vec<String>> {
+    let mut args: Vec<String> = split_args(browser_command)
+        .into_iter()
+        .map(|arg| arg.replace(BROWSER_URL, url))
+        .collect();
+    if args.is_empty() {
+        return None;
+    }
+    if !browser_command.contains(BROWSER_URL) {
+        args.push(url.to_string());
+    }
+    Some(args)
+}
+
+/// Splits `command` at whitespace outside of quotes, so paths with spaces can be
+/// quoted: `'...'` is taken literally, within `"..."` and outside of quotes a backslash
+/// escapes the next character. Backslashes are kept on Windows, where they separate
+/// paths. An unterminated quote lasts until the end.
+fn split_args(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg: Option<String> = None;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => arg.get_or_insert_default().push(c),
+            (_, '\\') if cfg!(not(windows)) => {
+                let arg = arg.get_or_insert_default();
+                match chars.next() {
+                    // only quotes and backslashes are escaped within double quotes
+                    Some(c @ ('"' | '\\')) if quote.is_some() => arg.push(c),
+                    Some(c) if quote.is_some() => arg.extend(['\\', c]),
+                    Some(c) => arg.push(c),
+                    None => arg.push('\\'),
+                }
+            },
+            (Some(_), c) => arg.get_or_insert_default().push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                arg.get_or_insert_default();
+            },
+            (None, c) if c.is_whitespace() => args.extend(arg.take()),
+            (None, c) => arg.get_or_insert_default().push(c),
+        }
+    }
+    args.extend(arg);
+    args
+}
+
+/// The browser isn't waited for, it might keep running until it is closed. A thread
+/// waits for it instead, so it doesn't linger as a zombie process once it exited.
+fn spawn(command: &[String]) -> std::io::Result<()> {
+    let mut child = Command::new(&command[0]).args(&command[1..]).spawn()?;
+    let program = command[0].clone();
+    std::thread::spawn(move || match child.wait() {
+        Ok(status) if !status.success() => {
+            tracing::warn!(?program, %status, "The browser exited with an error")
+        },
+        Ok(_) => {},
+        Err(e) => tracing::debug!(?e, ?program, "Failed to wait for the browser"),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_args() {
+        let url = "https://veloren.net";
+        assert_eq!(browser_args("  ", url), None);
+        assert_eq!(browser_args("firefox --new-tab", url).unwrap(), [
+            "firefox",
+            "--new-tab",
+            url
+        ]);
+        assert_eq!(
+            browser_args("chromium --app={url} --incognito", url).unwrap(),
+            ["chromium", "--app=https://veloren.net", "--incognito"]
+        );
+        assert_eq!(
+            browser_args("'/opt/My Browser/browser' --profile \"a b\"", url).unwrap(),
+            ["/opt/My Browser/browser", "--profile", "a b", url]
+        );
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(split_args(r#"a "" 'b c'd "e f""#), ["a", "", "b cd", "e f"]);
+        assert_eq!(split_args("'unterminated quote"), ["unterminated quote"]);
+        #[cfg(not(windows))]
+        assert_eq!(split_args(r#"My\ Browser "\x\"" '\"'"#), [
+            "My Browser",
+            "\\x\"",
+            "\\\""
+        ]);
+        #[cfg(windows)]
+        assert_eq!(split_args(r#""C:\Program Files\browser.exe" --new"#), [
+            r"C:\Program Files\browser.exe",
+            "--new"
+        ]);
+    }
+}
@@ -1,47 +1,255 @@
 //! Deals with all filesystem specific details
 
-use crate::consts;
+use crate::{Result, consts};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
 use ron::ser::PrettyConfig;
+use serde::{Serialize, de::DeserializeOwned};
 use std::{
-    io::Write,
+    io::{Read, Write},
     path::{Path, PathBuf},
+    sync::{
+        Mutex, OnceLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
 };
 
 lazy_static::lazy_static! {
-    // Base for config, profiles, ...
+    // Base for profiles, logs, ...
     pub static ref BASE_PATH: PathBuf = base();
+    // Base for the saved state
+    pub static ref CONFIG_PATH: PathBuf = config();
+    // Downloaded files which can be fetched again
+    static ref CACHE_PATH: PathBuf = cache();
 }
 
-/// Returns the base path where all airshipper files like config, profiles belong.
+/// Directories which could not be used, kept until the logger is set up as the base
+/// path is resolved before it.
+static DIR_FALLBACKS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Creates and returns the first of `candidates` which can be created, `None` stands
+/// for a directory the OS doesn't provide. A directory in the temp dir is the last
+/// resort, so containers and sandboxes without the usual OS directories still work.
+fn usable_dir(
+    purpose: &str,
+    candidates: impl IntoIterator<Item = Option<PathBuf>>,
+) -> PathBuf {
+    for candidate in candidates {
+        let Some(dir) = candidate else {
+            record_fallback(format!("No OS directory for the {purpose} available"));
+            continue;
+        };
+        match std::fs::create_dir_all(&dir) {
+            Ok(()) => return dir,
+            Err(e) => record_fallback(format!(
+                "Failed to create the {purpose} directory {}: {e}",
+                dir.display()
+            )),
+        }
+    }
+    private_dir_in(&std::env::temp_dir())
+}
+
+/// Directory in `temp` only the current user can access. The temp dir is shared by all
+/// users on unix, so the directory name includes the uid, it's created with mode 0700
+/// and isn't used if it belongs to someone else or is a symlink. A fresh directory is
+/// created instead then.
+#[cfg(unix)]
+fn private_dir_in(temp: &Path) -> PathBuf {
+    use std::os::unix::fs::DirBuilderExt;
+
+    let uid = match current_uid(temp) {
+        Ok(uid) => uid,
+        Err(e) => {
+            record_fallback(format!(
+                "Failed to determine the user id in {}: {e}",
+                temp.display()
+            ));
+            return temp.join(format!("airshipper-{}", std::process::id()));
+        },
+    };
+    let dir = temp.join(format!("airshipper-{uid}"));
+    let Err(e) = claim_private_dir(&dir, uid) else {
+        return dir;
+    };
+    record_fallback(format!("Can't use the directory {}: {e}", dir.display()));
+    let mut fresh = dir.clone();
+    for _ in 0..16 {
+        fresh = temp.join(format!("airshipper-{uid}-{:08x}", rand::random::<u32>()));
+        if std::fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&fresh)
+            .is_ok()
+        {
+            break;
+        }
+    }
+    fresh
+}
+
+/// The temp dir belongs to the current user on other platforms.
+#[cfg(not(unix))]
+fn private_dir_in(temp: &Path) -> PathBuf {
+    let dir = temp.join("airshipper");
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        record_fallback(format!(
+            "Failed to create the directory {}: {e}",
+            dir.display()
+        ));
+    }
+    dir
+}
+
+/// Owner of a file newly created in `dir`, which is the current user.
+#[cfg(unix)]
+fn current_uid(dir: &Path) -> std::io::Result<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    let probe = dir.join(format!(
+        ".airshipper-uid-{}-{:08x}",
+        std::process::id(),
+        rand::random::<u32>()
+    ));
+    let uid = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)?
+        .metadata()?
+        .uid();
+    let _ = std::fs::remove_file(&probe);
+    Ok(uid)
+}
+
+/// Creates `dir` with mode 0700, or checks that the existing one is a directory owned
+/// by `uid` and makes it private.
+#[cfg(unix)]
+fn claim_private_dir(dir: &Path, uid: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => return Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {},
+        Err(e) => return Err(e),
+    }
+    let meta = std::fs::symlink_metadata(dir)?;
+    if !meta.is_dir() || meta.uid() != uid {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "not a directory owned by the current user",
+        ));
+    }
+    if meta.mode() & 0o077 != 0 {
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+fn record_fallback(msg: String) {
+    let msg = format!("{msg}, using a fallback");
+    tracing::warn!("{msg}");
+    DIR_FALLBACKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(msg);
+}
+
+/// Logs the directories which had to be replaced by a fallback before the logger was
+/// set up.
+pub fn log_dir_fallbacks() {
+    for msg in DIR_FALLBACKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain(..)
+    {
+        tracing::warn!("{msg}");
+    }
+}
+
+/// Directory next to the executable, for environments without OS directories.
+fn next_to_executable(name: &str) -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.join(name))
+}
+
+/// Returns the base path where all airshipper data files like profiles and logs belong.
 ///
 /// |Platform | Example                                                       |
 /// | ------- | ------------------------------------------------------------- |
 /// | Linux   | /home/alice/.local/share/barapp                               |
 /// | macOS   | /Users/Alice/Library/Application Support/com.Foo-Corp.Bar-App |
 /// | Windows | C:\Users\Alice\AppData\Roaming                                |
+///
+/// Falls back to `airshipper-data` next to the executable, then to the temp dir.
 fn base() -> PathBuf {
-    let path = std::env::var("AIRSHIPPER_ROOT").map_or_else(
-        |_| {
-            dirs::data_dir()
-                .expect("Couldn't locate where to put launcher data!")
-                .join("airshipper")
-        },
-        PathBuf::from,
-    );
-    std::fs::create_dir_all(&path).expect("failed to create data directory!");
+    let preferred = match std::env::var("AIRSHIPPER_ROOT") {
+        Ok(path) => Some(PathBuf::from(path)),
+        Err(_) => dirs::data_dir().map(|dir| dir.join("airshipper")),
+    };
+    usable_dir("launcher data", [
+        preferred,
+        next_to_executable("airshipper-data"),
+    ])
+}
+
+/// Returns the path where the airshipper configuration belongs.
+/// Falls back to the base path if only `AIRSHIPPER_ROOT` is set, so portable setups
+/// keep everything in one place.
+///
+/// |Platform | Example                                                       |
+/// | ------- | ------------------------------------------------------------- |
+/// | Linux   | /home/alice/.config/barapp                                    |
+/// | macOS   | /Users/Alice/Library/Application Support/com.Foo-Corp.Bar-App |
+/// | Windows | C:\Users\Alice\AppData\Roaming                                |
+fn config() -> PathBuf {
+    let preferred = match std::env::var("AIRSHIPPER_CONFIG") {
+        Ok(path) => Some(PathBuf::from(path)),
+        Err(_) if std::env::var("AIRSHIPPER_ROOT").is_ok() => Some(BASE_PATH.clone()),
+        Err(_) => dirs::config_dir().map(|dir| dir.join("airshipper")),
+    };
+    let path = usable_dir("launcher config", [preferred, Some(BASE_PATH.clone())]);
+    migrate_savedstate(&path);
     path
 }
 
+/// Older versions stored the saved state next to the data, move it over so existing
+/// installs keep their settings.
+fn migrate_savedstate(config_path: &Path) {
+    let old = BASE_PATH.join(consts::SAVED_STATE_FILE);
+    let new = config_path.join(consts::SAVED_STATE_FILE);
+    if old == new || new.exists() || !old.exists() {
+        return;
+    }
+
+    tracing::info!(
+        "Moving saved state from {} to {}",
+        old.display(),
+        new.display()
+    );
+    if let Err(e) = std::fs::rename(&old, &new)
+        .or_else(|_| std::fs::copy(&old, &new).and_then(|_| std::fs::remove_file(&old)))
+    {
+        tracing::warn!(?e, "Failed to move saved state to the config directory");
+    }
+}
+
 pub fn base_path() -> impl std::fmt::Display {
     BASE_PATH.display()
 }
 
+pub fn config_path() -> impl std::fmt::Display {
+    CONFIG_PATH.display()
+}
+
 pub fn get_cache_path() -> PathBuf {
-    let cache_path = dirs::cache_dir()
-        .expect("Couldn't find OS cache directory")
-        .join(env!("CARGO_PKG_NAME"));
-    std::fs::create_dir_all(&cache_path).expect("failed to create cache directory!");
-    cache_path
+    CACHE_PATH.clone()
+}
+
+/// Falls back to the base path if the OS has no cache directory.
+fn cache() -> PathBuf {
+    usable_dir("cache", [
+        dirs::cache_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME"))),
+        Some(BASE_PATH.join("cache")),
+    ])
 }
 
 pub fn verify_cache() {
@@ -81,9 +289,229 @@ pub fn verify_cache() {
         .expect("Failed to write to cache version file!");
 }
 
+/// Saved state file passed via `--config`, replaces the default one for the session
+static SAVEDSTATE_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
 /// Returns path to the file which saves the current state
 pub fn savedstate_file() -> PathBuf {
-    BASE_PATH.join(consts::SAVED_STATE_FILE)
+    match SAVEDSTATE_OVERRIDE.get() {
+        Some(path) => path.clone(),
+        None => CONFIG_PATH.join(consts::SAVED_STATE_FILE),
+    }
+}
+
+/// Backup of the saved state which is written before importing a configuration
+pub fn savedstate_backup_file() -> PathBuf {
+    let mut path = savedstate_file().into_os_string();
+    path.push(".bak");
+    PathBuf::from(path)
+}
+
+/// Where the launcher exports its configuration to and imports it from, the documents
+/// folder so it is easy to find and carry over.
+pub fn export_file() -> PathBuf {
+    dirs::document_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| CONFIG_PATH.clone())
+        .join(consts::EXPORT_FILE)
+}
+
+/// Uses `path` as saved state file for the rest of the session. An existing file has
+/// to be readable and writable, a missing one is created on the first save.
+pub fn set_savedstate_file(path: PathBuf) -> Result<()> {
+    match std::fs::metadata(&path) {
+        Ok(meta) => {
+            if !meta.is_file() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("{} is not a file", path.display()),
+                )
+                .into());
+            }
+            // opening for writing without truncating checks both permissions
+            std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&path)?;
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+        },
+        Err(e) => return Err(e.into()),
+    }
+
+    let _ = SAVEDSTATE_OVERRIDE.set(path);
+    Ok(())
+}
+
+/// Parses the RON `content` read from `path`. A file which can't be parsed is moved out
+/// of the way as `<name>.corrupt`, so the caller can continue with defaults while the
+/// broken file is kept for inspection.
+pub fn parse_ron<T: DeserializeOwned>(path: &Path, content: &str) -> Result<T> {
+    ron::from_str(content).map_err(|e| {
+        tracing::error!(?e, "{} is corrupted", path.display());
+        backup_corrupt(path);
+        e.into()
+    })
+}
+
+/// first bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Whether the saved state and the caches are written gzipped, see
+/// [`Profile::compress_stored_files`](crate::profiles::Profile::compress_stored_files).
+static COMPRESS_STORED_FILES: AtomicBool = AtomicBool::new(false);
+
+pub fn set_compress_stored_files(compress: bool) {
+    COMPRESS_STORED_FILES.store(compress, Ordering::Relaxed);
+}
+
+pub fn compress_stored_files() -> bool {
+    COMPRESS_STORED_FILES.load(Ordering::Relaxed)
+}
+
+/// Decodes a stored RON file, gzipped files are recognized by their magic bytes so
+/// files written with and without compression can be read.
+pub fn decode_stored(bytes: &[u8]) -> std::io::Result<String> {
+    let mut content = String::new();
+    if bytes.starts_with(&GZIP_MAGIC) {
+        GzDecoder::new(bytes).read_to_string(&mut content)?;
+    } else {
+        content = String::from_utf8(bytes.to_vec())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    }
+    Ok(content)
+}
+
+/// Serializes `value` as pretty RON, or as gzipped RON without whitespace if
+/// `compress` is set.
+pub fn encode_stored<T: Serialize>(value: &T, compress: bool) -> Result<Vec<u8>> {
+    if compress {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(ron::ser::to_string(value)?.as_bytes())?;
+        Ok(encoder.finish()?)
+    } else {
+        Ok(ron::ser::to_string_pretty(value, PrettyConfig::default())?.into_bytes())
+    }
+}
+
+/// Reads and parses a RON file written by [`encode_stored`], see [`parse_ron`].
+pub fn read_ron<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    parse_ron(path, &decode_stored(&std::fs::read(path)?)?)
+}
+
+/// Like [`read_ron`], but a file which can't be parsed is left in place, for callers
+/// which only look at it.
+pub fn peek_ron<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    Ok(ron::from_str(&decode_stored(&std::fs::read(path)?)?)?)
+}
+
+/// Async version of [`read_ron`].
+pub async fn read_ron_async<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    parse_ron(path, &decode_stored(&tokio::fs::read(path).await?)?)
+}
+
+fn backup_corrupt(path: &Path) {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(".corrupt");
+    let backup = path.with_file_name(name);
+    match std::fs::rename(path, &backup) {
+        Ok(()) => tracing::warn!("Moved corrupted file to {}", backup.display()),
+        Err(e) => tracing::warn!(?e, "Failed to back up corrupted file"),
+    }
+}
+
+/// Checks that a corrupt `<name>.ron` is left alone by [`peek_ron`] and backed up by
+/// [`read_ron`].
+#[cfg(test)]
+pub(crate) fn assert_recovers<T: DeserializeOwned + std::fmt::Debug>(name: &str) {
+    let dir = std::env::temp_dir()
+        .join(format!("airshipper-corrupt-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{name}.ron"));
+    std::fs::write(&path, "(name: \"default\", ser").unwrap();
+
+    assert!(peek_ron::<T>(&path).is_err());
+    assert!(path.exists());
+    assert!(read_ron::<T>(&path).is_err());
+    assert!(!path.exists());
+    assert!(dir.join(format!("{name}.ron.corrupt")).exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// suffix of the temporary files of [`temp_path`]
+const TEMP_SUFFIX: &str = ".tmp";
+/// temporary files older than this were left behind by a crash, writing them takes
+/// far less
+const ORPHANED_TEMP_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Temporary file next to `path` to write it atomically. It is named
+/// `<name>.<pid>.<counter>.tmp`, so concurrent writes don't clash and
+/// [`remove_orphaned_temp_files`] recognizes it.
+pub fn temp_path(path: &Path) -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(format!(
+        ".{}.{}{TEMP_SUFFIX}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(name)
+}
+
+fn is_temp_file(name: &str) -> bool {
+    let Some(name) = name.strip_suffix(TEMP_SUFFIX) else {
+        return false;
+    };
+    let mut parts = name.rsplitn(3, '.');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(counter), Some(pid), Some(original)) => {
+            !original.is_empty()
+                && counter.parse::<u64>().is_ok()
+                && pid.parse::<u32>().is_ok()
+        },
+        _ => false,
+    }
+}
+
+/// Removes the temporary files of [`temp_path`] in `dir` which a crashed launcher left
+/// behind. Recent ones might belong to a running write and are kept, files named
+/// differently are never touched.
+pub fn remove_orphaned_temp_files(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_name().to_str().is_some_and(is_temp_file) {
+            continue;
+        }
+        let orphaned = entry
+            .metadata()
+            .and_then(|meta| {
+                Ok(meta.is_file()
+                    && meta
+                        .modified()?
+                        .elapsed()
+                        .is_ok_and(|age| age >= ORPHANED_TEMP_AGE))
+            })
+            .unwrap_or(false);
+        if !orphaned {
+            continue;
+        }
+        let path = entry.path();
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                tracing::info!("Removed orphaned temporary file {}", path.display())
+            },
+            Err(e) => {
+                tracing::warn!(?e, ?path, "Failed to remove orphaned temporary file")
+            },
+        }
+    }
 }
 
 /// Returns path to a profile while creating the folder
@@ -93,12 +521,216 @@ pub fn profile_path(profile_name: &str) -> PathBuf {
     path
 }
 
+/// Returns path to an install slot of a profile while creating the folder. Slots live
+/// outside of the profile folder, so updates of the default slot never touch them.
+pub fn slot_path(profile_name: &str, slot: &str) -> PathBuf {
+    let path = BASE_PATH.join("slots").join(profile_name).join(slot);
+    std::fs::create_dir_all(&path).expect("failed to create slot directory!");
+    path
+}
+
+/// Returns path to the kept game versions of an install slot while creating the
+/// folder, see [`crate::rollback`].
+pub fn kept_versions_path(profile_name: &str, slot: &str) -> PathBuf {
+    let path = BASE_PATH
+        .join("kept_versions")
+        .join(profile_name)
+        .join(slot);
+    std::fs::create_dir_all(&path).expect("failed to create kept versions directory!");
+    path
+}
+
+/// Returns the path of the CRC32 index of an install slot while creating its folder,
+/// see [`crate::update::PatchedLocalStorage`].
+pub fn hash_index_file(profile_name: &str, slot: &str) -> PathBuf {
+    let path = BASE_PATH.join("hash_index").join(profile_name);
+    std::fs::create_dir_all(&path).expect("failed to create hash index directory!");
+    path.join(format!("{slot}.ron"))
+}
+
 /// Returns path to the file where the logs will be stored
 pub fn log_file() -> PathBuf {
     BASE_PATH.join(consts::LOG_FILE)
 }
 
+/// Returns the path of the detailed log of the last update, see [`crate::update_log`]
+pub fn update_log_file() -> PathBuf {
+    BASE_PATH.join(consts::UPDATE_LOG_FILE)
+}
+
 /// Returns log-directory and log-file
 pub fn log_path_file() -> (&'static Path, &'static str) {
     (&BASE_PATH, consts::LOG_FILE)
 }
+
+/// Free space on the drive `dir` is on. `dir` doesn't have to exist yet.
+pub fn available_space(dir: &Path) -> Result<u64> {
+    let existing = dir.ancestors().find(|dir| dir.exists()).unwrap_or(dir);
+    Ok(fs4::available_space(existing)?)
+}
+
+/// Total size of an installation, broken down by its top-level entries.
+#[derive(Debug, Clone, Default)]
+pub struct InstallSize {
+    pub total: u64,
+    /// top-level files and directories with their size, largest first
+    pub entries: Vec<(String, u64)>,
+}
+
+/// Sums up the size of all files in `dir`. Symlinks are counted but never followed, so
+/// links pointing back into the tree can't cause loops.
+pub async fn install_size(dir: PathBuf) -> Result<InstallSize> {
+    Ok(tokio::task::spawn_blocking(move || install_size_blocking(&dir)).await??)
+}
+
+fn install_size_blocking(dir: &Path) -> std::io::Result<InstallSize> {
+    let mut size = InstallSize::default();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let bytes = dir_size(&entry.path())?;
+        size.total += bytes;
+        size.entries
+            .push((entry.file_name().to_string_lossy().into_owned(), bytes));
+    }
+    size.entries.sort_by(|a, b| b.1.cmp(&a.1));
+    Ok(size)
+}
+
+fn dir_size(path: &Path) -> std::io::Result<u64> {
+    let mut total = 0;
+    let mut todo = vec![path.to_path_buf()];
+    while let Some(path) = todo.pop() {
+        let meta = std::fs::symlink_metadata(&path)?;
+        if meta.is_dir() {
+            for entry in std::fs::read_dir(&path)? {
+                todo.push(entry?.path());
+            }
+        } else {
+            total += meta.len();
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gui::components::ChangelogPanelComponent, profiles::Profile};
+
+    #[test]
+    fn test_stored_files_round_trip() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-stored-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut profile = Profile::default();
+        profile.version = Some("1.0".to_owned());
+
+        for compress in [false, true] {
+            let path = dir.join(format!("{compress}.ron"));
+            let bytes = encode_stored(&profile, compress).unwrap();
+            assert_eq!(bytes.starts_with(&GZIP_MAGIC), compress);
+            std::fs::write(&path, bytes).unwrap();
+            let loaded: Profile = read_ron(&path).unwrap();
+            assert_eq!(loaded.version.as_deref(), Some("1.0"));
+        }
+        // a file which is neither RON nor gzip is still reported as an error
+        assert!(decode_stored(&[0xff, 0xfe, 0x00]).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_usable_dir_falls_back() {
+        let dir =
+            std::env::temp_dir().join(format!("airshipper-dirs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // a directory can't be created below a file
+        let file = dir.join("file");
+        std::fs::write(&file, b"").unwrap();
+
+        let usable = dir.join("usable");
+        assert_eq!(
+            usable_dir("test", [
+                None,
+                Some(file.join("data")),
+                Some(usable.clone())
+            ]),
+            usable
+        );
+        assert!(usable.is_dir());
+        let last_resort = usable_dir("test", [Some(file.join("data"))]);
+        assert!(last_resort.starts_with(std::env::temp_dir()));
+        assert!(last_resort.is_dir());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_private_dir() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let temp = std::env::temp_dir()
+            .join(format!("airshipper-private-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&temp);
+        std::fs::create_dir_all(&temp).unwrap();
+        let uid = current_uid(&temp).unwrap();
+        let mode = |dir: &Path| std::fs::metadata(dir).unwrap().mode() & 0o777;
+
+        let dir = private_dir_in(&temp);
+        assert_eq!(dir, temp.join(format!("airshipper-{uid}")));
+        assert_eq!(mode(&dir), 0o700);
+        // an existing directory is made private
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(private_dir_in(&temp), dir);
+        assert_eq!(mode(&dir), 0o700);
+
+        // a link prepared by someone else isn't followed
+        std::fs::remove_dir(&dir).unwrap();
+        let target = temp.join("target");
+        std::fs::create_dir(&target).unwrap();
+        std::os::unix::fs::symlink(&target, &dir).unwrap();
+        let fresh = private_dir_in(&temp);
+        assert_ne!(fresh, dir);
+        assert!(fresh.is_dir());
+        assert_eq!(mode(&fresh), 0o700);
+        assert_eq!(std::fs::read_dir(&temp).unwrap().count(), 3);
+
+        std::fs::remove_dir_all(&temp).unwrap();
+    }
+
+    #[test]
+    fn test_orphaned_temp_files_are_removed() {
+        let dir =
+            std::env::temp_dir().join(format!("airshipper-temp-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let orphaned = temp_path(&dir.join("airshipper_state.ron"));
+        let recent = temp_path(&dir.join("airshipper_state.ron"));
+        let user = dir.join("notes.tmp");
+        for path in [&orphaned, &recent, &user] {
+            std::fs::write(path, b"").unwrap();
+        }
+        let old = std::time::SystemTime::now() - ORPHANED_TEMP_AGE * 2;
+        for path in [&orphaned, &user] {
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(old)
+                .unwrap();
+        }
+
+        remove_orphaned_temp_files(&dir);
+        assert!(!orphaned.exists());
+        assert!(recent.exists());
+        assert!(user.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_ron_is_backed_up() {
+        assert_recovers::<Profile>("profile");
+        assert_recovers::<ChangelogPanelComponent>("changelog");
+    }
+}
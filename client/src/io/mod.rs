@@ -1,3 +1,5 @@
+/// opens links in a browser, with fallbacks if no default browser is set
+pub mod browser;
 /// deals with finding os specific paths and extensions.
 pub mod fs;
 /// stream process output line by line followed by the exit status
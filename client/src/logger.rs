@@ -1,8 +1,12 @@
+use crate::profiles::{LogRetention, LogRule, LogRuleAction};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
 use termcolor::{ColorChoice, StandardStream};
-use tracing::info;
+use tracing::{Level, info};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{
     EnvFilter,
@@ -12,7 +16,8 @@ use tracing_subscriber::{
     registry,
 };
 
-const MAX_LOG_LINES: usize = 10_000;
+/// the log is rotated on startup once it is larger, see [`LogRetention`]
+pub const MAX_LOG_SIZE: u64 = 10_000_000;
 const RUST_LOG_ENV: &str = "RUST_LOG";
 
 pub fn init(log_path_file: Option<(&Path, &str)>, level: LevelFilter) -> Vec<impl Drop> {
@@ -84,15 +89,12 @@ pub fn init(log_path_file: Option<(&Path, &str)>, level: LevelFilter) -> Vec<imp
         registry.with(tracing_subscriber::fmt::layer().with_writer(non_blocking))
     };
 
+    let mut rotated = None;
     if let Some((path, file)) = log_path_file {
-        // Clean up log file if possible
-        let logfile = path.join(file);
-        if logfile.exists()
-            && let Ok(count) =
-                std::fs::read_to_string(&logfile).map(|x| x.lines().count())
-            && count > MAX_LOG_LINES
-        {
-            let _ = std::fs::remove_file(&logfile);
+        // before the appender opens the file, so no line ends up in the rotated log
+        match rotate_log(path, file, chrono::Local::now()) {
+            Ok(path) => rotated = path,
+            Err(e) => eprintln!("WARN failed to rotate the log file: {e}"),
         }
 
         match std::fs::create_dir_all(path) {
@@ -132,6 +134,9 @@ pub fn init(log_path_file: Option<(&Path, &str)>, level: LevelFilter) -> Vec<imp
         let (path, file) = log_path_file.unwrap();
         info!(?path, ?file, "Setup terminal and file logging.");
     }
+    if let Some(rotated) = rotated {
+        info!(?rotated, "Rotated the previous log file");
+    }
 
     if tracing::level_enabled!(tracing::Level::TRACE) {
         info!("Tracing Level: TRACE");
@@ -147,52 +152,146 @@ lazy_static! {
     static ref LOG_REGEX: Regex = Regex::new(r"(?:\x{1b}\[\dm)?(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}.\d{1,6}Z)(?:\x{1b}\[\dm\s+\x{1b}\[\d{2}m)?\s?(INFO|TRACE|DEBUG|ERROR|WARN)(?:\x{1b}\[\dm\s\x{1b}\[\dm)?\s?((?:[A-Za-z_]+:{0,2})+)\s?(.*)").unwrap();
 }
 
-pub(crate) fn redirect_voxygen_log(line: &str) {
-    if let Some(cap) = LOG_REGEX.captures(line) {
-        if let (Some(level), Some(target), Some(msg)) =
-            (cap.get(2), cap.get(3), cap.get(4))
-        {
-            let target = target.as_str();
-            let msg = msg.as_str();
-
-            match level.as_str() {
-                "TRACE" => tracing::trace!(
-                    target: "voxygen",
-                    "{} {}",
-                    target,
-                    msg,
-                ),
-                "DEBUG" => tracing::debug!(
-                    target: "voxygen",
-                    "{} {}",
-                    target,
-                    msg,
-                ),
-                "INFO" => tracing::info!(
-                    target: "voxygen",
-                    "{} {}",
-                    target,
-                    msg,
-                ),
-                "WARN" => tracing::warn!(
-                    target: "voxygen",
-                    "{} {}",
-                    target,
-                    msg,
-                ),
-                "ERROR" => tracing::error!(
-                    target: "voxygen",
-                    "{} {}",
-                    target,
-                    msg,
-                ),
-                _ => tracing::info!(target: "voxygen","{}", msg),
-            }
-        } else {
-            tracing::info!(target: "voxygen","{}", line);
+pub(crate) fn redirect_voxygen_log(line: &str, rules: &[LogRule]) {
+    let Some(cap) = LOG_REGEX.captures(line) else {
+        return log_voxygen_line(line, Level::INFO, "", line, rules);
+    };
+    match (cap.get(2), cap.get(3), cap.get(4)) {
+        (Some(level), Some(target), Some(msg)) => {
+            let level = level.as_str().parse().unwrap_or(Level::INFO);
+            log_voxygen_line(line, level, target.as_str(), msg.as_str(), rules)
+        },
+        _ => log_voxygen_line(line, Level::INFO, "", line, rules),
+    }
+}
+
+/// Logs the message of a game log line. It is only joined with its target when it is
+/// written, so there is no allocation per line for lines which aren't.
+fn log_voxygen_line(
+    line: &str,
+    level: Level,
+    target: &str,
+    msg: &str,
+    rules: &[LogRule],
+) {
+    let Some(level) = apply_log_rules(line, level, rules) else {
+        return;
+    };
+
+    let separator = if target.is_empty() { "" } else { " " };
+    match level {
+        Level::TRACE => tracing::trace!(target: "voxygen", "{target}{separator}{msg}"),
+        Level::DEBUG => tracing::debug!(target: "voxygen", "{target}{separator}{msg}"),
+        Level::INFO => tracing::info!(target: "voxygen", "{target}{separator}{msg}"),
+        Level::WARN => tracing::warn!(target: "voxygen", "{target}{separator}{msg}"),
+        // Level::ERROR
+        _ => tracing::error!(target: "voxygen", "{target}{separator}{msg}"),
+    }
+}
+
+/// Returns the level a game log line is logged with, `None` if it is suppressed
+fn apply_log_rules(line: &str, level: Level, rules: &[LogRule]) -> Option<Level> {
+    let Some(rule) = rules
+        .iter()
+        .find(|rule| line.contains(rule.contains.as_str()))
+    else {
+        return Some(level);
+    };
+
+    match rule.action {
+        LogRuleAction::Suppress => None,
+        LogRuleAction::Trace => Some(Level::TRACE),
+        LogRuleAction::Debug => Some(Level::DEBUG),
+        LogRuleAction::Info => Some(Level::INFO),
+        LogRuleAction::Warn => Some(Level::WARN),
+        LogRuleAction::Error => Some(Level::ERROR),
+    }
+}
+
+/// Renames the log `file` in `path` to `<name>.<time>.log` if it is larger than
+/// [`MAX_LOG_SIZE`], returns the rotated file.
+fn rotate_log(
+    path: &Path,
+    file: &str,
+    time: chrono::DateTime<chrono::Local>,
+) -> std::io::Result<Option<PathBuf>> {
+    let log = path.join(file);
+    match std::fs::metadata(&log) {
+        Ok(meta) if meta.len() > MAX_LOG_SIZE => {},
+        Ok(_) => return Ok(None),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let (name, extension) = file.rsplit_once('.').unwrap_or((file, "log"));
+    let rotated = path.join(format!(
+        "{name}.{}.{extension}",
+        time.format("%Y-%m-%d_%H-%M-%S")
+    ));
+    std::fs::rename(&log, &rotated)?;
+    Ok(Some(rotated))
+}
+
+/// Rotated logs of the log `file` in `path`, newest first. Their names contain the
+/// time they were rotated, so they sort by it.
+fn rotated_logs(path: &Path, file: &str) -> std::io::Result<Vec<PathBuf>> {
+    let (name, extension) = file.rsplit_once('.').unwrap_or((file, "log"));
+    let prefix = format!("{name}.");
+    let suffix = format!(".{extension}");
+    let mut logs: Vec<_> = std::fs::read_dir(path)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|log| {
+            log.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name != file && name.starts_with(&prefix) && name.ends_with(&suffix)
+                })
+        })
+        .collect();
+    logs.sort_by(|a, b| b.cmp(a));
+    Ok(logs)
+}
+
+/// Rotated logs of the log `file` in `path` which `retention` doesn't keep.
+fn expired_logs(
+    path: &Path,
+    file: &str,
+    retention: &LogRetention,
+    now: SystemTime,
+) -> std::io::Result<Vec<PathBuf>> {
+    let max_age = Duration::from_secs(retention.max_days * 86400);
+    let expired = rotated_logs(path, file)?
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, log)| {
+            let too_many = retention.max_files > 0 && *idx >= retention.max_files;
+            let too_old = retention.max_days > 0
+                && std::fs::metadata(log)
+                    .and_then(|meta| meta.modified())
+                    .is_ok_and(|modified| {
+                        now.duration_since(modified).is_ok_and(|age| age > max_age)
+                    });
+            too_many || too_old
+        })
+        .map(|(_, log)| log)
+        .collect();
+    Ok(expired)
+}
+
+/// Removes the rotated logs which `retention` doesn't keep.
+pub(crate) fn prune_logs((path, file): (&Path, &str), retention: &LogRetention) {
+    let expired = match expired_logs(path, file, retention, SystemTime::now()) {
+        Ok(expired) => expired,
+        Err(e) => {
+            tracing::warn!(?e, "Failed to look for old log files");
+            return;
+        },
+    };
+    for log in expired {
+        match std::fs::remove_file(&log) {
+            Ok(()) => tracing::debug!(?log, "Removed old log file"),
+            Err(e) => tracing::warn!(?e, ?log, "Failed to remove old log file"),
         }
-    } else {
-        tracing::info!(target: "voxygen","{}", line);
     }
 }
 
@@ -203,3 +302,122 @@ pub(crate) fn pretty_bytes(bytes: u64) -> String {
         bytes => format!("{} MB", bytes / 1_000_000),
     }
 }
+
+/// The throughput, `None` if `duration` is too short to measure it, e.g. when all
+/// bytes arrived in one go.
+pub(crate) fn bytes_per_second(bytes: u64, duration: Duration) -> Option<u64> {
+    (duration >= Duration::from_millis(1))
+        .then(|| (bytes as f64 / duration.as_secs_f64()) as u64)
+}
+
+/// Local date and time of `time`, or "never".
+pub(crate) fn pretty_time(time: Option<&chrono::DateTime<chrono::Utc>>) -> String {
+    match time {
+        Some(time) => chrono::DateTime::<chrono::Local>::from(*time)
+            .format("%Y-%m-%d %H:%M")
+            .to_string(),
+        None => "never".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_per_second() {
+        assert_eq!(
+            bytes_per_second(10_000, Duration::from_millis(500)),
+            Some(20_000)
+        );
+        // a sample which arrived in one go doesn't divide by zero
+        assert_eq!(bytes_per_second(10_000, Duration::ZERO), None);
+        assert_eq!(bytes_per_second(10_000, Duration::from_micros(10)), None);
+    }
+
+    #[test]
+    fn test_apply_log_rules() {
+        let rules = vec![
+            LogRule {
+                contains: "noisy".to_owned(),
+                action: LogRuleAction::Suppress,
+            },
+            LogRule {
+                contains: "panicked".to_owned(),
+                action: LogRuleAction::Error,
+            },
+        ];
+
+        assert_eq!(apply_log_rules("a noisy line", Level::WARN, &rules), None);
+        assert_eq!(
+            apply_log_rules("thread 'main' panicked", Level::INFO, &rules),
+            Some(Level::ERROR)
+        );
+        assert_eq!(
+            apply_log_rules("something else", Level::DEBUG, &rules),
+            Some(Level::DEBUG)
+        );
+    }
+
+    #[test]
+    fn test_log_rotation() {
+        let dir =
+            std::env::temp_dir().join(format!("airshipper-logs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = "airshipper.log";
+        let log = dir.join(file);
+        let time = |day| {
+            use chrono::TimeZone;
+            chrono::Local
+                .with_ymd_and_hms(2026, 1, day, 12, 0, 0)
+                .unwrap()
+        };
+
+        std::fs::write(&log, b"small").unwrap();
+        assert_eq!(rotate_log(&dir, file, time(1)).unwrap(), None);
+        let big = vec![b'x'; MAX_LOG_SIZE as usize + 1];
+        for day in 1..=4 {
+            std::fs::write(&log, &big).unwrap();
+            assert!(rotate_log(&dir, file, time(day)).unwrap().is_some());
+        }
+        assert!(!log.exists());
+        std::fs::write(&log, b"current").unwrap();
+        std::fs::write(dir.join("airshipper_update.jsonl"), b"other").unwrap();
+        assert_eq!(rotated_logs(&dir, file).unwrap().len(), 4);
+
+        let retention = LogRetention {
+            max_files: 2,
+            max_days: 0,
+        };
+        let expired = expired_logs(&dir, file, &retention, SystemTime::now()).unwrap();
+        assert_eq!(expired.len(), 2);
+        // the oldest ones
+        assert!(expired.iter().all(|log| {
+            let name = log.file_name().unwrap().to_string_lossy();
+            name.contains("-01_") || name.contains("-02_")
+        }));
+
+        let retention = LogRetention {
+            max_files: 0,
+            max_days: 30,
+        };
+        let later = SystemTime::now() + Duration::from_secs(31 * 86400);
+        assert_eq!(
+            expired_logs(&dir, file, &retention, later).unwrap().len(),
+            4
+        );
+        assert!(
+            expired_logs(&dir, file, &retention, SystemTime::now())
+                .unwrap()
+                .is_empty()
+        );
+
+        prune_logs((&dir, file), &LogRetention {
+            max_files: 1,
+            max_days: 0,
+        });
+        assert_eq!(rotated_logs(&dir, file).unwrap().len(), 1);
+        assert!(log.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{
+    ClientError, Result,
+    profiles::{MacosSigning, PatchedInfo, Profile},
+};
+use std::path::Path;
+
+/// Signs the game binaries with the signing command of `profile`. Signing changes the
+/// binaries, so like the NixOS patches their checksums are returned to avoid
+/// downloading them again. Failures are only logged unless signing is required.
+///
+/// Note: it's synchronous!
+pub fn sign_binaries(profile: &Profile) -> Result<Vec<PatchedInfo>> {
+    let signing = &profile.macos_signing;
+    if !signing.is_enabled() {
+        return Ok(Vec::new());
+    }
+
+    let profile_directory = profile.directory();
+    let mut signed = Vec::new();
+    for file in profile.installed_executables() {
+        match sign(&profile_directory, file, signing, &profile.patched_crc32s) {
+            Ok(info) => signed.push(info),
+            Err(e) if signing.required => return Err(e),
+            Err(e) => tracing::warn!(?e, "Failed to sign {file}, it might not start"),
+        }
+    }
+    Ok(signed)
+}
+
+/// Runs the signing command on `file`, unless it is unchanged since it was signed
+/// during a previous update.
+fn sign(
+    profile_directory: &Path,
+    file: &str,
+    signing: &MacosSigning,
+    previous: &[PatchedInfo],
+) -> Result<PatchedInfo> {
+    let path = profile_directory.join(file);
+    let pre_crc32 = crc32fast::hash(&std::fs::read(&path)?);
+    if let Some(info) = previous
+        .iter()
+        .find(|info| info.local_unix_path == file && info.post_crc32 == pre_crc32)
+    {
+        tracing::debug!("{file} is already signed");
+        return Ok(info.clone());
+    }
+
+    let mut command = signing.command.split_whitespace();
+    let program = command.next().unwrap_or_default();
+    tracing::info!("Signing {file} with {program}");
+    let output = std::process::Command::new(program)
+        .args(command)
+        .arg(&path)
+        .current_dir(profile_directory)
+        .output()
+        .map_err(|e| {
+            ClientError::MacosSigning(format!("{program} could not be started: {e}"))
+        })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(ClientError::MacosSigning(format!(
+            "{program} failed to sign {file} ({}):\nstderr: {stderr}\nstdout: {stdout}",
+            output.status
+        )));
+    }
+    // codesign reports on stderr even if it succeeds
+    tracing::info!("Signed {file}:\nstderr: {stderr}\nstdout: {stdout}");
+
+    let post_crc32 = crc32fast::hash(&std::fs::read(&path)?);
+    Ok(PatchedInfo {
+        local_unix_path: file.to_string(),
+        pre_crc32,
+        post_crc32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::VOXYGEN_FILE;
+
+    #[test]
+    fn test_sign_records_checksums() {
+        let dir =
+            std::env::temp_dir().join(format!("airshipper-sign-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(VOXYGEN_FILE), b"unsigned").unwrap();
+
+        // replaces the binary, like a signature would change it
+        let signing = MacosSigning {
+            command: "cp /bin/echo".to_string(),
+            required: true,
+        };
+        let info = sign(&dir, VOXYGEN_FILE, &signing, &[]).unwrap();
+        assert_eq!(info.pre_crc32, crc32fast::hash(b"unsigned"));
+        assert_ne!(info.pre_crc32, info.post_crc32);
+
+        // a binary which changed since it was signed is signed again
+        std::fs::write(dir.join(VOXYGEN_FILE), b"updated").unwrap();
+        let failing = MacosSigning {
+            command: "false".to_string(),
+            required: true,
+        };
+        assert!(sign(&dir, VOXYGEN_FILE, &failing, &[info.clone()]).is_err());
+        // an unchanged one is kept as it is
+        std::fs::copy("/bin/echo", dir.join(VOXYGEN_FILE)).unwrap();
+        let kept = sign(&dir, VOXYGEN_FILE, &failing, &[info.clone()]).unwrap();
+        assert_eq!(kept.pre_crc32, info.pre_crc32);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,14 +3,20 @@ mod channels;
 mod cli;
 mod consts;
 mod error;
+mod feeds;
 mod gui;
 mod io;
 mod logger;
+#[cfg(target_os = "macos")]
+mod macos;
+mod manifest;
 mod net;
 #[cfg(unix)]
 mod nix;
 mod profiles;
+mod rollback;
 mod update;
+mod update_log;
 #[cfg(windows)]
 mod windows;
 
@@ -21,16 +27,19 @@ pub use net::*;
 
 pub type Result<T> = std::result::Result<T, ClientError>;
 
-fn main() {
+fn main() -> std::process::ExitCode {
     error::panic_hook();
 
-    // If we fail to read a line, the user probably cancelled an action
-    if let Some(e) = cli::process()
-        .err()
-        .filter(|e| !matches!(e, ClientError::Readline(_)))
-    {
-        tracing::error!("{}", e);
-        tracing::info!("Press enter to exit...");
-        let _ = std::io::stdin().read_line(&mut String::new());
+    match cli::process() {
+        Ok(code) => code.into(),
+        Err(e) => {
+            // If we fail to read a line, the user probably cancelled an action
+            if !matches!(e, ClientError::Readline(_)) {
+                tracing::error!("{}", e);
+                tracing::info!("Press enter to exit...");
+                let _ = std::io::stdin().read_line(&mut String::new());
+            }
+            cli::ExitCode::from(&e).into()
+        },
     }
 }
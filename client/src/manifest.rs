@@ -0,0 +1,141 @@
+//! Lists the installed game files with their sizes and CRC32s for `airshipper
+//! manifest`, so packagers can verify an install or diff two of them without the
+//! launcher. Only the files an update manages are listed, see
+//! [`install_files`](crate::update::install_files).
+use crate::{Result, profiles::Profile, update::install_files};
+use serde::Serialize;
+use std::{io::Read, path::Path};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Manifest {
+    pub channel: String,
+    pub version: Option<String>,
+    /// sorted by path
+    pub files: Vec<ManifestFile>,
+    /// CRC32 of the version followed by the path, size and CRC32 of every file, equal
+    /// for installs of the same version with the same files
+    pub checksum: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestFile {
+    /// unix path relative to the install
+    pub path: String,
+    pub size: u64,
+    pub crc32: u32,
+}
+
+/// Reads every installed file of `profile`.
+///
+/// Note: it's synchronous!
+pub fn manifest(profile: &Profile) -> Result<Manifest> {
+    let shipped_maps = profile
+        .sync_shipped_maps
+        .then_some(profile.shipped_maps.as_slice());
+    manifest_of(
+        &profile.directory(),
+        shipped_maps,
+        profile.channel.0.clone(),
+        profile.version.clone(),
+    )
+}
+
+fn manifest_of(
+    dir: &Path,
+    shipped_maps: Option<&[String]>,
+    channel: String,
+    version: Option<String>,
+) -> Result<Manifest> {
+    let mut files = Vec::new();
+    for (path, meta) in install_files(dir, shipped_maps)? {
+        // symlinks are skipped by updates as well
+        if !meta.is_file() {
+            continue;
+        }
+        files.push(ManifestFile {
+            crc32: crc32(&dir.join(&path))?,
+            path,
+            size: meta.len(),
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut manifest = Manifest {
+        channel,
+        version,
+        files,
+        checksum: 0,
+    };
+    manifest.checksum = checksum(&manifest);
+    Ok(manifest)
+}
+
+/// Reads the file in chunks, game assets can be large.
+fn crc32(path: &Path) -> std::io::Result<u32> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        match file.read(&mut buf)? {
+            0 => return Ok(hasher.finalize()),
+            n => hasher.update(&buf[..n]),
+        }
+    }
+}
+
+fn checksum(manifest: &Manifest) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(manifest.version.as_deref().unwrap_or_default().as_bytes());
+    for file in &manifest.files {
+        hasher.update(file.path.as_bytes());
+        hasher.update(&file.size.to_le_bytes());
+        hasher.update(&file.crc32.to_le_bytes());
+    }
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-manifest-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::create_dir_all(dir.join("userdata")).unwrap();
+        std::fs::create_dir_all(dir.join("maps")).unwrap();
+        std::fs::write(dir.join("veloren-voxygen"), b"game").unwrap();
+        std::fs::write(dir.join("assets/common.ron"), b"assets").unwrap();
+        std::fs::write(dir.join("userdata/settings.ron"), b"()").unwrap();
+        std::fs::write(dir.join("maps/world.bin"), b"map").unwrap();
+
+        let manifest_of = |shipped_maps: Option<&[String]>| {
+            manifest_of(&dir, shipped_maps, "weekly".to_string(), None).unwrap()
+        };
+        let manifest = manifest_of(None);
+        assert_eq!(manifest.files, [
+            ManifestFile {
+                path: "assets/common.ron".to_string(),
+                size: 6,
+                crc32: crc32fast::hash(b"assets"),
+            },
+            ManifestFile {
+                path: "veloren-voxygen".to_string(),
+                size: 4,
+                crc32: crc32fast::hash(b"game"),
+            },
+        ]);
+        let shipped = ["maps/world.bin".to_string()];
+        let with_maps = manifest_of(Some(&shipped[..]));
+        assert_eq!(with_maps.files.len(), 3);
+        assert_ne!(with_maps.checksum, manifest.checksum);
+
+        // user data doesn't change the manifest
+        std::fs::write(dir.join("userdata/settings.ron"), b"(changed)").unwrap();
+        assert_eq!(manifest_of(None), manifest);
+        std::fs::write(dir.join("veloren-voxygen"), b"GAME").unwrap();
+        assert_ne!(manifest_of(None).checksum, manifest.checksum);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,5 +1,14 @@
-use crate::Result;
-use reqwest::IntoUrl;
+use crate::{
+    Result,
+    profiles::{DownloadAuth, HttpVersion, Profile},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use reqwest::{
+    ClientBuilder, IntoUrl, Method, RequestBuilder,
+    header::{AUTHORIZATION, CONTENT_TYPE, HeaderMap, HeaderValue, RANGE},
+};
+use std::sync::OnceLock;
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 // Name your user agent after your app?
 const USER_AGENT: &str = concat!("Airshipper/", env!("CARGO_PKG_VERSION"));
@@ -7,27 +16,111 @@ const USER_AGENT: &str = concat!("Airshipper/", env!("CARGO_PKG_VERSION"));
 lazy_static::lazy_static! {
     // Base for config, profiles, ...
     pub static ref WEB_CLIENT: reqwest::Client = {
-        reqwest::Client::builder()
-            .user_agent(USER_AGENT)
-            .use_rustls_tls()
+        client_builder(HttpVersion::Auto)
             .connect_timeout(std::time::Duration::from_secs(10))
             .build()
             .expect("FATAL: Failed to build reqwest client!")
     };
 
+    // Requests to the download server, in the HTTP version set by `set_http_version`
     pub static ref GITHUB_CLIENT: reqwest::Client = {
-        reqwest::Client::builder()
-            .user_agent(USER_AGENT)
-            .http2_prior_knowledge()
-            .use_rustls_tls()
+        client_builder(http_version())
             .connect_timeout(std::time::Duration::from_secs(10))
             .build()
             .expect("FATAL: Failed to build reqwest client!")
     };
 }
 
+static HTTP_VERSION: OnceLock<HttpVersion> = OnceLock::new();
+
+/// Sets the HTTP version of the requests to the download server. Has no effect once
+/// the first request has been made.
+pub fn set_http_version(version: HttpVersion) {
+    if HTTP_VERSION.set(version).is_err() {
+        tracing::warn!("The HTTP version can't be changed after the first download");
+    }
+}
+
+fn http_version() -> HttpVersion {
+    *HTTP_VERSION.get_or_init(HttpVersion::default)
+}
+
+/// Uses the pinned TLS config if certificates are pinned, see [`super::pinning`].
+fn client_builder(http: HttpVersion) -> ClientBuilder {
+    let builder = reqwest::Client::builder().user_agent(USER_AGENT);
+    let builder = match http {
+        HttpVersion::Auto => builder,
+        HttpVersion::Http1 => builder.http1_only(),
+        HttpVersion::Http2 => builder.http2_prior_knowledge(),
+    };
+    match super::pinning::tls_config(http) {
+        Some(config) => builder.use_preconfigured_tls(config),
+        None => builder.use_rustls_tls(),
+    }
+}
+
+pub const DEFAULT_MAX_PARALLEL_FETCHES: usize = 4;
+
+/// Limits how many feeds, changelogs and images are fetched at once, so slow
+/// connections aren't flooded with requests on startup.
+static FETCH_PERMITS: OnceLock<FetchPermits> = OnceLock::new();
+
+struct FetchPermits {
+    all: Semaphore,
+    /// one less than `all`, so images never hold back text content
+    images: Semaphore,
+}
+
+impl FetchPermits {
+    fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self {
+            all: Semaphore::new(max),
+            images: Semaphore::new(max.saturating_sub(1).max(1)),
+        }
+    }
+}
+
+fn fetch_permits() -> &'static FetchPermits {
+    FETCH_PERMITS.get_or_init(|| FetchPermits::new(DEFAULT_MAX_PARALLEL_FETCHES))
+}
+
+/// Sets how many requests for launcher content run at once. Has no effect once the
+/// first request has been made.
+pub fn set_max_parallel_fetches(max: usize) {
+    if FETCH_PERMITS.set(FetchPermits::new(max)).is_err() {
+        tracing::warn!("Parallel fetches can't be changed after the first fetch");
+    }
+}
+
+/// Waits until an image may be fetched, the permits have to be held while fetching.
+pub(crate) async fn image_fetch_permit()
+-> (SemaphorePermit<'static>, SemaphorePermit<'static>) {
+    let permits = fetch_permits();
+    let image = permits
+        .images
+        .acquire()
+        .await
+        .expect("semaphore is never closed");
+    let all = permits
+        .all
+        .acquire()
+        .await
+        .expect("semaphore is never closed");
+    (image, all)
+}
+
+async fn fetch_permit() -> SemaphorePermit<'static> {
+    fetch_permits()
+        .all
+        .acquire()
+        .await
+        .expect("semaphore is never closed")
+}
+
 /// Queries url for the etag header
 pub(crate) async fn query_etag<U: IntoUrl>(url: U) -> Result<Option<String>> {
+    let _permit = fetch_permit().await;
     Ok(WEB_CLIENT
         .head(url)
         .send()
@@ -39,11 +132,281 @@ pub(crate) async fn query_etag<U: IntoUrl>(url: U) -> Result<Option<String>> {
 
 /// Extracts Etag value from response
 /// Note: Will default to `MISSING_ETAG` incase header isn't found
-pub(crate) fn get_etag(x: &reqwest::Response) -> String {
-    x.headers().get("etag").map(|x| x.to_str().unwrap().to_string()) // Etag will always be a valid UTF-8 due to it being ASCII
+pub(crate) fn get_etag(x: &Fetched) -> String {
+    x.headers.get("etag").map(|x| x.to_str().unwrap().to_string()) // Etag will always be a valid UTF-8 due to it being ASCII
         .unwrap_or_else(|| "MISSING_ETAG".into())
 }
 
-pub(crate) async fn query<U: IntoUrl>(url: U) -> Result<reqwest::Response> {
-    Ok(WEB_CLIENT.get(url).send().await?)
+/// Response of [`query`], its body is read while the fetch permit is held.
+#[derive(Debug)]
+pub(crate) struct Fetched {
+    pub headers: HeaderMap,
+    pub body: bytes::Bytes,
+}
+
+impl Fetched {
+    pub(crate) fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    pub(crate) fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| crate::ClientError::Http(format!("Invalid JSON: {e}")))
+    }
+}
+
+/// Fetches `url` including its body, so slow responses count towards the parallel
+/// fetches until they arrived completely.
+pub(crate) async fn query<U: IntoUrl>(url: U) -> Result<Fetched> {
+    let _permit = fetch_permit().await;
+    let response = WEB_CLIENT.get(url).send().await?;
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    Ok(Fetched { headers, body })
+}
+
+/// Builds a request which carries the download server credentials of `profile`, see
+/// [`Profile::download_auth`]. They are only added if `url` points to the configured
+/// download server, and reqwest drops them on redirects to other hosts.
+pub(crate) fn download_server_request(
+    method: Method,
+    url: &str,
+    profile: &Profile,
+) -> RequestBuilder {
+    let request = GITHUB_CLIENT.request(method, url);
+    match download_server_auth(url, profile) {
+        Some(DownloadAuth::Bearer(token)) => request.bearer_auth(token),
+        Some(DownloadAuth::Basic { user, password }) => {
+            request.basic_auth(user, password)
+        },
+        None => request,
+    }
+}
+
+/// Client for the archive at `url`. remozipsy builds the range requests itself, so the
+/// credentials are sent as default headers, with the same scope as in
+/// [`download_server_request`]. Reads fail after the idle timeout of `profile`.
+pub(crate) fn download_client(profile: &Profile, url: &str) -> reqwest::Client {
+    build_download_client(profile, url, http_version())
+}
+
+fn build_download_client(
+    profile: &Profile,
+    url: &str,
+    http: HttpVersion,
+) -> reqwest::Client {
+    let mut headers = HeaderMap::new();
+    let auth = match download_server_auth(url, profile) {
+        Some(DownloadAuth::Bearer(token)) => Some(format!("Bearer {token}")),
+        Some(DownloadAuth::Basic { user, password }) => Some(format!(
+            "Basic {}",
+            STANDARD.encode(format!("{user}:{}", password.unwrap_or_default()))
+        )),
+        None => None,
+    };
+    if let Some(auth) = auth {
+        match HeaderValue::from_str(&auth) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                headers.insert(AUTHORIZATION, value);
+            },
+            Err(_) => {
+                tracing::warn!("The download credentials contain invalid characters")
+            },
+        }
+    }
+    let builder = client_builder(http)
+        .connect_timeout(std::time::Duration::from_secs(10))
+        .default_headers(headers);
+    let builder = match crate::update::download_idle_timeout(profile) {
+        Some(timeout) => builder.read_timeout(timeout),
+        None => builder,
+    };
+    builder
+        .build()
+        .expect("FATAL: Failed to build reqwest client!")
+}
+
+fn download_server_auth(url: &str, profile: &Profile) -> Option<DownloadAuth> {
+    let auth = profile.download_auth()?;
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned));
+    (host.is_some() && host == profile.download_host()).then_some(auth)
+}
+
+/// Whether a response is a web page, judged by its content type or the start of its
+/// `body`. Captive portals and misconfigured proxies answer with one instead of the
+/// requested file, often with status 200.
+pub(crate) fn is_html(headers: &HeaderMap, body: &[u8]) -> bool {
+    let html_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().to_ascii_lowercase().starts_with("text/html"));
+    let start = String::from_utf8_lossy(&body[..body.len().min(64)])
+        .trim_start()
+        .to_ascii_lowercase();
+    html_type || start.starts_with("<!doctype html") || start.starts_with("<html")
+}
+
+/// Whether the download server answers `url` with a web page, see [`is_html`]. Only
+/// the first bytes are downloaded.
+pub(crate) async fn serves_html(url: &str, profile: &Profile) -> bool {
+    let Ok(mut response) = download_server_request(Method::GET, url, profile)
+        .header(RANGE, "bytes=0-1023")
+        .send()
+        .await
+    else {
+        return false;
+    };
+    let body = response.chunk().await.ok().flatten().unwrap_or_default();
+    is_html(response.headers(), &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_are_scoped_to_the_download_server() {
+        let mut profile = Profile::default();
+        profile.download_auth = Some(DownloadAuth::Bearer("secret".to_string()));
+
+        let request =
+            download_server_request(Method::GET, &profile.version_url(), &profile)
+                .build()
+                .unwrap();
+        assert!(request.headers()[AUTHORIZATION].is_sensitive());
+        let request =
+            download_server_request(Method::GET, crate::consts::NEWS_URL, &profile)
+                .build()
+                .unwrap();
+        assert!(!request.headers().contains_key(AUTHORIZATION));
+        assert!(!format!("{profile:?}").contains("secret"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_download_client_sends_credentials() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server =
+            format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+        let url = format!("{server}/veloren.zip");
+        let answer = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let len = stream.read(&mut request).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request[..len]).to_lowercase()
+        });
+
+        let mut profile = Profile::default();
+        profile.download_auth = Some(DownloadAuth::Basic {
+            user: "user".to_string(),
+            password: Some("secret".to_string()),
+        });
+        profile.url_templates.version = Some(format!("{server}/{{channel}}"));
+        let client = build_download_client(&profile, &url, HttpVersion::Http1);
+        client.get(&url).send().await.unwrap();
+        let request = answer.await.unwrap();
+        assert!(
+            request.contains("authorization: basic dxnlcjpzzwnyzxq="),
+            "{request}"
+        );
+
+        // other hosts never get them
+        let client =
+            build_download_client(&profile, crate::consts::NEWS_URL, HttpVersion::Http1);
+        let request = client.get(&url).build().unwrap();
+        assert!(!request.headers().contains_key(AUTHORIZATION));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_download_client_http_version() {
+        use tokio::io::AsyncReadExt;
+
+        for (http, start) in [
+            (HttpVersion::Http1, &b"GET /veloren.zip HTTP/1.1"[..]),
+            (HttpVersion::Http2, &b"PRI * HTTP/2.0"[..]),
+        ] {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/veloren.zip", listener.local_addr().unwrap());
+            let received = tokio::spawn(async move {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![0; start.len()];
+                stream.read_exact(&mut request).await.unwrap();
+                request
+            });
+
+            let client = build_download_client(&Profile::default(), &url, http);
+            // the stub never answers
+            let request = tokio::spawn(async move { client.get(&url).send().await });
+            assert_eq!(received.await.unwrap(), start, "{http}");
+            request.abort();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_download_client_idle_timeout() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/veloren.zip", listener.local_addr().unwrap());
+        // sends the headers and a few bytes, then stalls with the connection open
+        let stalled = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![0; 4096];
+            let _ = stream.read(&mut request).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 100\r\n\r\nPK")
+                .await
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+            drop(stream);
+        });
+
+        let mut profile = Profile::default();
+        profile.download_idle_timeout = Some(1);
+        let client = build_download_client(&profile, &url, HttpVersion::Http1);
+        let started = std::time::Instant::now();
+        let response = client.get(&url).send().await.unwrap();
+        let err = response.bytes().await.unwrap_err();
+        assert!(err.is_timeout(), "{err:?}");
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+        stalled.abort();
+    }
+
+    #[test]
+    fn test_is_html() {
+        let mut headers = HeaderMap::new();
+        assert!(is_html(
+            &headers,
+            b"\n  <!DOCTYPE html><html><body>Login</body></html>"
+        ));
+        assert!(is_html(&headers, b"<HTML><head></head></HTML>"));
+        assert!(!is_html(&headers, b"0.17.0"));
+        assert!(!is_html(&headers, b"PK\x03\x04"));
+        headers.insert(CONTENT_TYPE, "Text/HTML; charset=utf-8".parse().unwrap());
+        assert!(is_html(&headers, b""));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_serves_html() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://{}/latest/linux/x86_64/weekly",
+            listener.local_addr().unwrap()
+        );
+        let server = tokio::spawn(crate::net::test_server::captive_portal(listener));
+
+        assert!(serves_html(&url, &Profile::default()).await);
+        server.abort();
+        let _ = server.await;
+        // unreachable servers are no web page
+        assert!(!serves_html(&url, &Profile::default()).await);
+    }
 }
@@ -1,7 +1,127 @@
 pub mod client;
 pub mod ping;
+pub mod pinning;
 pub mod server_list;
+#[cfg(test)]
+pub(crate) mod test_server;
 
 pub use client::*;
 
+use crate::consts::SUPPORTED_SERVER_API_VERSION;
+use std::sync::OnceLock;
+
 pub const DEFAULT_GAME_PORT: u16 = 14004;
+/// overrides the server API version the launcher expects, e.g. to test against a
+/// pre-release server without being asked to update Airshipper
+const SERVER_API_VERSION_ENV: &str = "AIRSHIPPER_SERVER_API_VERSION";
+
+/// The server API version this launcher supports, `None` if every version is
+/// accepted. Defaults to [`SUPPORTED_SERVER_API_VERSION`], developers can set
+/// `AIRSHIPPER_SERVER_API_VERSION` to another version or `any`.
+pub fn supported_server_api_version() -> Option<u32> {
+    static VERSION: OnceLock<Option<u32>> = OnceLock::new();
+    *VERSION.get_or_init(|| {
+        let Ok(value) = std::env::var(SERVER_API_VERSION_ENV) else {
+            return Some(SUPPORTED_SERVER_API_VERSION);
+        };
+        match parse_server_api_version(&value) {
+            Some(version) => {
+                tracing::warn!(
+                    ?version,
+                    "The supported server API version is overridden via \
+                     {SERVER_API_VERSION_ENV}, the update notice might be wrong"
+                );
+                version
+            },
+            None => {
+                tracing::warn!(?value, "Ignoring invalid {SERVER_API_VERSION_ENV}");
+                Some(SUPPORTED_SERVER_API_VERSION)
+            },
+        }
+    })
+}
+
+fn parse_server_api_version(value: &str) -> Option<Option<u32>> {
+    match value.trim() {
+        "any" => Some(None),
+        version => version.parse().ok().map(Some),
+    }
+}
+
+/// Checks that `address` looks like `host`, `host:port` or `[ipv6]:port`, returns a
+/// message suitable for the user otherwise.
+pub fn validate_server_address(address: &str) -> Result<(), &'static str> {
+    const INVALID_PORT: &str = "The port has to be a number between 1 and 65535";
+
+    let valid_port = |port: &str| matches!(port.parse::<u16>(), Ok(port) if port != 0);
+
+    if address.is_empty() {
+        return Err("Enter an address like example.com:14004");
+    }
+    if address.chars().any(char::is_whitespace) {
+        return Err("The address must not contain spaces");
+    }
+
+    let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+        let Some((ip, port)) = rest.split_once(']') else {
+            return Err("An IPv6 address is missing its closing bracket");
+        };
+        if ip.parse::<std::net::Ipv6Addr>().is_err() {
+            return Err("Invalid IPv6 address");
+        }
+        match port {
+            "" => return Ok(()),
+            port => match port.strip_prefix(':') {
+                Some(port) if valid_port(port) => return Ok(()),
+                _ => return Err(INVALID_PORT),
+            },
+        }
+    } else {
+        match address.split_once(':') {
+            Some((_, port)) if port.contains(':') => {
+                return Err("Put IPv6 addresses in brackets, e.g. [::1]:14004");
+            },
+            Some((host, port)) => (host, Some(port)),
+            None => (address, None),
+        }
+    };
+
+    if host.is_empty()
+        || !host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    {
+        return Err("Invalid host name");
+    }
+    match port {
+        Some(port) if !valid_port(port) => Err(INVALID_PORT),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_server_address() {
+        assert!(validate_server_address("server.veloren.net").is_ok());
+        assert!(validate_server_address("192.168.0.2:14004").is_ok());
+        assert!(validate_server_address("[::1]:14004").is_ok());
+        assert!(validate_server_address("[::1]").is_ok());
+        assert!(validate_server_address("").is_err());
+        assert!(validate_server_address("my server").is_err());
+        assert!(validate_server_address("localhost:0").is_err());
+        assert!(validate_server_address("localhost:port").is_err());
+        assert!(validate_server_address("::1").is_err());
+        assert!(validate_server_address(":14004").is_err());
+    }
+
+    #[test]
+    fn test_parse_server_api_version() {
+        assert_eq!(parse_server_api_version("2"), Some(Some(2)));
+        assert_eq!(parse_server_api_version(" any "), Some(None));
+        assert_eq!(parse_server_api_version("latest"), None);
+        assert_eq!(parse_server_api_version("-1"), None);
+    }
+}
@@ -0,0 +1,300 @@
+//! Optional certificate pinning for the download server.
+//!
+//! A pin is the base64 encoded SHA-256 hash of a DER encoded SubjectPublicKeyInfo,
+//! the same format as curl's `--pinnedpubkey sha256//<hash>`. The README shows how
+//! to obtain it with openssl.
+//!
+//! Once set, connections to the download server fail unless its certificate chain
+//! is trusted as usual *and* the leaf or an intermediate matches one of the pins.
+//! Other hosts, like the news feeds, are not affected.
+use crate::profiles::{HttpVersion, Profile};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, OtherError, RootCertStore,
+    SignatureScheme,
+    client::{
+        WebPkiServerVerifier,
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    },
+    crypto::ring,
+    pki_types::{CertificateDer, ServerName, UnixTime},
+};
+use sha2::{Digest, Sha256};
+use std::{
+    error::Error,
+    sync::{Arc, OnceLock},
+};
+
+/// Set once on startup, before the first request is made.
+static PINS: OnceLock<Pins> = OnceLock::new();
+
+#[derive(Debug)]
+struct Pins {
+    host: String,
+    hashes: Vec<[u8; 32]>,
+}
+
+/// The certificate chain of `0` does not contain a pinned key.
+#[derive(Debug, thiserror::Error)]
+#[error("the certificate of {0} does not match any pinned key")]
+pub struct PinMismatch(String);
+
+/// Pins the host of the download server of `profile` to its `pinned_keys`. Does
+/// nothing if no keys are configured. Has no effect once the first request has
+/// been made.
+pub fn pin_download_server(profile: &Profile) {
+    if profile.pinned_keys.is_empty() {
+        return;
+    }
+    let Some(host) = profile.download_host() else {
+        return;
+    };
+
+    // invalid pins are kept out of the list, but the host stays pinned, so a typo
+    // fails the connection instead of silently disabling pinning
+    let hashes = profile
+        .pinned_keys
+        .iter()
+        .filter_map(|pin| match parse_pin(pin) {
+            Some(hash) => Some(hash),
+            None => {
+                tracing::warn!(?pin, "Ignoring invalid pinned key");
+                None
+            },
+        })
+        .collect();
+    tracing::debug!(?host, "Pinning the certificate of the download server");
+    if PINS.set(Pins { host, hashes }).is_err() {
+        tracing::debug!("Certificate pins can't be changed once they are set");
+    }
+}
+
+/// TLS config for the reqwest clients, `None` unless a host is pinned.
+pub(crate) fn tls_config(http: HttpVersion) -> Option<ClientConfig> {
+    let pins = PINS.get()?;
+    let provider = Arc::new(ring::default_provider());
+    let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let verifier = WebPkiServerVerifier::builder_with_provider(
+        Arc::new(roots),
+        Arc::clone(&provider),
+    )
+    .build()
+    .expect("FATAL: Failed to build certificate verifier!");
+
+    let mut config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .expect("FATAL: Failed to build TLS config!")
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinningVerifier {
+            inner: verifier,
+            pins,
+        }))
+        .with_no_client_auth();
+    // reqwest only sets these for the TLS configs it builds itself
+    config.alpn_protocols = match http {
+        HttpVersion::Auto => vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+        HttpVersion::Http1 => vec![b"http/1.1".to_vec()],
+        HttpVersion::Http2 => vec![b"h2".to_vec()],
+    };
+    Some(config)
+}
+
+/// Returns the pinned host if `err` was caused by a [`PinMismatch`].
+pub(crate) fn pin_mismatch(err: &(dyn Error + 'static)) -> Option<String> {
+    let mut source = Some(err);
+    while let Some(mut err) = source {
+        // io errors don't report the error they wrap as their source
+        if let Some(inner) = err
+            .downcast_ref::<std::io::Error>()
+            .and_then(std::io::Error::get_ref)
+        {
+            err = inner;
+        }
+        if let Some(rustls::Error::InvalidCertificate(CertificateError::Other(
+            OtherError(other),
+        ))) = err.downcast_ref::<rustls::Error>()
+            && let Some(PinMismatch(host)) = other.downcast_ref::<PinMismatch>()
+        {
+            return Some(host.clone());
+        }
+        source = err.source();
+    }
+    None
+}
+
+/// Accepts the bare base64 hash as well as curl's `sha256//<hash>`.
+fn parse_pin(pin: &str) -> Option<[u8; 32]> {
+    let pin = pin.trim();
+    let pin = pin.strip_prefix("sha256//").unwrap_or(pin);
+    STANDARD.decode(pin).ok()?.try_into().ok()
+}
+
+#[derive(Debug)]
+struct PinningVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: &'static Pins,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+
+        let pinned = match server_name {
+            ServerName::DnsName(name) => {
+                name.as_ref().eq_ignore_ascii_case(&self.pins.host)
+            },
+            _ => false,
+        };
+        if !pinned {
+            return Ok(verified);
+        }
+        let matches = std::iter::once(end_entity)
+            .chain(intermediates)
+            .filter_map(|cert| spki(cert))
+            .any(|spki| {
+                let hash: [u8; 32] = Sha256::digest(spki).into();
+                self.pins.hashes.contains(&hash)
+            });
+        if matches {
+            Ok(verified)
+        } else {
+            tracing::error!(host = ?self.pins.host, "Certificate does not match any pin");
+            Err(rustls::Error::InvalidCertificate(CertificateError::Other(
+                OtherError(Arc::new(PinMismatch(self.pins.host.clone()))),
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// A DER value split off the front of some bytes.
+struct DerValue<'a> {
+    tag: u8,
+    /// the whole encoding including the header
+    encoded: &'a [u8],
+    content: &'a [u8],
+    /// the bytes after this value
+    rest: &'a [u8],
+}
+
+/// Splits the first DER value off `der`.
+fn der_value(der: &[u8]) -> Option<DerValue<'_>> {
+    let (&tag, rest) = der.split_first()?;
+    let (&len, rest) = rest.split_first()?;
+    let (len, rest) = if len < 0x80 {
+        (len as usize, rest)
+    } else {
+        let bytes = (len & 0x7f) as usize;
+        if bytes == 0 || bytes > 4 || rest.len() < bytes {
+            return None;
+        }
+        let (len, rest) = rest.split_at(bytes);
+        (len.iter().fold(0, |len, &b| len << 8 | b as usize), rest)
+    };
+    if rest.len() < len {
+        return None;
+    }
+    let header = der.len() - rest.len();
+    let (content, rest) = rest.split_at(len);
+    Some(DerValue {
+        tag,
+        encoded: &der[..header + len],
+        content,
+        rest,
+    })
+}
+
+/// The DER encoded SubjectPublicKeyInfo of an X.509 certificate.
+fn spki(cert: &[u8]) -> Option<&[u8]> {
+    const EXPLICIT_VERSION: u8 = 0xa0;
+
+    let cert = der_value(cert)?.content;
+    let tbs_certificate = der_value(cert)?.content;
+    let version = der_value(tbs_certificate)?;
+    // the version is optional
+    let mut fields = if version.tag == EXPLICIT_VERSION {
+        version.rest
+    } else {
+        tbs_certificate
+    };
+    // serial number, signature algorithm, issuer, validity, subject
+    for _ in 0..5 {
+        fields = der_value(fields)?.rest;
+    }
+    Some(der_value(fields)?.encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spki_pinning() {
+        let spki_der = [0x30, 0x03, 0x02, 0x01, 0x07];
+        #[rustfmt::skip]
+        let cert = [
+            0x30, 0x17,
+            // tbsCertificate
+            0x30, 0x15,
+            0xa0, 0x03, 0x02, 0x01, 0x02, // version
+            0x02, 0x01, 0x01, // serial number
+            0x30, 0x00, 0x30, 0x00, 0x30, 0x00, 0x30, 0x00,
+            0x30, 0x03, 0x02, 0x01, 0x07, // subject public key info
+        ];
+        assert_eq!(spki(&cert), Some(&spki_der[..]));
+        // without the optional version
+        let mut v1 = vec![0x30, 0x12, 0x30, 0x10];
+        v1.extend_from_slice(&cert[9..]);
+        assert_eq!(spki(&v1), Some(&spki_der[..]));
+        assert_eq!(spki(&cert[..20]), None);
+
+        let hash: [u8; 32] = Sha256::digest(spki_der).into();
+        let pin = STANDARD.encode(hash);
+        assert_eq!(parse_pin(&pin), Some(hash));
+        assert_eq!(parse_pin(&format!("sha256//{pin}")), Some(hash));
+        assert_eq!(parse_pin("c2hvcnQ="), None);
+
+        let err = std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            rustls::Error::InvalidCertificate(CertificateError::Other(OtherError(
+                Arc::new(PinMismatch("download.veloren.net".to_string())),
+            ))),
+        );
+        assert_eq!(pin_mismatch(&err).as_deref(), Some("download.veloren.net"));
+        assert_eq!(pin_mismatch(&std::io::Error::other("offline")), None);
+    }
+}
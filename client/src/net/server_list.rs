@@ -4,7 +4,7 @@ use veloren_serverbrowser_api::GameServerList;
 pub(crate) async fn fetch_server_list(url: String) -> Result<GameServerList> {
     let response = query(url).await?;
 
-    let server_list = response.json::<GameServerList>().await?;
+    let server_list = response.json::<GameServerList>()?;
 
     Ok(server_list)
 }
@@ -0,0 +1,58 @@
+//! A minimal HTTP/1.1 server for the tests, every connection gets one response and is
+//! closed.
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+pub(crate) struct Response {
+    pub status: &'static str,
+    /// extra header lines, each ending with `\r\n`
+    pub headers: String,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: "200 OK",
+            headers: String::new(),
+            body: body.into(),
+        }
+    }
+}
+
+/// Answers every request on `listener` with `respond`, which gets the request head
+/// in lowercase.
+pub(crate) async fn serve(listener: TcpListener, respond: impl Fn(&str) -> Response) {
+    loop {
+        let Ok((mut stream, _)) = listener.accept().await else {
+            return;
+        };
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => request.extend_from_slice(&buf[..n]),
+            }
+        }
+        let response = respond(&String::from_utf8_lossy(&request).to_lowercase());
+        let head = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+            response.status,
+            response.body.len(),
+            response.headers
+        );
+        let _ = stream.write_all(head.as_bytes()).await;
+        let _ = stream.write_all(&response.body).await;
+    }
+}
+
+/// A captive portal answering every request with its login page
+pub(crate) async fn captive_portal(listener: TcpListener) {
+    serve(listener, |_| {
+        Response::ok("<!DOCTYPE html>\n<html><body>Please log in</body></html>")
+    })
+    .await
+}
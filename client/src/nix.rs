@@ -3,18 +3,38 @@ use crate::{
     consts::{SERVER_CLI_FILE, VOXYGEN_FILE},
     profiles::PatchedInfo,
 };
-use std::{ffi::OsString, path::Path};
+use std::path::Path;
 
 const OS_RELEASE: &str = "/etc/os-release";
+const VOXYGEN_PATCHER_ENV: &str = "VELOREN_VOXYGEN_PATCHER";
+const SERVER_CLI_PATCHER_ENV: &str = "VELOREN_SERVER_CLI_PATCHER";
+/// patches every other executable, which is passed as its argument
+const EXTRA_PATCHER_ENV: &str = "VELOREN_EXTRA_PATCHER";
 
-/// Get patcher for patching voxygen.
-fn get_voxygen_patcher() -> Option<OsString> {
-    std::env::var_os("VELOREN_VOXYGEN_PATCHER")
+/// Environment variable of the patcher for `file`.
+fn patcher_env(file: &str) -> &'static str {
+    match file {
+        VOXYGEN_FILE => VOXYGEN_PATCHER_ENV,
+        SERVER_CLI_FILE => SERVER_CLI_PATCHER_ENV,
+        _ => EXTRA_PATCHER_ENV,
+    }
 }
 
-/// Get patcher for patching server-cli.
-fn get_server_patcher() -> Option<OsString> {
-    std::env::var_os("VELOREN_SERVER_CLI_PATCHER")
+/// Ensures the patchers of all `files` are configured, so we don't fail halfway
+/// through patching.
+pub fn check_patchers(files: &[String]) -> Result<()> {
+    let mut missing: Vec<_> = Vec::new();
+    for env in files.iter().map(|file| patcher_env(file)) {
+        if std::env::var_os(env).is_none() && !missing.contains(&env) {
+            missing.push(env);
+        }
+    }
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(ClientError::NixPatcherMissing(missing.join(" and ")))
+    }
 }
 
 /// Check if we are on NixOS.
@@ -29,15 +49,9 @@ pub fn is_nixos() -> Result<bool> {
 pub fn patch(profile_directory: &Path, file: &str) -> Result<PatchedInfo> {
     tracing::info!("Patching an executable file for NixOS");
 
-    let patcher = match file {
-        VOXYGEN_FILE => get_voxygen_patcher().ok_or_else(|| {
-            ClientError::Custom("patcher binary was not detected.".to_string())
-        })?,
-        SERVER_CLI_FILE => get_server_patcher().ok_or_else(|| {
-            ClientError::Custom("patcher binary was not detected.".to_string())
-        })?,
-        _ => return Err(ClientError::Custom("Unknown file to patch".to_string())),
-    };
+    let env = patcher_env(file);
+    let patcher = std::env::var_os(env)
+        .ok_or_else(|| ClientError::NixPatcherMissing(env.to_string()))?;
 
     // get pre-patched-crc32
     let patched_file = {
@@ -50,9 +64,12 @@ pub fn patch(profile_directory: &Path, file: &str) -> Result<PatchedInfo> {
 
     // Patch the file
     tracing::info!("Executing {patcher:?} on directory {profile_directory:?}");
-    let output = std::process::Command::new(patcher)
-        .current_dir(profile_directory)
-        .output()?;
+    let mut command = std::process::Command::new(patcher);
+    command.current_dir(profile_directory);
+    if env == EXTRA_PATCHER_ENV {
+        command.arg(file);
+    }
+    let output = command.output()?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1,11 +1,16 @@
-use crate::{Result, channels::Channel, consts, fs};
-use ron::ser::PrettyConfig;
+use crate::{
+    ClientError, Result,
+    channels::{Channel, STABLE_CHANNEL},
+    consts, fs,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     ffi::OsString,
     path::{Path, PathBuf},
     process::Stdio,
+    time::Duration,
 };
 use tokio::{fs::File, io::AsyncWriteExt, process::Command};
 use tracing::error;
@@ -35,18 +40,239 @@ pub struct Profile {
 
     /// used to avoid duplicate redownload of patched binaries on nixos
     pub patched_crc32s: Vec<PatchedInfo>,
+    /// fingerprint of the installed files after the last successful update
+    #[serde(default)]
+    pub install_fingerprint: Option<u32>,
+    /// update the user doesn't want to be asked about again, cleared once any update
+    /// is installed
+    #[serde(default)]
+    pub skipped_version: Option<String>,
+    /// when the download server was last asked for the latest version successfully
+    #[serde(default)]
+    pub last_checked: Option<DateTime<Utc>>,
+    /// when the game files were last updated
+    #[serde(default)]
+    pub last_updated: Option<DateTime<Utc>>,
+
+    /// trade download speed for a lower peak memory usage while updating
+    #[serde(default)]
+    pub low_memory: bool,
+
+    /// update the maps shipped with the game while preserving user created ones
+    #[serde(default)]
+    pub sync_shipped_maps: bool,
+    /// map files installed by airshipper, used to tell them apart from user maps
+    #[serde(default)]
+    pub shipped_maps: Vec<String>,
+
+    /// removes files which the game doesn't need after every update
+    #[serde(default)]
+    pub compaction: Compaction,
+    /// files removed by the compaction, updates skip them so they aren't downloaded
+    /// again
+    #[serde(default)]
+    pub compacted_files: Vec<String>,
+
+    /// how many previous game versions are copied before an update, for a rollback
+    /// via `airshipper rollback`. Every kept version needs as much space as the game
+    #[serde(default)]
+    pub keep_versions: usize,
+
+    /// look of the launcher
+    #[serde(default)]
+    pub theme: UiTheme,
+
+    /// zoom of the launcher, e.g. for HiDPI screens
+    #[serde(default)]
+    pub ui_scale: UiScale,
+
+    /// write the files of an update one at a time, roughly in the order of the zip, for
+    /// a less fragmented install at the cost of a slower update
+    #[serde(default)]
+    pub serial_unzip: bool,
+
+    /// store the cached remote file lists compressed, which saves space and loads
+    /// faster for installs with many files
+    #[serde(default)]
+    pub compact_file_lists: bool,
+
+    /// store the saved state and the changelog and news caches gzipped, plain files
+    /// are still read
+    #[serde(default)]
+    pub compress_stored_files: bool,
+
+    /// drives the progress bar of updates
+    #[serde(default)]
+    pub progress_metric: ProgressMetric,
+
+    /// write the timings of every batch and file of an update to
+    /// [`update_log_file`](crate::fs::update_log_file), see [`crate::update_log`]
+    #[serde(default)]
+    pub update_log: bool,
+
+    /// files at most this many bytes apart in the game zip are downloaded with a single
+    /// range request, including the bytes in between. Scattered small changes then need
+    /// fewer requests. Defaults to the gap of remozipsy, 0 in low memory mode, where it
+    /// is capped at 64 KiB
+    #[serde(default)]
+    pub max_batch_gap: Option<u64>,
+
+    /// render the launcher without antialiasing on the software renderer
+    #[serde(default)]
+    pub lite_ui: bool,
+
+    /// command the game is started with, e.g. `gamemoderun` or `mangohud`, see
+    /// [`Profile::launch_command`]
+    #[serde(default)]
+    pub launch_wrapper: String,
+    /// command links are opened with, e.g. `firefox`, the system default if empty. See
+    /// [`crate::io::browser::open_url`]
+    #[serde(default)]
+    pub browser_command: String,
+
+    /// files made executable after an update, patched on NixOS and signed on macOS,
+    /// relative to the install folder, see [`Profile::installed_executables`]
+    #[serde(default = "default_executables")]
+    pub executables: Vec<String>,
+
+    /// skip patching the binaries on NixOS, e.g. when running the game in an FHS env
+    #[serde(default)]
+    pub disable_nix_patching: bool,
+
+    /// whether the console window is shown on Windows
+    #[serde(default)]
+    pub console: ConsoleMode,
+
+    /// manually entered game server addresses, most recent first
+    #[serde(default)]
+    pub recent_servers: Vec<String>,
+
+    /// how many feeds and images are fetched at once, defaults to
+    /// [`DEFAULT_MAX_PARALLEL_FETCHES`](crate::net::DEFAULT_MAX_PARALLEL_FETCHES)
+    #[serde(default)]
+    pub max_parallel_fetches: Option<usize>,
+
+    /// how often fetching the latest version is tried before going offline, defaults
+    /// to [`DEFAULT_VERSION_FETCH_ATTEMPTS`](crate::update::DEFAULT_VERSION_FETCH_ATTEMPTS)
+    #[serde(default)]
+    pub version_fetch_attempts: Option<u32>,
+
+    /// seconds without any download or unzip progress before a stalled update is
+    /// restarted, defaults to
+    /// [`DEFAULT_DOWNLOAD_STALL_TIMEOUT`](crate::update::DEFAULT_DOWNLOAD_STALL_TIMEOUT),
+    /// `0` disables it
+    #[serde(default)]
+    pub download_stall_timeout: Option<u64>,
+
+    /// seconds a single download connection may go without receiving data before its
+    /// request fails and the sync is restarted, defaults to
+    /// [`DEFAULT_DOWNLOAD_IDLE_TIMEOUT`](crate::update::DEFAULT_DOWNLOAD_IDLE_TIMEOUT),
+    /// `0` disables it
+    #[serde(default)]
+    pub download_idle_timeout: Option<u64>,
+
+    /// rules applied to the game output before it is written to the launcher log
+    #[serde(default)]
+    pub log_rules: Vec<LogRule>,
+
+    /// warnings for environment variables on top of the built-in ones, see
+    /// [`Profile::env_var_warnings`]
+    #[serde(default)]
+    pub env_var_checks: Vec<EnvVarCheck>,
+
+    /// when to stop relaunching the game after it crashed repeatedly
+    #[serde(default)]
+    pub crash_guard: CrashGuard,
+
+    /// how long the rotated launcher logs are kept
+    #[serde(default)]
+    pub log_retention: LogRetention,
+
+    /// hide the launcher in the system tray instead of closing it, requires the `tray`
+    /// feature
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+
+    /// show the progress of an update in the window title
+    #[serde(default)]
+    pub progress_in_title: bool,
+
+    /// credentials for a download server behind authentication, see
+    /// [`Profile::download_auth`]
+    #[serde(default)]
+    pub download_auth: Option<DownloadAuth>,
+
+    /// base64 encoded SHA-256 hashes of public keys, the certificate chain of the
+    /// download server has to contain one of them, see [`crate::net::pinning`]
+    #[serde(default)]
+    pub pinned_keys: Vec<String>,
+
+    /// custom version and download URLs which replace the ones of `server`
+    #[serde(default)]
+    pub url_templates: UrlTemplates,
+
+    /// HTTP version of the requests to the download server
+    #[serde(default)]
+    pub http_version: HttpVersion,
+
+    /// free space kept on top of what an update writes, see [`SpaceMargin`]
+    #[serde(default)]
+    pub min_free_space_margin: SpaceMargin,
+
+    /// re-signs the game binaries after an update, only used on macOS
+    #[serde(default)]
+    pub macos_signing: MacosSigning,
+
+    /// how often the GUI refreshes the news, announcements and changelog
+    #[serde(default)]
+    pub refresh_intervals: RefreshIntervals,
+
+    /// updates which download at least this many bytes are confirmed, even when
+    /// updating without asking, see [`Profile::is_large_update`]
+    #[serde(default)]
+    pub large_update_threshold: Option<u64>,
+    /// seconds CLI confirmations wait for an answer before taking `prompt_default`, so
+    /// automated runs don't hang on an unexpected prompt. `None` waits forever
+    #[serde(default)]
+    pub prompt_timeout: Option<u64>,
+    /// answer of CLI confirmations which timed out, see `prompt_timeout`
+    #[serde(default)]
+    pub prompt_default: PromptAnswer,
+
+    /// install slot the game is updated and started from, `None` is the default slot,
+    /// see [`Profile::switch_slot`]
+    #[serde(default)]
+    pub slot: Option<String>,
+    /// install state of the slots which aren't active
+    #[serde(default)]
+    pub slots: Vec<InstallSlot>,
 
     #[serde(skip)]
     pub supported_wgpu_backends: Vec<WgpuBackend>,
 }
 
 const DEFAULT_PROFILE_NAME: &str = "default";
+/// name of the slot installed in the profile folder itself
+pub const DEFAULT_SLOT_NAME: &str = "default";
+/// replaced with the path of the game in the launch wrapper
+const WRAPPER_GAME: &str = "{game}";
+/// replaced with the install folder in the launch wrapper
+const WRAPPER_INSTALL_DIR: &str = "{install_dir}";
+
+/// The game and server binaries, the default of [`Profile::executables`]
+pub fn default_executables() -> Vec<String> {
+    vec![
+        consts::VOXYGEN_FILE.to_owned(),
+        consts::SERVER_CLI_FILE.to_owned(),
+    ]
+}
+
 impl Default for Profile {
     fn default() -> Self {
         Profile::new(
             DEFAULT_PROFILE_NAME.to_owned(),
             Server::Production,
-            Channel("weekly".to_owned()),
+            Channel(STABLE_CHANNEL.to_owned()),
         )
     }
 }
@@ -84,6 +310,22 @@ static WGPU_BACKENDS: &[WgpuBackend] = &[WgpuBackend::Auto, WgpuBackend::Vulkan]
 #[cfg(target_os = "macos")]
 static WGPU_BACKENDS: &[WgpuBackend] = &[WgpuBackend::Auto, WgpuBackend::Metal];
 
+/// values of `WGPU_BACKEND` and the backends they select, including the aliases wgpu
+/// accepts
+const WGPU_BACKEND_NAMES: &[(&str, WgpuBackend)] = &[
+    ("gl", WgpuBackend::OpenGl),
+    ("opengl", WgpuBackend::OpenGl),
+    ("gles", WgpuBackend::OpenGl),
+    ("dx11", WgpuBackend::DX11),
+    ("d3d11", WgpuBackend::DX11),
+    ("dx12", WgpuBackend::DX12),
+    ("d3d12", WgpuBackend::DX12),
+    ("metal", WgpuBackend::Metal),
+    ("mtl", WgpuBackend::Metal),
+    ("vulkan", WgpuBackend::Vulkan),
+    ("vk", WgpuBackend::Vulkan),
+];
+
 pub async fn query_wgpu_backends(process_path: &Path) -> Vec<WgpuBackend> {
     if let Some(res) = Command::new(process_path)
         .arg("list-wgpu-backends")
@@ -148,6 +390,673 @@ pub enum LogLevel {
 pub static LOG_LEVELS: &[LogLevel] =
     &[LogLevel::Default, LogLevel::Debug, LogLevel::Trace];
 
+/// HTTP version of the requests to the download server
+#[derive(
+    Debug,
+    Default,
+    derive_more::Display,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum HttpVersion {
+    /// HTTP/2 if the server supports it, HTTP/1.1 otherwise
+    #[default]
+    Auto,
+    /// for proxies and CDNs which stall on HTTP/2
+    #[display("HTTP/1.1")]
+    Http1,
+    /// HTTP/2 without falling back, all requests share one connection
+    #[display("HTTP/2")]
+    Http2,
+}
+
+/// Look of the launcher, see [`crate::gui::style::AirshipperTheme`]
+#[derive(
+    Debug,
+    Default,
+    derive_more::Display,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum UiTheme {
+    #[default]
+    Default,
+    /// black and white with yellow highlights and clear borders, for low vision
+    #[display("High contrast")]
+    HighContrast,
+}
+
+pub static UI_THEMES: &[UiTheme] = &[UiTheme::Default, UiTheme::HighContrast];
+
+/// What the progress bar of an update shows
+#[derive(
+    Debug,
+    Default,
+    derive_more::Display,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum ProgressMetric {
+    /// the current step, downloading and unzipping fill the bar one after another
+    #[default]
+    #[display("Current step")]
+    Step,
+    /// downloading and unzipping together, weighted by the compressed and
+    /// decompressed bytes, see [`crate::update::combined_percent`]
+    #[display("Whole update")]
+    Combined,
+}
+
+pub static PROGRESS_METRICS: &[ProgressMetric] =
+    &[ProgressMetric::Step, ProgressMetric::Combined];
+
+/// Answer of a CLI confirmation which timed out, see [`Profile::prompt_timeout`]
+#[derive(
+    Debug,
+    Default,
+    derive_more::Display,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum PromptAnswer {
+    #[display("yes")]
+    Yes,
+    /// nothing is downloaded without an explicit answer
+    #[default]
+    #[display("no")]
+    No,
+}
+
+impl std::str::FromStr for PromptAnswer {
+    type Err = String;
+
+    fn from_str(answer: &str) -> std::result::Result<Self, Self::Err> {
+        match answer.trim().to_lowercase().as_str() {
+            "y" | "yes" => Ok(Self::Yes),
+            "n" | "no" => Ok(Self::No),
+            _ => Err(format!("'{answer}' is neither yes nor no")),
+        }
+    }
+}
+
+/// Zoom of the launcher in percent, applies to text and layout alike
+#[derive(
+    Debug, derive_more::Display, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+#[display("{_0}%")]
+pub struct UiScale(pub u16);
+
+impl UiScale {
+    pub const MIN: u16 = 50;
+    pub const MAX: u16 = 200;
+
+    /// Scale factor of the window, values outside of the supported range are clamped
+    pub fn factor(self) -> f64 {
+        f64::from(self.0.clamp(Self::MIN, Self::MAX)) / 100.0
+    }
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        Self(100)
+    }
+}
+
+pub static UI_SCALES: &[UiScale] = &[
+    UiScale(75),
+    UiScale(90),
+    UiScale(100),
+    UiScale(110),
+    UiScale(125),
+    UiScale(150),
+    UiScale(175),
+    UiScale(200),
+];
+
+/// Visibility of the console window on Windows
+#[derive(
+    Debug,
+    Default,
+    derive_more::Display,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum ConsoleMode {
+    /// only shown when Airshipper was started from a console
+    #[default]
+    Auto,
+    /// always shown, the game logs are printed to it
+    Show,
+    /// always hidden, even when started from a console
+    Hide,
+}
+
+/// Reclassifies or suppresses game log lines containing `contains`, e.g.
+/// `(contains: "wgpu_hal", action: Suppress)`. The first matching rule wins.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRule {
+    pub contains: String,
+    pub action: LogRuleAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogRuleAction {
+    Suppress,
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// Removes files matching `patterns` from the install after every update, e.g. debug
+/// symbols which are only needed to debug the game. Patterns use `*` for any part of a
+/// file name and `**` for any number of folders. Patterns without a `/` match the file
+/// name anywhere in the install.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Compaction {
+    pub enabled: bool,
+    pub patterns: Vec<String>,
+}
+
+impl Default for Compaction {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            patterns: ["*.pdb", "*.debug", "*.dwp", "**/*.dSYM/**"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl Profile {
+    /// Whether an update downloading `download_bytes` has to be confirmed, e.g. on a
+    /// metered connection. A threshold of `0` confirms every update.
+    pub fn is_large_update(&self, download_bytes: u64) -> bool {
+        self.large_update_threshold
+            .is_some_and(|threshold| download_bytes >= threshold)
+    }
+
+    /// Enables or disables the compaction. Once disabled, the next update restores the
+    /// compacted files.
+    pub fn set_compaction(&mut self, enabled: bool) {
+        self.compaction.enabled = enabled;
+        if !enabled && !self.compacted_files.is_empty() {
+            self.compacted_files.clear();
+            self.install_fingerprint = None;
+        }
+    }
+}
+
+/// The game is not relaunched automatically once it exited with an error
+/// `max_crashes` times within `window_secs` seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashGuard {
+    pub max_crashes: u32,
+    pub window_secs: u64,
+}
+
+impl Default for CrashGuard {
+    fn default() -> Self {
+        Self {
+            max_crashes: 3,
+            window_secs: 60,
+        }
+    }
+}
+
+/// The launcher log is rotated on startup once it grew larger than
+/// [`MAX_LOG_SIZE`](crate::logger::MAX_LOG_SIZE). Only the newest `max_files` rotated
+/// logs which are at most `max_days` old are kept, `0` disables either limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRetention {
+    pub max_files: usize,
+    pub max_days: u64,
+}
+
+impl Default for LogRetention {
+    fn default() -> Self {
+        Self {
+            max_files: 5,
+            max_days: 30,
+        }
+    }
+}
+
+/// How often the GUI refreshes its panels in the background, in minutes. Panels are
+/// only fetched on startup unless `enabled`, an interval of `0` disables the refresh
+/// of a single panel. Refreshes pause while an update is downloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefreshIntervals {
+    pub enabled: bool,
+    pub news: u64,
+    pub community: u64,
+    /// short by default to show server alerts in time
+    pub announcements: u64,
+    pub changelog: u64,
+}
+
+impl Default for RefreshIntervals {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            news: 60,
+            community: 60,
+            announcements: 15,
+            changelog: 60,
+        }
+    }
+}
+
+impl RefreshIntervals {
+    /// longest interval in minutes, a week
+    pub const MAX_MINUTES: u64 = 7 * 24 * 60;
+
+    /// Time between two refreshes of a panel refreshed every `minutes`. Intervals
+    /// edited into the configuration are capped at [`Self::MAX_MINUTES`].
+    pub fn period(minutes: u64) -> Duration {
+        Duration::from_secs(minutes.min(Self::MAX_MINUTES) * 60)
+    }
+}
+
+impl std::str::FromStr for RefreshIntervals {
+    type Err = String;
+
+    /// Accepts `off`, or the intervals of the news, community, announcements and
+    /// changelog panels like `60 60 15 60`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("off") {
+            return Ok(Self::default());
+        }
+        let intervals = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<std::result::Result<Vec<u64>, _>>()
+            .map_err(|_| format!("'{s}' contains an invalid number of minutes"))?;
+        if intervals.iter().any(|minutes| *minutes > Self::MAX_MINUTES) {
+            return Err(format!(
+                "'{s}' contains an interval longer than {} minutes",
+                Self::MAX_MINUTES
+            ));
+        }
+        match intervals[..] {
+            [news, community, announcements, changelog] => Ok(Self {
+                enabled: true,
+                news,
+                community,
+                announcements,
+                changelog,
+            }),
+            _ => Err(format!("'{s}' is neither 'off' nor four intervals")),
+        }
+    }
+}
+
+impl std::fmt::Display for RefreshIntervals {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.enabled {
+            write!(
+                f,
+                "{} {} {} {}",
+                self.news, self.community, self.announcements, self.changelog
+            )
+        } else {
+            write!(f, "off")
+        }
+    }
+}
+
+/// Free space an update leaves on the drive, on top of the size of the files it writes.
+/// Updates don't start unless it is available, as temporary files and filesystem
+/// overhead need more space than the files themselves. A too small margin risks a
+/// full disk close to the end of an update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpaceMargin {
+    Bytes(u64),
+    /// percent of the size of the files the update writes
+    Percent(u8),
+}
+
+impl Default for SpaceMargin {
+    fn default() -> Self {
+        Self::Bytes(1_000_000_000)
+    }
+}
+
+impl SpaceMargin {
+    /// Space needed to write `bytes` while keeping the margin.
+    pub fn required_space(self, bytes: u64) -> u64 {
+        let margin = match self {
+            Self::Bytes(margin) => margin,
+            Self::Percent(percent) => bytes / 100 * percent as u64,
+        };
+        bytes.saturating_add(margin)
+    }
+}
+
+impl std::str::FromStr for SpaceMargin {
+    type Err = String;
+
+    /// Accepts a size like `2 GB` or `500MiB`, or a percentage like `10%`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => percent
+                .trim()
+                .parse()
+                .map(Self::Percent)
+                .map_err(|_| format!("'{s}' is not a percentage like 10%")),
+            None => s
+                .parse::<bytesize::ByteSize>()
+                .map(|size| Self::Bytes(size.as_u64()))
+                .map_err(|_| format!("'{s}' is neither a size like 2 GB nor like 10%")),
+        }
+    }
+}
+
+impl std::fmt::Display for SpaceMargin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bytes(bytes) => write!(f, "{}", crate::logger::pretty_bytes(*bytes)),
+            Self::Percent(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+/// Signs the game binaries after they were downloaded on macOS, e.g. when Gatekeeper
+/// refuses to start them otherwise.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MacosSigning {
+    /// run for every binary with its path appended, e.g. `codesign --force --sign -`
+    /// or a script. Signing is disabled if empty.
+    pub command: String,
+    /// fail the update if signing fails instead of only logging a warning
+    pub required: bool,
+}
+
+impl MacosSigning {
+    pub fn is_enabled(&self) -> bool {
+        !self.command.trim().is_empty()
+    }
+}
+
+impl std::fmt::Display for MacosSigning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.is_enabled(), self.required) {
+            (false, _) => write!(f, "disabled"),
+            (true, false) => write!(f, "{}", self.command),
+            (true, true) => write!(f, "{} (required)", self.command),
+        }
+    }
+}
+
+/// Custom URLs of the download server, e.g. to distribute a fork without recompiling
+/// the launcher. Placeholders in braces are replaced when the URL is used:
+/// `{os}` and `{arch}` with the platform (e.g. `linux`/`x86_64`), `{channel}` with
+/// the selected channel and, in `download` only, `{version}` with the version that
+/// the `version` URL returned. Unset templates use the built-in URLs.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrlTemplates {
+    /// returns the latest version of a channel as plain text, has to contain
+    /// `{channel}`
+    pub version: Option<String>,
+    /// zip archive of a version, has to contain `{channel}` or `{version}`
+    pub download: Option<String>,
+}
+
+impl UrlTemplates {
+    /// Checks the templates before they are stored, so a typo doesn't point the
+    /// launcher at a wrong URL.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if let Some(version) = &self.version {
+            validate_template(version, &["os", "arch", "channel"], &["channel"])?;
+        }
+        if let Some(download) = &self.download {
+            validate_template(download, &["os", "arch", "channel", "version"], &[
+                "channel", "version",
+            ])?;
+        }
+        Ok(())
+    }
+}
+
+/// `template` may only use `known` placeholders and has to contain one of `required`.
+fn validate_template(
+    template: &str,
+    known: &[&str],
+    required: &[&str],
+) -> std::result::Result<(), String> {
+    let mut placeholders = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err(format!("'{template}' contains an unclosed placeholder"));
+        };
+        placeholders.push(&rest[start + 1..start + len]);
+        rest = &rest[start + len + 1..];
+    }
+
+    let braced = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| format!("{{{name}}}"))
+            .collect::<Vec<_>>()
+    };
+    if let Some(unknown) = placeholders.iter().find(|p| !known.contains(p)) {
+        return Err(format!(
+            "'{template}' contains the unknown placeholder {{{unknown}}}, supported are \
+             {}",
+            braced(known).join(", ")
+        ));
+    }
+    if !placeholders.iter().any(|p| required.contains(p)) {
+        return Err(format!(
+            "'{template}' has to contain {}",
+            braced(required).join(" or ")
+        ));
+    }
+    let example = known.iter().fold(template.to_string(), |url, name| {
+        url.replace(&format!("{{{name}}}"), "x")
+    });
+    match url::Url::parse(&example) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+        _ => Err(format!("'{template}' is not an http(s) URL")),
+    }
+}
+
+/// Credentials sent to the download server, e.g. for forks hosting the game
+/// privately. They are never printed, not even by the [`Debug`] impl.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DownloadAuth {
+    Bearer(String),
+    Basic {
+        user: String,
+        password: Option<String>,
+    },
+}
+
+impl std::fmt::Debug for DownloadAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadAuth::Bearer(_) => write!(f, "Bearer(<redacted>)"),
+            DownloadAuth::Basic { .. } => write!(f, "Basic(<redacted>)"),
+        }
+    }
+}
+
+/// A named install next to the default one, e.g. to keep a test build without
+/// replacing the regular game. Each slot has its own folder and is updated and
+/// verified on its own, the cached file lists are shared. The state of the active slot
+/// lives in the profile itself, the other slots keep it here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallSlot {
+    pub name: String,
+    pub server: Server,
+    pub channel: Channel,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub install_fingerprint: Option<u32>,
+    #[serde(default)]
+    pub skipped_version: Option<String>,
+    #[serde(default)]
+    pub last_checked: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_updated: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub patched_crc32s: Vec<PatchedInfo>,
+    #[serde(default)]
+    pub shipped_maps: Vec<String>,
+    #[serde(default)]
+    pub compacted_files: Vec<String>,
+}
+
+/// Slot names are used as folder names, so only simple ones are allowed.
+pub fn validate_slot_name(name: &str) -> std::result::Result<(), String> {
+    if name.is_empty() || name.len() > 32 {
+        return Err("slot names have to be 1 to 32 characters long".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "'{name}' may only contain letters, digits, '-' and '_'"
+        ));
+    }
+    Ok(())
+}
+
+impl Profile {
+    /// Name of the active install slot
+    pub fn slot_name(&self) -> &str {
+        self.slot.as_deref().unwrap_or(DEFAULT_SLOT_NAME)
+    }
+
+    /// Whether `other` updates the same install from the same source. The result of an
+    /// update must not be applied to another install, see
+    /// [`crate::gui::components::GamePanelComponent`].
+    pub fn same_install(&self, other: &Profile) -> bool {
+        self.name == other.name
+            && self.slot == other.slot
+            && self.server == other.server
+            && self.channel == other.channel
+    }
+
+    /// Names of all install slots, the default one first
+    pub fn slot_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = std::iter::once(self.slot_name())
+            .chain(self.slots.iter().map(|slot| slot.name.as_str()))
+            .map(str::to_owned)
+            .collect();
+        names.sort_by_key(|name| (name != DEFAULT_SLOT_NAME, name.clone()));
+        names
+    }
+
+    /// Makes `name` the active install slot. The install state of the current slot is
+    /// kept, so switching back doesn't download the game again. A slot which doesn't
+    /// exist yet is created with the server and channel of the current one and is
+    /// empty until the next update.
+    pub fn switch_slot(&mut self, name: &str) -> Result<()> {
+        validate_slot_name(name).map_err(ClientError::Custom)?;
+        if name == self.slot_name() {
+            return Ok(());
+        }
+
+        let target = match self.slots.iter().position(|slot| slot.name == name) {
+            Some(idx) => self.slots.remove(idx),
+            None => InstallSlot {
+                name: name.to_owned(),
+                server: self.server,
+                channel: self.channel.clone(),
+                version: None,
+                install_fingerprint: None,
+                skipped_version: None,
+                last_checked: None,
+                last_updated: None,
+                patched_crc32s: Vec::new(),
+                shipped_maps: Vec::new(),
+                compacted_files: Vec::new(),
+            },
+        };
+        let current = InstallSlot {
+            name: self.slot_name().to_owned(),
+            server: self.server,
+            channel: self.channel.clone(),
+            version: self.version.take(),
+            install_fingerprint: self.install_fingerprint.take(),
+            skipped_version: self.skipped_version.take(),
+            last_checked: self.last_checked.take(),
+            last_updated: self.last_updated.take(),
+            patched_crc32s: std::mem::take(&mut self.patched_crc32s),
+            shipped_maps: std::mem::take(&mut self.shipped_maps),
+            compacted_files: std::mem::take(&mut self.compacted_files),
+        };
+        self.slots.push(current);
+
+        self.slot = (target.name != DEFAULT_SLOT_NAME).then_some(target.name);
+        self.server = target.server;
+        self.channel = target.channel;
+        self.version = target.version;
+        self.install_fingerprint = target.install_fingerprint;
+        self.skipped_version = target.skipped_version;
+        self.last_checked = target.last_checked;
+        self.last_updated = target.last_updated;
+        self.patched_crc32s = target.patched_crc32s;
+        self.shipped_maps = target.shipped_maps;
+        self.compacted_files = target.compacted_files;
+        tracing::info!("Switched to install slot {}", self.slot_name());
+        Ok(())
+    }
+
+    /// Forgets an inactive install slot and returns its folder, which is left for the
+    /// caller to remove. The default and the active slot can't be removed.
+    pub fn remove_slot(&mut self, name: &str) -> Result<PathBuf> {
+        if name == DEFAULT_SLOT_NAME || name == self.slot_name() {
+            return Err(ClientError::Custom(format!(
+                "The {name} slot can't be removed, switch to another slot first"
+            )));
+        }
+        let idx = self
+            .slots
+            .iter()
+            .position(|slot| slot.name == name)
+            .ok_or_else(|| {
+                ClientError::Custom(format!("There is no slot named {name}"))
+            })?;
+        self.slots.remove(idx);
+        Ok(fs::slot_path(&self.name, name))
+    }
+}
+
 impl Server {
     pub fn url(&self) -> &str {
         match self {
@@ -171,66 +1080,269 @@ impl Profile {
             env_vars: String::new(),
             assets_override: None,
             patched_crc32s: Vec::new(),
+            install_fingerprint: None,
+            skipped_version: None,
+            last_checked: None,
+            last_updated: None,
+            low_memory: false,
+            sync_shipped_maps: false,
+            shipped_maps: Vec::new(),
+            compaction: Compaction::default(),
+            compacted_files: Vec::new(),
+            compact_file_lists: false,
+            compress_stored_files: false,
+            progress_metric: ProgressMetric::default(),
+            update_log: false,
+            max_batch_gap: None,
+            serial_unzip: false,
+            keep_versions: 0,
+            theme: UiTheme::default(),
+            ui_scale: UiScale::default(),
+            lite_ui: false,
+            launch_wrapper: String::new(),
+            executables: default_executables(),
+            browser_command: String::new(),
+            disable_nix_patching: false,
+            console: ConsoleMode::Auto,
+            recent_servers: Vec::new(),
+            max_parallel_fetches: None,
+            version_fetch_attempts: None,
+            download_stall_timeout: None,
+            download_idle_timeout: None,
+            log_rules: Vec::new(),
+            env_var_checks: Vec::new(),
+            crash_guard: CrashGuard::default(),
+            log_retention: LogRetention::default(),
+            minimize_to_tray: false,
+            progress_in_title: false,
+            download_auth: None,
+            pinned_keys: Vec::new(),
+            url_templates: UrlTemplates::default(),
+            http_version: HttpVersion::default(),
+            min_free_space_margin: SpaceMargin::default(),
+            macos_signing: MacosSigning::default(),
+            refresh_intervals: RefreshIntervals::default(),
+            large_update_threshold: None,
+            prompt_timeout: None,
+            prompt_default: PromptAnswer::default(),
+            slot: None,
+            slots: Vec::new(),
             supported_wgpu_backends: Vec::new(),
         }
     }
 
     pub fn load() -> Self {
         fs::verify_cache();
-        let saved_state_file = fs::savedstate_file();
-        match std::fs::File::open(&saved_state_file) {
-            Ok(file) => {
-                match ron::de::from_reader(file) {
-                    Ok(profile) => {
-                        // Rust type inference magic
-                        let mut profile: Profile = profile;
-                        profile.reload_wgpu_backends();
-                        profile
-                    },
-                    Err(e) => {
-                        tracing::debug!(
-                            "Decoding state failed. Falling back to default: {}",
-                            e
-                        );
-                        Self::default()
-                    },
+        if let Some(dir) = fs::savedstate_file().parent() {
+            fs::remove_orphaned_temp_files(dir);
+        }
+        let profile = Self::load_state();
+        crate::logger::prune_logs(fs::log_path_file(), &profile.log_retention);
+        profile
+    }
+
+    fn load_state() -> Self {
+        match Self::load_from(&fs::savedstate_file()) {
+            Ok(mut profile) => {
+                fs::set_compress_stored_files(profile.compress_stored_files);
+                profile.reload_wgpu_backends();
+                if let Err(e) = profile.url_templates.validate() {
+                    tracing::warn!("Ignoring the custom download URLs: {e}");
+                    profile.url_templates = UrlTemplates::default();
                 }
+                profile
             },
             Err(e) => {
                 tracing::debug!(
                     ?e,
-                    "Failed to read saved state from {}, falling back to default state",
-                    saved_state_file.to_string_lossy()
+                    "Failed to load saved state from {}, falling back to default state",
+                    fs::savedstate_file().to_string_lossy()
                 );
                 Self::default()
             },
         }
     }
 
-    pub async fn save(self) -> Result<()> {
-        let data = tokio::task::block_in_place(|| {
-            ron::ser::to_string_pretty(&self, PrettyConfig::default())
-        })?;
-        let mut file = File::create(fs::savedstate_file()).await?;
-        file.write_all(data.as_bytes()).await?;
-        file.sync_all().await?;
+    /// Reads the saved state without touching the cache or querying the game. A corrupt
+    /// saved state is left in place for the next [`Profile::load`].
+    pub fn load_saved_state() -> Result<Self> {
+        fs::peek_ron(&fs::savedstate_file())
+    }
 
-        Ok(())
+    fn load_from(path: &Path) -> Result<Self> {
+        fs::read_ron(path)
+    }
+
+    pub async fn save(self) -> Result<()> {
+        self.save_ref().await
     }
 
     pub async fn save_ref(&self) -> Result<()> {
-        let data = tokio::task::block_in_place(|| {
-            ron::ser::to_string_pretty(self, PrettyConfig::default())
-        })?;
-        let mut file = File::create(fs::savedstate_file()).await?;
-        file.write_all(data.as_bytes()).await?;
+        // the caches follow the setting as soon as it is saved
+        fs::set_compress_stored_files(self.compress_stored_files);
+        self.save_to(&fs::savedstate_file()).await
+    }
+
+    /// Writes to `path`, compressed if the profile asks for it.
+    async fn save_to(&self, path: &Path) -> Result<()> {
+        self.write_to(path, self.compress_stored_files).await
+    }
+
+    /// Writes to a temporary file first and renames it afterwards, so a crash while
+    /// saving never leaves a torn saved state behind. Every save uses its own temporary
+    /// file, the last rename wins if the GUI and an update save at the same time.
+    async fn write_to(&self, path: &Path, compress: bool) -> Result<()> {
+        let data = tokio::task::block_in_place(|| fs::encode_stored(self, compress))?;
+        let tmp = fs::temp_path(path);
+
+        let mut file = File::create(&tmp).await?;
+        file.write_all(&data).await?;
         file.sync_all().await?;
+        drop(file);
+        if let Err(e) = tokio::fs::rename(&tmp, path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(e.into());
+        }
 
         Ok(())
     }
 
+    /// Saves the progress of an ongoing update of this install. The saved state is read
+    /// again first, so settings changed in the meantime are kept. Only the install
+    /// fingerprint is cleared, which makes the next start verify all files, and the
+    /// shipped maps are updated. The new version is saved once the update completes.
+    pub async fn save_update_progress(
+        &self,
+        shipped_maps: Option<Vec<String>>,
+    ) -> Result<()> {
+        self.save_update_progress_to(&fs::savedstate_file(), shipped_maps)
+            .await
+    }
+
+    async fn save_update_progress_to(
+        &self,
+        path: &Path,
+        shipped_maps: Option<Vec<String>>,
+    ) -> Result<()> {
+        let mut saved = match fs::read_ron_async::<Profile>(path).await {
+            Ok(saved) if saved.same_install(self) => saved,
+            Ok(_) => {
+                tracing::debug!("Install switched during the update, progress not saved");
+                return Ok(());
+            },
+            Err(_) => Profile {
+                version: None,
+                ..self.clone()
+            },
+        };
+        saved.install_fingerprint = None;
+        if let Some(maps) = shipped_maps {
+            saved.shipped_maps = maps;
+        }
+        saved.save_to(path).await
+    }
+
+    /// Writes the configuration to `path`, e.g. to carry it over to a reinstalled OS.
+    /// The install state is left out unless `install_state` is set and the download
+    /// credentials unless `credentials` is set, see [`Profile::without_install_state`].
+    /// Exports are never compressed, so they can be read and edited.
+    pub async fn export(
+        &self,
+        path: &Path,
+        install_state: bool,
+        credentials: bool,
+    ) -> Result<()> {
+        let mut exported = match install_state {
+            true => self.clone(),
+            false => self.clone().without_install_state(),
+        };
+        exported.download_auth = match credentials {
+            true => self.download_auth.clone(),
+            false => None,
+        };
+        exported.write_to(path, false).await
+    }
+
+    /// Clears the fields which describe the game installed on this machine: the
+    /// installed and skipped version, the install fingerprint, when it was checked and
+    /// updated, the NixOS patches, the shipped maps, the compacted files and the install
+    /// slots. The download credentials are cleared as well, as they are stored in plain
+    /// text. Everything else is portable, though paths like `assets_override` or the
+    /// `launch_wrapper` might not exist on another machine.
+    pub fn without_install_state(mut self) -> Self {
+        self.download_auth = None;
+        self.slot = None;
+        self.slots.clear();
+        self.version = None;
+        self.install_fingerprint = None;
+        self.skipped_version = None;
+        self.last_checked = None;
+        self.last_updated = None;
+        self.patched_crc32s.clear();
+        self.shipped_maps.clear();
+        self.compacted_files.clear();
+        self
+    }
+
+    /// Reads a configuration written by [`Profile::export`] and validates it, so it can
+    /// replace `self`. The install state and download credentials of `self` are kept
+    /// unless the file contains them. A malformed file is left where it is.
+    pub fn import(&self, path: &Path) -> Result<Profile> {
+        let mut imported: Profile =
+            ron::from_str(&fs::decode_stored(&std::fs::read(path)?)?)?;
+        imported
+            .url_templates
+            .validate()
+            .map_err(|e| ClientError::Custom(format!("Invalid url_templates: {e}")))?;
+        if let Some(e) = parse_env_vars(&imported.env_vars).1.first() {
+            return Err(ClientError::Custom(format!("Invalid env_vars: {e}")));
+        }
+
+        if imported.version.is_none() {
+            imported.version = self.version.clone();
+            imported.install_fingerprint = self.install_fingerprint;
+            imported.skipped_version = self.skipped_version.clone();
+            imported.last_checked = self.last_checked;
+            imported.last_updated = self.last_updated;
+            imported.patched_crc32s = self.patched_crc32s.clone();
+            imported.shipped_maps = self.shipped_maps.clone();
+            imported.compacted_files = self.compacted_files.clone();
+            imported.slot = self.slot.clone();
+            imported.slots = self.slots.clone();
+        }
+        if imported.download_auth.is_none() {
+            imported.download_auth = self.download_auth.clone();
+        }
+        imported.supported_wgpu_backends = self.supported_wgpu_backends.clone();
+        if imported.wgpu_backend != WgpuBackend::Auto
+            && !imported.supported_wgpu_backends.is_empty()
+            && !imported
+                .supported_wgpu_backends
+                .contains(&imported.wgpu_backend)
+        {
+            tracing::warn!(
+                "Graphics backend {} is not supported here, using Auto",
+                imported.wgpu_backend
+            );
+            imported.wgpu_backend = WgpuBackend::Auto;
+        }
+        Ok(imported)
+    }
+
+    /// Saves the configuration to [`fs::savedstate_backup_file`], e.g. before it is
+    /// replaced by an import.
+    pub async fn backup(&self) -> Result<PathBuf> {
+        let path = fs::savedstate_backup_file();
+        self.save_to(&path).await?;
+        Ok(path)
+    }
+
+    /// Install folder of the active slot
     pub fn directory(&self) -> PathBuf {
-        fs::profile_path(&self.name)
+        match &self.slot {
+            Some(slot) => fs::slot_path(&self.name, slot),
+            None => fs::profile_path(&self.name),
+        }
     }
 
     /// Returns path to voxygen binary.
@@ -245,25 +1357,46 @@ impl Profile {
         self.directory().join(consts::LOGS_DIR)
     }
 
-    /// Returns the download url for this profile
-    pub fn download_url(&self) -> String {
-        format!(
-            "{}/latest/{}/{}/{}",
-            self.server.url(),
-            std::env::consts::OS,
-            std::env::consts::ARCH,
-            self.channel
-        )
+    /// Returns the download url of `version` for this profile
+    pub fn download_url(&self, version: &str) -> String {
+        match &self.url_templates.download {
+            Some(template) => self.fill_template(template).replace("{version}", version),
+            None => format!(
+                "{}/latest/{}/{}/{}",
+                self.server.url(),
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+                self.channel
+            ),
+        }
     }
 
     pub(crate) fn version_url(&self) -> String {
-        format!(
-            "{}/version/{}/{}/{}",
-            self.server.url(),
-            std::env::consts::OS,
-            std::env::consts::ARCH,
-            self.channel
-        )
+        match &self.url_templates.version {
+            Some(template) => self.fill_template(template),
+            None => format!(
+                "{}/version/{}/{}/{}",
+                self.server.url(),
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+                self.channel
+            ),
+        }
+    }
+
+    fn fill_template(&self, template: &str) -> String {
+        template
+            .replace("{os}", std::env::consts::OS)
+            .replace("{arch}", std::env::consts::ARCH)
+            .replace("{channel}", &self.channel.0)
+    }
+
+    /// Host the game is downloaded from, credentials and certificate pins only apply
+    /// to it.
+    pub(crate) fn download_host(&self) -> Option<String> {
+        url::Url::parse(&self.version_url())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_owned))
     }
 
     pub(crate) fn channel_url(&self) -> String {
@@ -275,6 +1408,23 @@ impl Profile {
         )
     }
 
+    /// Credentials for the download server. `AIRSHIPPER_DOWNLOAD_TOKEN` (a bearer token)
+    /// and `AIRSHIPPER_DOWNLOAD_AUTH` (`user:password`) take precedence over the ones
+    /// in the configuration.
+    pub(crate) fn download_auth(&self) -> Option<DownloadAuth> {
+        if let Ok(token) = std::env::var("AIRSHIPPER_DOWNLOAD_TOKEN") {
+            return Some(DownloadAuth::Bearer(token));
+        }
+        if let Ok(auth) = std::env::var("AIRSHIPPER_DOWNLOAD_AUTH") {
+            let (user, password) = match auth.split_once(':') {
+                Some((user, password)) => (user.to_string(), Some(password.to_string())),
+                None => (auth, None),
+            };
+            return Some(DownloadAuth::Basic { user, password });
+        }
+        self.download_auth.clone()
+    }
+
     pub(crate) fn api_version_url(&self) -> String {
         format!("{}/api/version", self.server.url(),)
     }
@@ -283,8 +1433,29 @@ impl Profile {
         format!("{}/announcement", self.server.url(),)
     }
 
+    fn safe_mode_config_dir(&self) -> PathBuf {
+        self.directory().join(consts::SAFE_MODE_CONFIG_DIR)
+    }
+
+    /// Deletes the settings the game saved in safe mode, so the next launch in safe
+    /// mode starts from the defaults again, call it before [`Profile::start`].
+    pub fn reset_safe_mode_settings(&self) -> std::io::Result<()> {
+        match std::fs::remove_dir_all(self.safe_mode_config_dir()) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
     // TODO: add possibility to start the server too
-    pub fn start(profile: &Profile, game_server_address: Option<&str>) -> Command {
+    /// Builds the command to start the game. In safe mode the graphics related settings
+    /// of the profile are ignored: the game uses the [`SAFE_MODE_ENV`] and starts from
+    /// its default settings, windowed at a low resolution. These are stored apart from
+    /// the regular ones so those are left untouched.
+    pub fn start(
+        profile: &Profile,
+        game_server_address: Option<&str>,
+        safe_mode: bool,
+    ) -> Result<Command> {
         let mut envs = HashMap::new();
         let userdata_dir = profile.directory().join("userdata").into_os_string();
         let screenshot_dir = profile.directory().join("screenshots").into_os_string();
@@ -311,7 +1482,7 @@ impl Profile {
         envs.insert("VELOREN_USERDATA", userdata_dir);
         envs.insert("VELOREN_ASSETS", assets_dir);
 
-        if profile.wgpu_backend != WgpuBackend::Auto {
+        if profile.wgpu_backend != WgpuBackend::Auto && !safe_mode {
             let wgpu_backend = match profile.wgpu_backend {
                 WgpuBackend::OpenGl => "gl",
                 WgpuBackend::DX11 => "dx11",
@@ -333,12 +1504,32 @@ impl Profile {
         for (var, value) in env_vars {
             envs.insert(var, OsString::from(value));
         }
+        for warning in profile.env_var_warnings(&profile.env_vars) {
+            tracing::warn!("Environment variable warning: {}", warning);
+        }
 
-        tracing::debug!("Launching {}", profile.voxygen_path().display());
+        if safe_mode {
+            // the profile's variables must not bring the risky settings back
+            envs.extend(
+                SAFE_MODE_ENV
+                    .iter()
+                    .map(|(var, value)| (*var, value.into())),
+            );
+            envs.insert(
+                "VOXYGEN_CONFIG",
+                profile.safe_mode_config_dir().into_os_string(),
+            );
+            tracing::info!("Starting in safe mode with default settings");
+        }
+
+        let mut command = profile.launch_command()?.into_iter();
+        let mut cmd = Command::new(command.next().unwrap_or_default());
+        cmd.args(command);
+
+        tracing::debug!("Launching {:?}", cmd.as_std());
         tracing::debug!("CWD: {:?}", profile.directory());
         tracing::debug!("ENV: {:?}", envs);
 
-        let mut cmd = Command::new(profile.voxygen_path());
         cmd.current_dir(profile.directory());
         cmd.envs(envs);
 
@@ -347,7 +1538,72 @@ impl Profile {
             cmd.args(["--server", game_server_address]);
         }
 
-        cmd
+        Ok(cmd)
+    }
+
+    /// The program and arguments which start the game. The launch wrapper, e.g.
+    /// `bwrap` or `sudo -u veloren` to drop privileges, is split on whitespace and
+    /// `{game}` and `{install_dir}` in its arguments are replaced with the paths of the
+    /// game and its install folder. The game is appended unless `{game}` is used.
+    pub fn launch_command(&self) -> Result<Vec<OsString>> {
+        let game = self.voxygen_path().into_os_string();
+        if self.launch_wrapper.trim().is_empty() {
+            return Ok(vec![game]);
+        }
+        validate_launch_wrapper(&self.launch_wrapper)?;
+
+        let install_dir = self.directory().into_os_string();
+        let mut command: Vec<OsString> = self
+            .launch_wrapper
+            .split_whitespace()
+            .map(|arg| match arg {
+                WRAPPER_GAME => game.clone(),
+                WRAPPER_INSTALL_DIR => install_dir.clone(),
+                // e.g. `--bind={install_dir}`, paths which aren't valid UTF-8 only work
+                // as a whole argument
+                _ => arg
+                    .replace(WRAPPER_GAME, &game.to_string_lossy())
+                    .replace(WRAPPER_INSTALL_DIR, &install_dir.to_string_lossy())
+                    .into(),
+            })
+            .collect();
+        if !self.launch_wrapper.contains(WRAPPER_GAME) {
+            command.push(game);
+        }
+        tracing::info!(?command, "Resolved the launch wrapper");
+        Ok(command)
+    }
+
+    /// The configured executables which exist in the install. Paths which point
+    /// outside of the install or weren't downloaded are skipped with a warning, so a
+    /// binary missing from a release doesn't fail the update.
+    pub fn installed_executables(&self) -> Vec<&str> {
+        self.installed_executables_in(&self.directory())
+    }
+
+    fn installed_executables_in(&self, directory: &Path) -> Vec<&str> {
+        self.executables
+            .iter()
+            .map(String::as_str)
+            .filter(|file| {
+                let inside = Path::new(file)
+                    .components()
+                    .all(|c| matches!(c, std::path::Component::Normal(_)));
+                if !inside || file.is_empty() {
+                    tracing::warn!(
+                        "Ignoring the executable '{file}', it is not in the install"
+                    );
+                    false
+                } else if !directory.join(file).is_file() {
+                    tracing::warn!(
+                        "The executable '{file}' was not found in the install"
+                    );
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect()
     }
 
     /// Returns whether the profile is ready to be started
@@ -355,6 +1611,16 @@ impl Profile {
         self.voxygen_path().exists() && self.version.is_some()
     }
 
+    /// Backends the game supports, or the defaults of this platform as long as the game
+    /// wasn't asked, e.g. before it is installed
+    pub fn known_wgpu_backends(&self) -> &[WgpuBackend] {
+        if self.supported_wgpu_backends.is_empty() {
+            WGPU_BACKENDS
+        } else {
+            &self.supported_wgpu_backends
+        }
+    }
+
     pub fn reload_wgpu_backends(&mut self) {
         if self.installed() {
             self.supported_wgpu_backends = iced::futures::executor::block_on(
@@ -379,6 +1645,133 @@ impl Profile {
     }
 }
 
+/// Environment variables forcing the graphics backend least likely to crash when
+/// starting in safe mode
+#[cfg(target_os = "macos")]
+pub const SAFE_MODE_ENV: &[(&str, &str)] = &[("WGPU_BACKEND", "metal")];
+#[cfg(not(target_os = "macos"))]
+pub const SAFE_MODE_ENV: &[(&str, &str)] = &[("WGPU_BACKEND", "gl")];
+
+/// Checks that the program of a launch wrapper exists and that it only uses known
+/// placeholders, see [`Profile::launch_command`].
+pub fn validate_launch_wrapper(wrapper: &str) -> Result<()> {
+    let invalid = |reason: String| Err(ClientError::LaunchWrapper(reason));
+
+    let mut rest = wrapper;
+    while let Some(start) = rest.find('{') {
+        let placeholder = match rest[start..].find('}') {
+            Some(end) => &rest[start..=start + end],
+            None => &rest[start..],
+        };
+        if placeholder != WRAPPER_GAME && placeholder != WRAPPER_INSTALL_DIR {
+            return invalid(format!(
+                "unknown placeholder '{placeholder}', only {WRAPPER_GAME} and \
+                 {WRAPPER_INSTALL_DIR} are supported"
+            ));
+        }
+        rest = &rest[start + placeholder.len()..];
+    }
+
+    match wrapper.split_whitespace().next() {
+        Some(program) if program.contains('{') => {
+            invalid("the wrapper has to start with a program".to_string())
+        },
+        Some(program) if find_executable(program).is_none() => {
+            invalid(format!("'{program}' was not found"))
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Looks up a program by path or in `PATH`
+fn find_executable(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| {
+            candidate.is_file()
+                || (cfg!(windows) && candidate.with_extension("exe").is_file())
+        })
+}
+
+/// Warns when an environment variable is set, optionally only for a single value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvVarCheck {
+    pub name: String,
+    /// any value is warned about if unset
+    #[serde(default)]
+    pub value: Option<String>,
+    pub warning: String,
+}
+
+impl Profile {
+    /// Warnings for variables of `env_vars` which are valid, but likely keep the game
+    /// from starting or working as expected. They are only shown, the variables are
+    /// set anyway.
+    pub fn env_var_warnings(&self, env_vars: &str) -> Vec<String> {
+        let (vars, _) = parse_env_vars(env_vars);
+        vars.into_iter()
+            .flat_map(|(name, value)| {
+                let custom = self
+                    .env_var_checks
+                    .iter()
+                    .filter(move |check| {
+                        check.name == name
+                            && check.value.as_deref().is_none_or(|v| v == value)
+                    })
+                    .map(move |check| format!("{name}: {}", check.warning));
+                self.builtin_env_var_warning(name, value)
+                    .into_iter()
+                    .chain(custom)
+            })
+            .collect()
+    }
+
+    fn builtin_env_var_warning(&self, name: &str, value: &str) -> Option<String> {
+        match name {
+            "WGPU_BACKEND" => value.split(',').map(str::trim).find_map(|backend| {
+                match WGPU_BACKEND_NAMES
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(backend))
+                {
+                    None => {
+                        let names: Vec<_> =
+                            WGPU_BACKEND_NAMES.iter().map(|(name, _)| *name).collect();
+                        Some(format!(
+                            "{name}: '{backend}' is no graphics backend, use one of {}",
+                            names.join(", ")
+                        ))
+                    },
+                    Some((_, backend))
+                        if !self.supported_wgpu_backends.is_empty()
+                            && !self.supported_wgpu_backends.contains(backend) =>
+                    {
+                        Some(format!("{name}: {backend} is not supported by the game"))
+                    },
+                    Some(_) => None,
+                }
+            }),
+            "RUST_LOG" => tracing_subscriber::EnvFilter::try_new(value)
+                .err()
+                .map(|e| format!("{name}: '{value}' is no valid filter, {e}")),
+            "WGPU_POWER_PREF" if !["low", "high", "none"].contains(&value) => Some(
+                format!("{name}: '{value}' is invalid, use one of low, high, none"),
+            ),
+            "VELOREN_USERDATA" | "VELOREN_ASSETS" | "VOXYGEN_SCREENSHOT" => {
+                Some(format!(
+                    "{name} is set by the launcher, overriding it can make the game use \
+                     the wrong folders"
+                ))
+            },
+            _ => None,
+        }
+    }
+}
+
 pub fn parse_env_vars(env_vars: &str) -> (Vec<(&str, &str)>, Vec<String>) {
     let env_vars = env_vars.trim();
     let mut errors = Vec::new();
@@ -417,6 +1810,266 @@ pub fn parse_env_vars(env_vars: &str) -> (Vec<(&str, &str)>, Vec<String>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_installed_executables() {
+        let profile = Profile {
+            executables: vec![
+                consts::VOXYGEN_FILE.to_owned(),
+                "tools/missing".to_owned(),
+                "../outside".to_owned(),
+                "/bin/sh".to_owned(),
+            ],
+            ..Default::default()
+        };
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-executables-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(consts::VOXYGEN_FILE), b"").unwrap();
+
+        assert_eq!(profile.installed_executables_in(&dir), vec![
+            consts::VOXYGEN_FILE
+        ]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_save_survives_crash_while_writing() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-savedstate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(consts::SAVED_STATE_FILE);
+
+        let installed = Profile {
+            version: Some("old".to_owned()),
+            install_fingerprint: Some(7),
+            ..Default::default()
+        };
+        installed.save_to(&path).await.unwrap();
+
+        // the update saves its progress while the GUI saves a changed setting
+        let updating = Profile {
+            version: Some("new".to_owned()),
+            ..installed.clone()
+        };
+        let gui = Profile {
+            low_memory: !installed.low_memory,
+            ..installed.clone()
+        };
+        let saves = (0..20).map(|i| {
+            let (updating, gui, path) = (&updating, &gui, &path);
+            async move {
+                let maps = vec![format!("maps/{i}.bin")];
+                updating.save_update_progress_to(path, Some(maps)).await?;
+                gui.save_to(path).await
+            }
+        });
+        for result in futures_util::future::join_all(saves).await {
+            result.unwrap();
+        }
+
+        // then the launcher is killed while writing
+        let tmp = fs::temp_path(&path);
+        std::fs::write(&tmp, "(name: \"def").unwrap();
+        let crashed = std::time::SystemTime::now() - Duration::from_secs(60 * 60);
+        std::fs::File::options()
+            .write(true)
+            .open(&tmp)
+            .unwrap()
+            .set_modified(crashed)
+            .unwrap();
+
+        // the next start finds a whole saved state without the new version, and no
+        // temporary files are left behind
+        fs::remove_orphaned_temp_files(&dir);
+        assert!(!tmp.exists());
+        let loaded = Profile::load_from(&path).unwrap();
+        assert_eq!(loaded.version.as_deref(), Some("old"));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_update_progress_keeps_settings() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-update-progress-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(consts::SAVED_STATE_FILE);
+
+        let updating = Profile {
+            version: Some("new".to_owned()),
+            install_fingerprint: Some(7),
+            ..Default::default()
+        };
+        // the version is unknown without a saved state
+        updating
+            .save_update_progress_to(&path, Some(vec!["maps/a.bin".to_owned()]))
+            .await
+            .unwrap();
+        let loaded = Profile::load_from(&path).unwrap();
+        assert_eq!(loaded.version, None);
+        assert_eq!(loaded.shipped_maps, vec!["maps/a.bin"]);
+
+        // changed in the GUI while updating
+        let saved = Profile {
+            version: Some("old".to_owned()),
+            install_fingerprint: Some(7),
+            low_memory: !updating.low_memory,
+            ..Default::default()
+        };
+        saved.save_to(&path).await.unwrap();
+        updating
+            .save_update_progress_to(&path, Some(vec!["maps/b.bin".to_owned()]))
+            .await
+            .unwrap();
+        let loaded = Profile::load_from(&path).unwrap();
+        assert_eq!(loaded.version.as_deref(), Some("old"));
+        assert_eq!(loaded.install_fingerprint, None);
+        assert_eq!(loaded.low_memory, saved.low_memory);
+        assert_eq!(loaded.shipped_maps, vec!["maps/b.bin"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_launch_command() {
+        let mut profile = Profile::default();
+        let game = profile.voxygen_path().into_os_string();
+        assert_eq!(profile.launch_command().unwrap(), vec![game.clone()]);
+
+        let program = std::env::current_exe().unwrap();
+        profile.launch_wrapper =
+            format!("{} --bind {{install_dir}} /game", program.display());
+        let command = profile.launch_command().unwrap();
+        assert_eq!(command[2], profile.directory().into_os_string());
+        assert_eq!(command.last(), Some(&game));
+
+        profile.launch_wrapper = format!("{} {{game}} --flag", program.display());
+        let command = profile.launch_command().unwrap();
+        assert_eq!(command[1], game);
+        assert_eq!(command.last().unwrap(), "--flag");
+
+        assert!(validate_launch_wrapper("airshipper-missing-wrapper").is_err());
+        assert!(validate_launch_wrapper("{game} --flag").is_err());
+        let unknown = format!("{} {{user}}", program.display());
+        assert!(validate_launch_wrapper(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_safe_mode() {
+        let profile = Profile {
+            wgpu_backend: WgpuBackend::Vulkan,
+            env_vars: "WGPU_BACKEND=vulkan, RUST_BACKTRACE=1".to_owned(),
+            ..Profile::default()
+        };
+        let env = |safe_mode| {
+            let cmd = Profile::start(&profile, None, safe_mode).unwrap();
+            cmd.as_std()
+                .get_envs()
+                .map(|(var, value)| (var.to_owned(), value.map(|v| v.to_owned())))
+                .collect::<HashMap<_, _>>()
+        };
+
+        let regular = env(false);
+        assert_eq!(regular[OsStr::new("WGPU_BACKEND")], Some("vulkan".into()));
+        assert!(!regular.contains_key(OsStr::new("VOXYGEN_CONFIG")));
+
+        let safe = env(true);
+        for (var, value) in SAFE_MODE_ENV {
+            assert_eq!(safe[OsStr::new(var)], Some(value.into()));
+        }
+        assert_eq!(
+            safe[OsStr::new("VOXYGEN_CONFIG")],
+            Some(profile.safe_mode_config_dir().into_os_string())
+        );
+        assert_eq!(safe[OsStr::new("RUST_BACKTRACE")], Some("1".into()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export_import() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(consts::EXPORT_FILE);
+
+        let mut profile = Profile {
+            version: Some("installed".to_owned()),
+            last_updated: Some(Utc::now()),
+            launch_wrapper: "gamemoderun".to_owned(),
+            download_auth: Some(DownloadAuth::Bearer("secret".to_owned())),
+            compress_stored_files: true,
+            ..Default::default()
+        };
+        profile.export(&path, false, false).await.unwrap();
+        // readable text, only saving the active state changes the compression
+        let exported = std::fs::read_to_string(&path).unwrap();
+        assert!(!exported.contains("secret"));
+        assert!(exported.contains("compress_stored_files: true"));
+        assert!(!fs::compress_stored_files());
+
+        let other = Profile {
+            version: Some("other".to_owned()),
+            download_auth: Some(DownloadAuth::Bearer("other".to_owned())),
+            ..Default::default()
+        };
+        let imported = other.import(&path).unwrap();
+        assert_eq!(imported.launch_wrapper, "gamemoderun");
+        assert_eq!(imported.version.as_deref(), Some("other"));
+        assert_eq!(imported.last_updated, None);
+        assert_eq!(imported.download_auth, other.download_auth);
+
+        profile.export(&path, true, false).await.unwrap();
+        let imported = other.import(&path).unwrap();
+        assert_eq!(imported.version.as_deref(), Some("installed"));
+        assert_eq!(imported.last_updated, profile.last_updated);
+        assert_eq!(imported.download_auth, other.download_auth);
+
+        profile.export(&path, false, true).await.unwrap();
+        let imported = other.import(&path).unwrap();
+        assert_eq!(imported.download_auth, profile.download_auth);
+
+        profile.env_vars = "FOO".to_owned();
+        profile.export(&path, false, false).await.unwrap();
+        assert!(other.import(&path).is_err());
+
+        // a malformed file stays where it is
+        std::fs::write(&path, "(launch_wrapper: ").unwrap();
+        assert!(other.import(&path).is_err());
+        assert!(path.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_switch_slot() {
+        let mut profile = Profile {
+            version: Some("stable".to_owned()),
+            install_fingerprint: Some(1),
+            ..Default::default()
+        };
+        assert!(profile.switch_slot("../escape").is_err());
+
+        profile.switch_slot("staging").unwrap();
+        assert_eq!(profile.slot.as_deref(), Some("staging"));
+        assert_eq!(profile.version, None);
+        profile.server = Server::Staging;
+        profile.version = Some("test".to_owned());
+        assert_eq!(profile.slot_names(), vec!["default", "staging"]);
+        assert!(profile.remove_slot("staging").is_err());
+
+        profile.switch_slot(DEFAULT_SLOT_NAME).unwrap();
+        assert_eq!(profile.slot, None);
+        assert_eq!(profile.server, Server::Production);
+        assert_eq!(profile.version.as_deref(), Some("stable"));
+        assert_eq!(profile.install_fingerprint, Some(1));
+
+        profile.switch_slot("staging").unwrap();
+        assert_eq!(profile.server, Server::Staging);
+        assert_eq!(profile.version.as_deref(), Some("test"));
+        assert!(profile.remove_slot(DEFAULT_SLOT_NAME).is_err());
+    }
 
     #[test]
     fn test_empty_config() {
@@ -454,4 +2107,96 @@ mod tests {
             "Variable 'EMM' has no corresponding value".to_string()
         ]);
     }
+
+    #[test]
+    fn test_space_margin() {
+        assert_eq!("10%".parse(), Ok(SpaceMargin::Percent(10)));
+        assert_eq!("2 GB".parse(), Ok(SpaceMargin::Bytes(2_000_000_000)));
+        assert_eq!("500MiB".parse(), Ok(SpaceMargin::Bytes(500 * 1024 * 1024)));
+        assert!("ten percent".parse::<SpaceMargin>().is_err());
+        assert!("1000%".parse::<SpaceMargin>().is_err());
+
+        assert_eq!(SpaceMargin::Percent(10).required_space(2_000), 2_200);
+        assert_eq!(SpaceMargin::Bytes(500).required_space(2_000), 2_500);
+        assert_eq!(SpaceMargin::Bytes(1).required_space(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_env_var_warnings() {
+        let mut profile = Profile::default();
+        assert!(
+            profile
+                .env_var_warnings("WGPU_BACKEND=vulkan, RUST_LOG=veloren=debug,FOO=bar")
+                .is_empty()
+        );
+        assert_eq!(profile.env_var_warnings("WGPU_BACKEND=directx").len(), 1);
+        assert_eq!(profile.env_var_warnings("RUST_LOG=veloren=loud").len(), 1);
+        assert_eq!(profile.env_var_warnings("VELOREN_ASSETS=/tmp").len(), 1);
+
+        profile.supported_wgpu_backends = vec![WgpuBackend::Auto, WgpuBackend::Vulkan];
+        assert_eq!(profile.env_var_warnings("WGPU_BACKEND=dx12").len(), 1);
+
+        profile.env_var_checks.push(EnvVarCheck {
+            name: "FOO".to_string(),
+            value: Some("bar".to_string()),
+            warning: "breaks everything".to_string(),
+        });
+        assert_eq!(profile.env_var_warnings("FOO=bar"), vec![
+            "FOO: breaks everything".to_string()
+        ]);
+        assert!(profile.env_var_warnings("FOO=baz").is_empty());
+    }
+
+    #[test]
+    fn test_refresh_intervals() {
+        let intervals: RefreshIntervals = "30 0 5 120".parse().unwrap();
+        assert!(intervals.enabled);
+        assert_eq!(intervals.community, 0);
+        assert_eq!(intervals.to_string(), "30 0 5 120");
+        assert_eq!("OFF".parse(), Ok(RefreshIntervals::default()));
+        assert_eq!(RefreshIntervals::default().to_string(), "off");
+        assert!("30 0 5".parse::<RefreshIntervals>().is_err());
+        assert!("30 0 5 an hour".parse::<RefreshIntervals>().is_err());
+        assert!(
+            format!("30 0 5 {}", u64::MAX / 60 + 1)
+                .parse::<RefreshIntervals>()
+                .is_err()
+        );
+        assert_eq!(
+            RefreshIntervals::period(u64::MAX),
+            RefreshIntervals::period(RefreshIntervals::MAX_MINUTES)
+        );
+    }
+
+    #[test]
+    fn test_url_templates() {
+        let mut profile = Profile::default();
+        let built_in = profile.version_url();
+        profile.url_templates = UrlTemplates {
+            version: Some("https://example.com/{channel}/{os}-{arch}.txt".to_string()),
+            download: Some("https://cdn.example.com/{channel}/{version}.zip".to_string()),
+        };
+        assert!(profile.url_templates.validate().is_ok());
+        assert_ne!(profile.version_url(), built_in);
+        assert_eq!(
+            profile.download_url("0.17.0"),
+            format!("https://cdn.example.com/{}/0.17.0.zip", profile.channel.0)
+        );
+        assert_eq!(profile.download_host().as_deref(), Some("example.com"));
+
+        let invalid = |version: &str| {
+            UrlTemplates {
+                version: Some(version.to_string()),
+                download: None,
+            }
+            .validate()
+            .is_err()
+        };
+        assert!(invalid("https://example.com/{os}/{arch}"));
+        assert!(invalid("https://example.com/{chanel}"));
+        assert!(invalid("https://example.com/{channel"));
+        assert!(invalid("https://example.com/{version}/{channel}"));
+        assert!(invalid("ftp://example.com/{channel}"));
+        assert!(invalid("{channel}"));
+    }
 }
@@ -0,0 +1,441 @@
+//! Keeps copies of previous game versions, so an update which broke the game can be
+//! rolled back without downloading anything, see [`Profile::keep_versions`].
+//!
+//! Every kept version is a full copy of the game files, user data like `userdata/`
+//! and the maps stay in the install and are carried over on a rollback.
+use crate::{
+    ClientError, Result,
+    profiles::{PatchedInfo, Profile},
+    update::{KEEP_PATHS, MAPS_DIR, install_files},
+};
+use chrono::{DateTime, Utc};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// state of a kept version, stored next to its files
+const STATE_FILE: &str = "kept.ron";
+/// the game files of a kept version
+const GAME_DIR: &str = "game";
+
+/// Install state of a kept version, restored on a rollback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct KeptVersion {
+    pub version: String,
+    pub kept_at: DateTime<Utc>,
+    #[serde(default)]
+    patched_crc32s: Vec<PatchedInfo>,
+    #[serde(default)]
+    compacted_files: Vec<String>,
+}
+
+/// Where the install of a profile and its kept versions live
+struct Paths {
+    install: PathBuf,
+    kept: PathBuf,
+}
+
+impl Paths {
+    fn of(profile: &Profile) -> Self {
+        Self {
+            install: profile.directory(),
+            kept: crate::fs::kept_versions_path(&profile.name, profile.slot_name()),
+        }
+    }
+
+    /// Directory of a kept version. The version comes from the download server or the
+    /// command line, so it must not lead outside of the kept versions.
+    fn version(&self, version: &str) -> Result<PathBuf> {
+        validate_version(version)?;
+        Ok(self.kept.join(version))
+    }
+}
+
+fn validate_version(version: &str) -> Result<()> {
+    if version.is_empty() || version.contains(['/', '\\']) || version.contains("..") {
+        return Err(ClientError::Custom(format!(
+            "'{version}' is no valid version to keep"
+        )));
+    }
+    Ok(())
+}
+
+/// Kept versions of the active install slot of `profile`, newest first.
+pub(crate) fn kept_versions(profile: &Profile) -> std::io::Result<Vec<KeptVersion>> {
+    kept_versions_in(&Paths::of(profile))
+}
+
+fn kept_versions_in(paths: &Paths) -> std::io::Result<Vec<KeptVersion>> {
+    let dir = match std::fs::read_dir(&paths.kept) {
+        Ok(dir) => dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut versions = Vec::new();
+    for entry in dir.flatten() {
+        let path = entry.path().join(STATE_FILE);
+        // unfinished copies have no state file yet
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        match crate::fs::parse_ron::<KeptVersion>(&path, &content) {
+            Ok(kept) if validate_version(&kept.version).is_ok() => versions.push(kept),
+            Ok(kept) => tracing::warn!(?kept, ?path, "Ignoring an invalid kept version"),
+            Err(e) => tracing::warn!(?e, ?path, "Ignoring a broken kept version"),
+        }
+    }
+    versions.sort_by(|a, b| b.kept_at.cmp(&a.kept_at));
+    Ok(versions)
+}
+
+/// Whether [`keep_installed`] copies the install of `profile`. An install whose last
+/// update was interrupted is no good version to roll back to.
+fn keeps(profile: &Profile) -> bool {
+    profile.version.is_some()
+        && profile.keep_versions > 0
+        && profile.install_fingerprint.is_some()
+}
+
+/// Bytes [`keep_installed`] copies, `0` if it doesn't keep the install.
+///
+/// Note: it's synchronous!
+pub(crate) fn kept_size(profile: &Profile) -> std::io::Result<u64> {
+    if !keeps(profile) {
+        return Ok(0);
+    }
+    Ok(install_files(&profile.directory(), None)?
+        .iter()
+        .filter(|(_, meta)| meta.is_file())
+        .map(|(_, meta)| meta.len())
+        .sum())
+}
+
+/// Copies the installed game before it is updated and removes the oldest kept
+/// versions beyond the limit. Nothing is copied if no version is kept or the last
+/// update was interrupted, but versions kept before are still removed.
+///
+/// Note: it's synchronous!
+pub(crate) fn keep_installed(profile: &Profile) -> Result<()> {
+    keep_installed_in(&Paths::of(profile), profile)
+}
+
+fn keep_installed_in(paths: &Paths, profile: &Profile) -> Result<()> {
+    if let Some(version) = &profile.version
+        && keeps(profile)
+    {
+        tracing::info!("Keeping a copy of version {version} for a rollback");
+        let target = paths.version(version)?;
+        let partial = paths.kept.join(format!("{version}.partial"));
+        remove_if_exists(&partial)?;
+        copy_game_files(&paths.install, &partial.join(GAME_DIR))?;
+        write_state(&partial, &KeptVersion {
+            version: version.clone(),
+            kept_at: Utc::now(),
+            patched_crc32s: profile.patched_crc32s.clone(),
+            compacted_files: profile.compacted_files.clone(),
+        })?;
+        // a repaired install replaces the copy of the same version
+        remove_if_exists(&target)?;
+        std::fs::rename(&partial, &target)?;
+    }
+    prune(paths, profile.keep_versions)
+}
+
+/// Swaps the install with the kept `version`, or the newest kept one. The installed
+/// version is kept in its place, user data stays in the install. Returns the version
+/// which was restored.
+///
+/// Note: it's synchronous!
+pub(crate) fn rollback(profile: &mut Profile, version: Option<&str>) -> Result<String> {
+    rollback_in(&Paths::of(profile), profile, version)
+}
+
+fn rollback_in(
+    paths: &Paths,
+    profile: &mut Profile,
+    version: Option<&str>,
+) -> Result<String> {
+    if let Some(version) = version {
+        validate_version(version)?;
+    }
+    let kept = kept_versions_in(paths)?;
+    let restored = match version {
+        Some(version) => kept.into_iter().find(|kept| kept.version == version),
+        None => kept
+            .into_iter()
+            .find(|kept| Some(&kept.version) != profile.version.as_ref()),
+    }
+    .ok_or_else(|| {
+        ClientError::Custom(match version {
+            Some(version) => format!("Version {version} is not kept"),
+            None => "No previous version is kept".to_string(),
+        })
+    })?;
+    if Some(&restored.version) == profile.version.as_ref() {
+        return Err(ClientError::Custom(format!(
+            "Version {} is already installed",
+            restored.version
+        )));
+    }
+
+    let install = &paths.install;
+    let restored_path = paths.version(&restored.version)?;
+    let restored_game = restored_path.join(GAME_DIR);
+    // checked before anything is moved
+    let installed_path = match &profile.version {
+        Some(version) => Some(paths.version(version)?),
+        None => None,
+    };
+
+    let installed = profile.version.as_ref().map(|version| KeptVersion {
+        version: version.clone(),
+        kept_at: Utc::now(),
+        patched_crc32s: profile.patched_crc32s.clone(),
+        compacted_files: profile.compacted_files.clone(),
+    });
+    // every step is undone if a later one fails, e.g. on Windows with open files, so
+    // neither the install nor the user data end up in the kept versions
+    let swapped = paths.kept.join(".swap");
+    remove_if_exists(&swapped)?;
+    std::fs::rename(install, &swapped)?;
+    if let Err(e) = std::fs::rename(&restored_game, install) {
+        std::fs::rename(&swapped, install)?;
+        return Err(e.into());
+    }
+    if let Err(e) = move_user_data(&swapped, install) {
+        tracing::warn!(
+            ?e,
+            "Carrying the user data over failed, undoing the rollback"
+        );
+        move_user_data(install, &swapped)?;
+        std::fs::rename(install, &restored_game)?;
+        std::fs::rename(&swapped, install)?;
+        return Err(e.into());
+    }
+    std::fs::remove_dir_all(&restored_path)?;
+    match (installed, installed_path) {
+        (Some(installed), Some(target)) => {
+            std::fs::create_dir_all(&target)?;
+            std::fs::rename(&swapped, target.join(GAME_DIR))?;
+            write_state(&target, &installed)?;
+        },
+        _ => std::fs::remove_dir_all(&swapped)?,
+    }
+
+    profile.version = Some(restored.version.clone());
+    profile.patched_crc32s = restored.patched_crc32s;
+    profile.compacted_files = restored.compacted_files;
+    // the next start verifies the files instead of trusting the fingerprint
+    profile.install_fingerprint = None;
+    profile.last_updated = Some(Utc::now());
+    prune(paths, profile.keep_versions)?;
+    Ok(restored.version)
+}
+
+/// Removes the oldest kept versions beyond [`Profile::keep_versions`].
+fn prune(paths: &Paths, keep_versions: usize) -> Result<()> {
+    for kept in kept_versions_in(paths)?.iter().skip(keep_versions) {
+        tracing::info!("Removing the kept version {}", kept.version);
+        std::fs::remove_dir_all(paths.version(&kept.version)?)?;
+    }
+    Ok(())
+}
+
+/// Moves the user data and maps from the install in `from` to the one in `to`.
+fn move_user_data(from: &Path, to: &Path) -> std::io::Result<()> {
+    for path in KEEP_PATHS.iter().chain([&MAPS_DIR]) {
+        let path = path.trim_end_matches('/');
+        let source = from.join(path);
+        if std::fs::symlink_metadata(&source).is_ok() {
+            let target = to.join(path);
+            remove_if_exists(&target)?;
+            std::fs::rename(source, target)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_state(dir: &Path, kept: &KeptVersion) -> Result<()> {
+    let content = ron::ser::to_string_pretty(kept, PrettyConfig::default())?;
+    std::fs::write(dir.join(STATE_FILE), content)?;
+    Ok(())
+}
+
+fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(path),
+        Ok(_) => std::fs::remove_file(path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Copies the install without the user data. Symlinks are skipped like during updates.
+fn copy_game_files(install: &Path, target: &Path) -> std::io::Result<()> {
+    let mut todo = vec![install.to_path_buf()];
+    while let Some(current) = todo.pop() {
+        std::fs::create_dir_all(target.join(current.strip_prefix(install).unwrap()))?;
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let path = entry.path();
+            let relative = path.strip_prefix(install).unwrap();
+            let unix_path = relative.to_string_lossy().replace('\\', "/");
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                let dir_path = format!("{unix_path}/");
+                if !KEEP_PATHS.contains(&dir_path.as_str()) && dir_path != MAPS_DIR {
+                    todo.push(path);
+                }
+            } else if file_type.is_file() && !KEEP_PATHS.contains(&unix_path.as_str()) {
+                std::fs::copy(&path, target.join(relative))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_swaps_the_install() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-test-rollback-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = Paths {
+            install: dir.join("install"),
+            kept: dir.join("kept"),
+        };
+        let mut profile = Profile::default();
+        profile.version = Some("1.0".to_owned());
+        profile.keep_versions = 1;
+        profile.install_fingerprint = Some(1);
+        let install = &paths.install;
+        std::fs::create_dir_all(install.join("userdata")).unwrap();
+        std::fs::write(install.join("game.bin"), "old").unwrap();
+        keep_installed_in(&paths, &profile).unwrap();
+
+        // the update
+        std::fs::write(install.join("game.bin"), "new").unwrap();
+        std::fs::write(install.join("userdata/settings.ron"), "mine").unwrap();
+        profile.version = Some("2.0".to_owned());
+
+        assert_eq!(rollback_in(&paths, &mut profile, None).unwrap(), "1.0");
+        assert_eq!(profile.version.as_deref(), Some("1.0"));
+        let read = |path| std::fs::read_to_string(install.join(path)).unwrap();
+        assert_eq!(read("game.bin"), "old");
+        assert_eq!(read("userdata/settings.ron"), "mine");
+        // the update is kept in its place
+        let kept = kept_versions_in(&paths).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].version, "2.0");
+
+        assert!(rollback_in(&paths, &mut profile, Some("1.0")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_versions_stay_inside_the_kept_versions() {
+        let dir = std::env::temp_dir().join(format!(
+            "airshipper-test-rollback-escape-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = Paths {
+            install: dir.join("install"),
+            kept: dir.join("kept"),
+        };
+        std::fs::create_dir_all(&paths.install).unwrap();
+        std::fs::write(paths.install.join("game.bin"), "game").unwrap();
+
+        let mut profile = Profile::default();
+        profile.keep_versions = 1;
+        profile.install_fingerprint = Some(1);
+        for version in ["", "..", "../install", "1.0/../..", "a\\b"] {
+            assert!(paths.version(version).is_err(), "{version}");
+            profile.version = Some(version.to_owned());
+            assert!(keep_installed_in(&paths, &profile).is_err(), "{version}");
+            assert!(rollback_in(&paths, &mut profile, Some(version)).is_err());
+        }
+        assert!(paths.version("0.17.0-nightly").is_ok());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_interrupted_update_is_not_kept() {
+        let dir = std::env::temp_dir().join(format!(
+            "airshipper-test-rollback-interrupted-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = Paths {
+            install: dir.join("install"),
+            kept: dir.join("kept"),
+        };
+        std::fs::create_dir_all(paths.install.join("userdata")).unwrap();
+        std::fs::write(paths.install.join("game.bin"), "half").unwrap();
+        std::fs::write(paths.install.join("userdata/settings.ron"), "()").unwrap();
+
+        let mut profile = Profile::default();
+        profile.version = Some("1.0".to_owned());
+        profile.keep_versions = 1;
+        keep_installed_in(&paths, &profile).unwrap();
+        assert!(kept_versions_in(&paths).unwrap().is_empty());
+
+        // only the game files are copied
+        profile.install_fingerprint = Some(1);
+        assert!(keeps(&profile));
+        keep_installed_in(&paths, &profile).unwrap();
+        assert_eq!(kept_versions_in(&paths).unwrap().len(), 1);
+        assert!(!paths.kept.join("1.0/game/userdata").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_rollback_keeps_the_user_data() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!(
+            "airshipper-test-rollback-undo-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let paths = Paths {
+            install: dir.join("install"),
+            kept: dir.join("kept"),
+        };
+        let install = &paths.install;
+        std::fs::create_dir_all(install.join("userdata")).unwrap();
+        std::fs::write(install.join("game.bin"), "old").unwrap();
+        let mut profile = Profile::default();
+        profile.version = Some("1.0".to_owned());
+        profile.keep_versions = 2;
+        profile.install_fingerprint = Some(1);
+        keep_installed_in(&paths, &profile).unwrap();
+        std::fs::write(install.join("game.bin"), "new").unwrap();
+        std::fs::write(install.join("userdata/settings.ron"), "mine").unwrap();
+        profile.version = Some("2.0".to_owned());
+
+        // the user data can't be moved into the restored version
+        let restored = paths.kept.join("1.0/game");
+        std::fs::set_permissions(&restored, std::fs::Permissions::from_mode(0o500))
+            .unwrap();
+        let result = rollback_in(&paths, &mut profile, None);
+        // root ignores the permissions, the rollback succeeds then
+        if result.is_err() {
+            std::fs::set_permissions(&restored, std::fs::Permissions::from_mode(0o700))
+                .unwrap();
+            assert_eq!(profile.version.as_deref(), Some("2.0"));
+            let read = |path| std::fs::read_to_string(install.join(path)).unwrap();
+            assert_eq!(read("game.bin"), "new");
+            assert_eq!(read("userdata/settings.ron"), "mine");
+            assert!(!restored.join("userdata").exists());
+            assert_eq!(kept_versions_in(&paths).unwrap().len(), 1);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,20 +1,28 @@
 use std::{
+    collections::BTreeSet,
     future::Future,
-    path::PathBuf,
-    time::{Duration, SystemTime},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
-    ClientError, WEB_CLIENT,
+    ClientError,
+    logger::pretty_bytes,
     profiles::{PatchedInfo, Profile},
+    update_log::{LoggedRemoteZip, UpdateLog},
 };
 use futures_util::{Stream, stream};
 use remozipsy::{
     ProgressDetails, Statemachine,
-    reqwest::{ReqwestCachedRemoteZip, ReqwestRemoteZip},
-    tokio::TokioLocalStorage,
+    reqwest::{ReqwestCachedRemoteZip, ReqwestRemoteZip, ReqwestRemoteZipError},
+    tokio::{TokioLocalStorage, TokioLocalStorageError},
 };
-use ron::ser::{PrettyConfig, to_string_pretty};
+use reqwest::Method;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 #[derive(Debug, Clone)]
 pub(crate) enum Progress {
@@ -23,14 +31,20 @@ pub(crate) enum Progress {
     /// implement logic to avoid any download
     ReadyToSync {
         version: String,
+        /// how much the update downloads, large updates are confirmed even when
+        /// updating without asking, see [`Profile::is_large_update`]
+        download_bytes: u64,
     },
     // Status from remozipsy
     Incomplete {
         download: ProgressDetails,
         unzip: ProgressDetails,
         delete: ProgressDetails,
+        /// files removed so far, including those of restarted syncs, see
+        /// [`deleted_files_text`]
+        deleted_files: u64,
     },
-    Successful(Profile),
+    Successful(Box<Profile>),
     Errored(ClientError),
 }
 
@@ -38,33 +52,757 @@ pub(crate) enum Progress {
 #[expect(clippy::large_enum_variant)]
 pub(super) enum State {
     ToBeEvaluated(Profile),
-    Sync(
-        Profile,
-        Statemachine<ReqwestCachedRemoteZip<reqwest::Client>, PatchedLocalStorage>,
-    ),
+    Sync(Profile, SyncStatemachine, SyncBookkeeping),
     /// in case its finished early while evaluating
     Finished,
 }
 
+type SyncStatemachine = Statemachine<
+    LoggedRemoteZip<SkipSymlinks<ReqwestCachedRemoteZip<reqwest::Client>>>,
+    PatchedLocalStorage,
+>;
+
 pub(crate) fn update(p: Profile) -> impl Stream<Item = Progress> {
     tracing::debug!("start updating");
-    stream::unfold(State::ToBeEvaluated(p), |old_state| old_state.progress())
+    stream::unfold(State::ToBeEvaluated(p), |old_state| async move {
+        tokio::time::sleep(CLI_STEP_DELAY).await;
+        old_state.progress().await
+    })
+}
+
+pub const DEFAULT_VERSION_FETCH_ATTEMPTS: u32 = 3;
+/// pause before the second attempt to fetch the version, doubled for every further one
+const VERSION_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Fetches the latest version, retrying a few times so a single dropped request doesn't
+/// make the launcher go offline. A 404 is reported as an unknown channel.
+async fn version(profile: &Profile) -> Result<String, ClientError> {
+    let attempts = profile
+        .version_fetch_attempts
+        .unwrap_or(DEFAULT_VERSION_FETCH_ATTEMPTS)
+        .max(1);
+    let mut delay = VERSION_RETRY_DELAY;
+    let mut attempt = 1;
+    loop {
+        match fetch_version(profile).await {
+            Err(e) if attempt < attempts && is_retryable(&e) => {
+                tracing::warn!(
+                    ?e,
+                    "Fetching the version failed (attempt {attempt}/{attempts}), \
+                     retrying in {delay:?}"
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            },
+            Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+                return Err(ClientError::UnknownChannel(profile.channel.0.clone()));
+            },
+            Err(e) if e.is_connect() => {
+                tracing::info!(?e, "The download server can't be reached, going offline");
+                return Err(e.into());
+            },
+            Err(e) if is_retryable(&e) => {
+                tracing::warn!(?e, "Fetching the version failed {attempt} times");
+                return Err(e.into());
+            },
+            Err(e) => return Err(e.into()),
+            Ok(Some(version)) => return Ok(version),
+            Ok(None) => {
+                tracing::warn!("Got a web page instead of the version");
+                return Err(ClientError::HtmlResponse(profile.version_url()));
+            },
+        }
+    }
+}
+
+/// Timeouts and server errors are often temporary. A failed DNS lookup or connection
+/// means being offline, retrying only delays showing it. Other HTTP errors, like an
+/// unknown channel, won't change either.
+fn is_retryable(e: &reqwest::Error) -> bool {
+    !e.is_connect() && e.status().is_none_or(|status| status.is_server_error())
+}
+
+/// `None` if a web page was returned instead of the version, see
+/// [`crate::net::is_html`].
+async fn fetch_version(profile: &Profile) -> Result<Option<String>, reqwest::Error> {
+    let response =
+        crate::net::download_server_request(Method::GET, &profile.version_url(), profile)
+            .send()
+            .await?;
+    tracing::info!(
+        "Connected to the download server via {:?}",
+        response.version()
+    );
+    let response = response.error_for_status()?;
+    let headers = response.headers().clone();
+    let version = response.text().await?;
+    Ok((!crate::net::is_html(&headers, version.as_bytes())).then_some(version))
+}
+
+/// the largest [`Profile::max_batch_gap`] used in low memory mode
+const LOW_MEMORY_MAX_BATCH_GAP: u64 = 64 * 1024;
+
+/// remozipsy keeps every downloaded batch in memory until it is unzipped. In low
+/// memory mode we avoid merging files into bigger batches and keep fewer of them
+/// in flight at once.
+fn sync_config(profile: &Profile) -> remozipsy::Config {
+    let config = if profile.low_memory {
+        tracing::debug!("using low memory download mode");
+        remozipsy::Config {
+            max_parallel_filesystem: 8,
+            max_parallel_downloads: 2,
+            max_junk_bytes_before_next_batch: 0,
+            ..Default::default()
+        }
+    } else {
+        remozipsy::Config::default()
+    };
+    // remozipsy has no limit for the size of a batch, a large gap in low memory mode
+    // would keep more bytes in memory at once
+    let config = match profile.max_batch_gap {
+        Some(gap) if profile.low_memory && gap > LOW_MEMORY_MAX_BATCH_GAP => {
+            tracing::warn!(
+                gap,
+                "the batch gap is too large for low memory mode, using {}",
+                LOW_MEMORY_MAX_BATCH_GAP
+            );
+            remozipsy::Config {
+                max_junk_bytes_before_next_batch: LOW_MEMORY_MAX_BATCH_GAP,
+                ..config
+            }
+        },
+        Some(gap) => {
+            tracing::debug!(gap, "merging batches up to this many bytes apart");
+            remozipsy::Config {
+                max_junk_bytes_before_next_batch: gap,
+                ..config
+            }
+        },
+        None => config,
+    };
+
+    // one download and one unzip at a time, so files are written in the order of
+    // the zip, also used by the tests
+    #[cfg(any(test, feature = "deterministic"))]
+    let config = remozipsy::Config {
+        max_parallel_filesystem: 1,
+        max_parallel_downloads: 1,
+        ..config
+    };
+
+    config
+}
+
+/// how many bytes at the end of the archive are fetched to find its central directory:
+/// the 22 bytes of the end of central directory record and the longest zip comment
+const MAX_EOCD_SIZE: usize = 65_557;
+
+pub(crate) const MAPS_DIR: &str = "maps/";
+pub(crate) const KEEP_PATHS: &[&str] = &["userdata/", "screenshots/", "veloren.zip"];
+
+/// Cheap fingerprint of the installed files, built from their paths, sizes and
+/// modification times without reading any file content. Files kept across updates are
+/// left out, so playing the game does not change it.
+fn install_fingerprint(
+    dir: &Path,
+    shipped_maps: Option<&[String]>,
+) -> std::io::Result<u32> {
+    let mut files = Vec::new();
+    for (unix_path, meta) in install_files(dir, shipped_maps)? {
+        let modified = meta
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        files.push((unix_path, meta.len(), modified));
+    }
+    files.sort();
+
+    let mut hasher = crc32fast::Hasher::new();
+    for (path, len, modified) in files {
+        hasher.update(path.as_bytes());
+        hasher.update(&len.to_le_bytes());
+        hasher.update(&modified.to_le_bytes());
+    }
+    Ok(hasher.finalize())
+}
+
+/// Unix paths and metadata of the installed files an update manages, unordered. Files
+/// kept across updates are left out, maps unless they are `shipped_maps`. Symlinks
+/// aren't followed, they are returned with their own metadata.
+pub(crate) fn install_files(
+    dir: &Path,
+    shipped_maps: Option<&[String]>,
+) -> std::io::Result<Vec<(String, std::fs::Metadata)>> {
+    let mut files = Vec::new();
+    let mut todo = vec![dir.to_path_buf()];
+    while let Some(current) = todo.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let path = entry.path();
+            let unix_path = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            // does not follow symlinks
+            let meta = entry.metadata()?;
+            if meta.is_dir() {
+                let dir_path = format!("{unix_path}/");
+                if !KEEP_PATHS.contains(&dir_path.as_str())
+                    && (dir_path != MAPS_DIR || shipped_maps.is_some())
+                {
+                    todo.push(path);
+                }
+                continue;
+            }
+            if KEEP_PATHS.contains(&unix_path.as_str())
+                || (unix_path.starts_with(MAPS_DIR)
+                    && !shipped_maps.is_some_and(|maps| maps.contains(&unix_path)))
+            {
+                continue;
+            }
+            files.push((unix_path, meta));
+        }
+    }
+    Ok(files)
+}
+
+/// Returns the unix paths of all symlinks in the install outside of the kept paths.
+///
+/// Symlinks are never followed while walking the install: a link could point back to a
+/// parent and loop forever, or point outside the install and get files there deleted
+/// or overwritten. They are handed to remozipsy as ignored paths and the remote files
+/// under them are dropped by [`SkipSymlinks`], so they are neither followed nor updated
+/// nor deleted.
+fn find_symlinks(dir: &Path) -> std::io::Result<Vec<String>> {
+    let mut symlinks = Vec::new();
+    let mut todo = vec![dir.to_path_buf()];
+    while let Some(current) = todo.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let path = entry.path();
+            let unix_path = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let file_type = entry.file_type()?;
+            if file_type.is_symlink() {
+                symlinks.push(unix_path);
+            } else if file_type.is_dir()
+                && !KEEP_PATHS.contains(&format!("{unix_path}/").as_str())
+            {
+                todo.push(path);
+            }
+        }
+    }
+    Ok(symlinks)
+}
+
+/// Whether `unix_path` is one of the `symlinks` or inside of one.
+fn under_symlink(unix_path: &str, symlinks: &[String]) -> bool {
+    symlinks.iter().any(|link| {
+        unix_path == link
+            || unix_path
+                .strip_prefix(link.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Fails if `unix_path` or one of its folders below `root` is a symlink, writing
+/// through it could end up outside of the install. Remote files under the symlinks
+/// found before the sync are already skipped, see [`SkipSymlinks`].
+async fn check_no_symlink(
+    root: &Path,
+    unix_path: &str,
+) -> Result<(), TokioLocalStorageError> {
+    let mut path = root.to_path_buf();
+    for component in unix_path.split('/') {
+        path.push(component);
+        match tokio::fs::symlink_metadata(&path).await {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                return Err(TokioLocalStorageError::AccessOutOfBaseDirectory(path));
+            },
+            Ok(_) => {},
+            // created by the update
+            Err(_) => break,
+        }
+    }
+    Ok(())
+}
+
+/// Drops the remote files which would be written through a symlink in the install,
+/// see [`find_symlinks`]. Without them they would be downloaded again on every
+/// update, as remozipsy doesn't see them locally.
+#[derive(Debug, Clone)]
+pub(crate) struct SkipSymlinks<R> {
+    inner: R,
+    symlinks: Arc<[String]>,
+}
+
+impl<R> SkipSymlinks<R> {
+    fn new(inner: R, symlinks: Vec<String>) -> Self {
+        Self {
+            inner,
+            symlinks: symlinks.into(),
+        }
+    }
+}
+
+impl<R> remozipsy::RemoteZip for SkipSymlinks<R>
+where
+    R: remozipsy::RemoteZip + Sync,
+{
+    type Error = R::Error;
+
+    fn fetch_remote_file_info(
+        &self,
+    ) -> impl Future<Output = Result<Vec<remozipsy::RemoteFileInfo>, Self::Error>> + Send
+    {
+        let fetch = self.inner.fetch_remote_file_info();
+        let symlinks = Arc::clone(&self.symlinks);
+        async move {
+            let mut files = fetch.await?;
+            files.retain(|file| {
+                let skipped = under_symlink(&file.file_name, &symlinks);
+                if skipped {
+                    tracing::debug!(path = ?file.file_name, "Not updating a symlinked file");
+                }
+                !skipped
+            });
+            Ok(files)
+        }
+    }
+
+    fn fetch_bytes_stream(
+        &self,
+        range: std::ops::RangeInclusive<usize>,
+    ) -> impl Future<
+        Output = Result<
+            impl Stream<Item = Result<bytes::Bytes, Self::Error>> + Send,
+            Self::Error,
+        >,
+    > + Send {
+        self.inner.fetch_bytes_stream(range)
+    }
+}
+
+/// Map files of the remote which are already installed. When the shipped maps are
+/// synced for the first time nothing is recorded yet, and these came with an earlier
+/// version rather than from the user.
+async fn installed_shipped_maps(
+    remote: &impl remozipsy::RemoteZip,
+    dir: &Path,
+) -> Vec<String> {
+    let remote_maps: BTreeSet<String> = match remote.fetch_remote_file_info().await {
+        Ok(files) => files
+            .into_iter()
+            .map(|file| file.file_name)
+            .filter(|path| path.starts_with(MAPS_DIR))
+            .collect(),
+        Err(e) => {
+            tracing::warn!(?e, "Could not list the shipped maps");
+            return Vec::new();
+        },
+    };
+    let mut installed = Vec::new();
+    let mut todo = vec![dir.join(MAPS_DIR)];
+    while let Some(current) = todo.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                todo.push(path);
+                continue;
+            }
+            let unix_path = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            if remote_maps.contains(&unix_path) {
+                installed.push(unix_path);
+            }
+        }
+    }
+    installed.sort();
+    installed
+}
+
+/// Turns a compaction pattern into a regex matching unix paths relative to the
+/// install, see [`crate::profiles::Compaction`].
+fn compaction_regex(pattern: &str) -> Result<regex::Regex, regex::Error> {
+    let glob = regex::escape(pattern.trim())
+        .replace(r"\*\*/", "(.*/)?")
+        .replace(r"\*\*", ".*")
+        .replace(r"\*", "[^/]*");
+    if pattern.contains('/') {
+        regex::Regex::new(&format!("^{glob}$"))
+    } else {
+        regex::Regex::new(&format!("(^|/){glob}$"))
+    }
+}
+
+/// Removes the files matching `patterns` outside of the kept paths and maps, returns
+/// their unix paths and the bytes reclaimed.
+fn compact(dir: &Path, patterns: &[regex::Regex]) -> std::io::Result<(Vec<String>, u64)> {
+    let mut removed = Vec::new();
+    let mut reclaimed = 0;
+    let mut todo = vec![dir.to_path_buf()];
+    while let Some(current) = todo.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let path = entry.path();
+            let unix_path = path
+                .strip_prefix(dir)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            // does not follow symlinks
+            let meta = entry.metadata()?;
+            if meta.is_dir() {
+                let dir_path = format!("{unix_path}/");
+                if !KEEP_PATHS.contains(&dir_path.as_str()) && dir_path != MAPS_DIR {
+                    todo.push(path);
+                }
+            } else if meta.is_file()
+                && !KEEP_PATHS.contains(&unix_path.as_str())
+                && patterns.iter().any(|pattern| pattern.is_match(&unix_path))
+            {
+                std::fs::remove_file(&path)?;
+                tracing::debug!(?unix_path, "Compacted");
+                reclaimed += meta.len();
+                removed.push(unix_path);
+            }
+        }
+    }
+    Ok((removed, reclaimed))
+}
+
+/// Runs the compaction of `profile` if it is enabled, a failure only skips it.
+async fn compact_install(profile: &mut Profile) {
+    if !profile.compaction.enabled {
+        return;
+    }
+    let patterns: Vec<_> = profile
+        .compaction
+        .patterns
+        .iter()
+        .filter_map(|pattern| match compaction_regex(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                tracing::warn!(?e, ?pattern, "Ignoring invalid compaction pattern");
+                None
+            },
+        })
+        .collect();
+    let dir = profile.directory();
+    match tokio::task::spawn_blocking(move || compact(&dir, &patterns)).await {
+        Ok(Ok((removed, reclaimed))) => {
+            tracing::info!(
+                "Compaction removed {} files and reclaimed {}",
+                removed.len(),
+                pretty_bytes(reclaimed)
+            );
+            for path in removed {
+                if !profile.compacted_files.contains(&path) {
+                    profile.compacted_files.push(path);
+                }
+            }
+        },
+        Ok(Err(e)) => tracing::warn!(?e, "Failed to compact the install"),
+        Err(e) => tracing::warn!(?e, "Compacting the install panicked"),
+    }
+}
+
+async fn profile_fingerprint(profile: &Profile) -> Option<u32> {
+    let dir = profile.directory();
+    let shipped_maps = profile
+        .sync_shipped_maps
+        .then(|| profile.shipped_maps.clone());
+    match tokio::task::spawn_blocking(move || {
+        install_fingerprint(&dir, shipped_maps.as_deref())
+    })
+    .await
+    {
+        Ok(Ok(fingerprint)) => Some(fingerprint),
+        Ok(Err(e)) => {
+            tracing::debug!(?e, "Could not fingerprint the installed files");
+            None
+        },
+        Err(e) => {
+            tracing::warn!(?e, "Fingerprinting the installed files panicked");
+            None
+        },
+    }
+}
+
+/// Outcome of [`quick_check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QuickCheck {
+    UpToDate,
+    /// a different version than the installed one is available
+    Outdated {
+        installed: Option<String>,
+        latest: String,
+    },
+    /// the installed files changed since the last update, e.g. they were modified or
+    /// an update was interrupted
+    Modified,
+    Offline,
+}
+
+/// Checks whether the install is current by only asking the download server for the
+/// latest version. The installed files are trusted if their sizes and modification
+/// times match the last update, no file is hashed. Unlike an update this is no
+/// integrity check, files corrupted without changing their size or modification time
+/// go unnoticed.
+pub(crate) async fn quick_check(profile: &Profile) -> Result<QuickCheck, ClientError> {
+    let latest = match version(profile).await {
+        Ok(latest) => latest,
+        Err(e @ (ClientError::UnknownChannel(_) | ClientError::HtmlResponse(_))) => {
+            return Err(e);
+        },
+        Err(_) => return Ok(QuickCheck::Offline),
+    };
+
+    if profile.version.as_deref() != Some(latest.as_str()) {
+        return Ok(QuickCheck::Outdated {
+            installed: profile.version.clone(),
+            latest,
+        });
+    }
+    match profile.install_fingerprint {
+        Some(expected) if profile_fingerprint(profile).await == Some(expected) => {
+            Ok(QuickCheck::UpToDate)
+        },
+        _ => Ok(QuickCheck::Modified),
+    }
+}
+
+/// Like [`ProgressDetails::percent_complete`], but treats a step without any bytes as
+/// completed instead of dividing by zero.
+pub(crate) fn percent_complete(progress: &ProgressDetails) -> u64 {
+    percent(progress.processed_bytes(), progress.total_bytes())
+}
+
+/// `X / Y files` of the deletion. remozipsy counts the files to delete in the bytes of
+/// `delete`, which start over when a stalled sync is restarted, `deleted_files` also
+/// counts the files deleted before.
+pub(crate) fn deleted_files_text(delete: &ProgressDetails, deleted_files: u64) -> String {
+    let remaining = delete
+        .total_bytes()
+        .saturating_sub(delete.processed_bytes());
+    format!("{deleted_files} / {} files", deleted_files + remaining)
 }
 
-async fn version(url: String) -> Result<String, reqwest::Error> {
-    WEB_CLIENT.get(url).send().await?.text().await
+/// Progress of downloading and unzipping together. Downloading is counted in compressed
+/// and unzipping in decompressed bytes, so a well compressible update doesn't reach
+/// 100% while most of its work is still unzipping.
+pub(crate) fn combined_percent(
+    download: &ProgressDetails,
+    unzip: &ProgressDetails,
+) -> u64 {
+    weighted_percent(
+        [download, unzip].map(|step| (step.processed_bytes(), step.total_bytes())),
+    )
+}
+
+fn weighted_percent(steps: [(u64, u64); 2]) -> u64 {
+    let processed = steps
+        .iter()
+        .map(|&(processed, total)| processed.min(total))
+        .sum();
+    let total = steps.iter().map(|&(_, total)| total).sum();
+    percent(processed, total)
+}
+
+fn percent(processed: u64, total: u64) -> u64 {
+    (processed.min(total) * 100)
+        .checked_div(total)
+        .unwrap_or(100)
 }
 
 fn cache_base_path() -> PathBuf {
     crate::fs::get_cache_path().join("remotezip")
 }
 
+/// extension of the cached remote file lists, see [`Profile::compact_file_lists`]
+const FILE_LIST_EXTENSION: &str = ".ron";
+const COMPACT_FILE_LIST_EXTENSION: &str = ".ron.gz";
+
+fn file_list_path(dir: &Path, version: &str, compact: bool) -> PathBuf {
+    let extension = if compact {
+        COMPACT_FILE_LIST_EXTENSION
+    } else {
+        FILE_LIST_EXTENSION
+    };
+    dir.join(format!("{version}{extension}"))
+}
+
+/// Loads the cached remote file list of `version` in either format, returns whether
+/// it is stored in the format selected by `compact`.
+async fn load_file_list<T: DeserializeOwned>(
+    dir: &Path,
+    version: &str,
+    compact: bool,
+) -> Option<(T, bool)> {
+    for stored_compact in [compact, !compact] {
+        let path = file_list_path(dir, version, stored_compact);
+        let Ok(bytes) = tokio::fs::read(&path).await else {
+            continue;
+        };
+        let content = match crate::fs::decode_stored(&bytes) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!(?e, ?path, "Could not read the cached remote file list");
+                continue;
+            },
+        };
+        if let Ok(list) = crate::fs::parse_ron(&path, &content) {
+            return Some((list, stored_compact == compact));
+        }
+    }
+    None
+}
+
+/// Caches the remote file list of `version`, the compact format is gzipped RON
+/// without any whitespace, which is a fraction of the size for large installs.
+fn save_file_list<T: Serialize>(
+    dir: &Path,
+    version: &str,
+    list: &T,
+    compact: bool,
+) -> Result<(), ClientError> {
+    let data = crate::fs::encode_stored(list, compact)?;
+    std::fs::write(file_list_path(dir, version, compact), data)?;
+    // the list in the other format is outdated now
+    let _ = std::fs::remove_file(file_list_path(dir, version, !compact));
+    Ok(())
+}
+
+/// A cached remote file list of a single game version
+#[derive(Debug, Clone)]
+pub(crate) struct CachedVersion {
+    pub version: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+/// cached remote file lists older than this are removed after an update
+const CACHE_MAX_AGE: Duration = Duration::from_secs(14 * 86400);
+/// modification times further ahead than this mean the clock is wrong, a little skew
+/// e.g. to a network drive is expected
+const CLOCK_SKEW_TOLERANCE: Duration = Duration::from_secs(86400);
+
+/// How far the newest cached file was modified ahead of `now`, if further than the
+/// tolerance. The clock was set back since or is wrong now.
+pub(crate) fn clock_skew(
+    versions: &[CachedVersion],
+    now: SystemTime,
+) -> Option<Duration> {
+    versions
+        .iter()
+        .filter_map(|cached| cached.modified.duration_since(now).ok())
+        .max()
+        .filter(|skew| *skew > CLOCK_SKEW_TOLERANCE)
+}
+
+/// Cached remote file lists which are old enough to be removed. With a skewed clock
+/// their ages can't be trusted, so none are.
+fn expired_cache_files(
+    versions: &[CachedVersion],
+    now: SystemTime,
+) -> Vec<&CachedVersion> {
+    static WARNED: std::sync::Once = std::sync::Once::new();
+
+    if let Some(skew) = clock_skew(versions, now) {
+        WARNED.call_once(|| {
+            tracing::warn!(
+                "Cached files were modified {:.1} days in the future, the system clock \
+                 seems wrong. Old cache files are kept until it is fixed.",
+                skew.as_secs_f64() / 86400.0
+            )
+        });
+        return Vec::new();
+    }
+    let Some(max_age) = now.checked_sub(CACHE_MAX_AGE) else {
+        return Vec::new();
+    };
+    versions
+        .iter()
+        .filter(|cached| cached.modified < max_age)
+        .collect()
+}
+
+/// Returns all cached remote file lists, newest first
+pub(crate) fn cached_versions() -> std::io::Result<Vec<CachedVersion>> {
+    let dir = match std::fs::read_dir(cache_base_path()) {
+        Ok(dir) => dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut versions = Vec::new();
+    for file in dir.flatten() {
+        let path = file.path();
+        let cached = match file
+            .metadata()
+            .and_then(|meta| Ok((meta.modified()?, meta)))
+        {
+            Ok((modified, meta)) if meta.is_file() => CachedVersion {
+                version: file_list_version(&path),
+                path,
+                size: meta.len(),
+                modified,
+            },
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::warn!(?e, ?path, "Failed to read cache file metadata");
+                continue;
+            },
+        };
+        versions.push(cached);
+    }
+    versions.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(versions)
+}
+
+fn file_list_version(path: &Path) -> String {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    [COMPACT_FILE_LIST_EXTENSION, FILE_LIST_EXTENSION]
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension))
+        .map(str::to_owned)
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+}
+
+/// Pause before every step when the update is driven by the GUI, so the iced event loop
+/// gets a chance to render in between.
+pub(crate) const GUI_STEP_DELAY: Duration = Duration::from_millis(5);
+/// Pause before every step of [`update`]. remozipsy returns right away while its tasks
+/// are still running, without any pause the CLI would keep a core busy polling it.
+const CLI_STEP_DELAY: Duration = Duration::from_millis(1);
+
 impl State {
     pub(crate) async fn progress(self) -> Option<(Progress, Self)> {
-        tokio::time::sleep(Duration::from_millis(5)).await;
         match self {
             State::ToBeEvaluated(profile) => evaluate(profile).await,
-            State::Sync(profile, statemachine) => sync(profile, statemachine).await,
+            State::Sync(profile, statemachine, bookkeeping) => {
+                sync(profile, statemachine, bookkeeping).await
+            },
             State::Finished => None,
         }
     }
@@ -72,21 +810,53 @@ impl State {
 
 // checks if an update is necessary
 async fn evaluate(mut profile: Profile) -> Option<(Progress, State)> {
+    let directory = profile.directory();
+    if let Err(e) = recover_missing_install(&mut profile, &directory) {
+        return Some((Progress::Errored(e.into()), State::Finished));
+    }
+
     tracing::info!("Evaluating remote version...");
-    let remote_version = match version(profile.version_url()).await {
+    let remote_version = match version(&profile).await {
         Ok(ok) => ok,
+        Err(e @ (ClientError::UnknownChannel(_) | ClientError::HtmlResponse(_))) => {
+            return Some((Progress::Errored(e), State::Finished));
+        },
         Err(_) => return Some((Progress::Offline, State::Finished)),
     };
+    profile.last_checked = Some(chrono::Utc::now());
 
+    // nothing changed since the last successful update, skip verifying every file
+    if profile.version.as_deref() == Some(remote_version.as_str())
+        && let Some(expected) = profile.install_fingerprint
+        && profile_fingerprint(&profile).await == Some(expected)
+    {
+        tracing::info!("Installed files unchanged since the last update");
+        return Some((Progress::Successful(Box::new(profile)), State::Finished));
+    }
+
+    // copied before the first file changes, see `crate::rollback`
+    let kept_install = (profile.version.as_ref() != Some(&remote_version))
+        .then(|| Box::new(profile.clone()));
     profile.version = Some(remote_version.clone());
+    profile.install_fingerprint = None;
+    profile.skipped_version = None;
+    // so they don't show up as files to delete
+    crate::fs::remove_orphaned_temp_files(&profile.directory());
 
-    let cache_file_parent = cache_base_path();
-    let cache_file = cache_file_parent.join(format!("{remote_version}.ron"));
+    let cache_dir = cache_base_path();
+    let compact = profile.compact_file_lists;
     let mut cache = None;
-    if tokio::fs::create_dir_all(cache_file_parent).await.is_ok()
-        && let Ok(file_content) = tokio::fs::read_to_string(&cache_file).await
-        && let Ok(content) = ron::from_str(&file_content)
+    if tokio::fs::create_dir_all(&cache_dir).await.is_ok()
+        && let Some((content, current)) =
+            load_file_list(&cache_dir, &remote_version, compact).await
     {
+        if !current {
+            tracing::debug!(compact, "Converting the cached remote file list");
+            if let Err(e) = save_file_list(&cache_dir, &remote_version, &content, compact)
+            {
+                tracing::warn!(?e, "Could not convert the cached remote file list");
+            }
+        }
         cache = Some(content);
     };
     let need_save_cache = cache.is_none();
@@ -99,35 +869,110 @@ async fn evaluate(mut profile: Profile) -> Option<(Progress, State)> {
         tracing::debug!("Remote file list found in cache. Verifying file hashes");
     }
 
-    let Ok(remote) = ReqwestRemoteZip::with_url(profile.download_url()) else {
+    let download_url =
+        resolve_download_url(&profile, profile.download_url(&remote_version)).await;
+    // the client of the download server, with its pinned keys, HTTP version and
+    // credentials
+    let Ok(remote) = ReqwestRemoteZip::with_service(
+        crate::net::download_client(&profile, &download_url),
+        download_url.clone(),
+        MAX_EOCD_SIZE,
+    ) else {
         return Some((Progress::Offline, State::Finished));
     };
     let remote = ReqwestCachedRemoteZip::with_inner(remote, cache);
-    const KEEP_PATHS: &[&str] = &["userdata/", "screenshots/", "maps/", "veloren.zip"];
-    let ignore = KEEP_PATHS.iter().map(|p| p.to_string()).collect();
+    let mut ignore: Vec<String> = KEEP_PATHS.iter().map(|p| p.to_string()).collect();
+    let directory = profile.directory();
+    let symlinks =
+        match tokio::task::spawn_blocking(move || find_symlinks(&directory)).await {
+            Ok(Ok(symlinks)) => symlinks,
+            Ok(Err(e)) => {
+                tracing::debug!(?e, "Could not look for symlinks in the install");
+                Vec::new()
+            },
+            Err(e) => {
+                tracing::warn!(?e, "Looking for symlinks panicked");
+                Vec::new()
+            },
+        };
+    for symlink in &symlinks {
+        tracing::warn!("Skipping symlink {} in the install", symlink);
+        ignore.push(symlink.clone());
+    }
+    // compacted files are missing on purpose, don't download them again
+    if profile.compaction.enabled {
+        ignore.extend(profile.compacted_files.iter().cloned());
+    }
+    // shipped maps are only synced on request, user maps are always kept
+    let shipped_maps = if profile.sync_shipped_maps {
+        if profile.shipped_maps.is_empty() {
+            profile.shipped_maps =
+                installed_shipped_maps(&remote, &profile.directory()).await;
+            tracing::debug!(
+                maps = profile.shipped_maps.len(),
+                "Recorded the installed shipped maps"
+            );
+        }
+        Some(ShippedMaps::new(&profile.shipped_maps))
+    } else {
+        ignore.push(MAPS_DIR.to_string());
+        None
+    };
+    let config = sync_config(&profile);
+    let update_log = profile.update_log.then(|| {
+        UpdateLog::new(crate::update_log::Setup {
+            version: remote_version.clone(),
+            max_parallel_downloads: config.max_parallel_downloads,
+            max_parallel_filesystem: config.max_parallel_filesystem,
+            serial_unzip: profile.serial_unzip,
+        })
+    });
+    let deleted_files = Arc::new(AtomicU64::new(0));
     let local = PatchedLocalStorage {
-        inner: TokioLocalStorage::new(profile.directory(), ignore),
+        inner: TokioLocalStorage::new(profile.directory(), ignore.clone()),
+        root: profile.directory(),
+        ignore,
+        hash_index: Some(crate::fs::hash_index_file(
+            &profile.name,
+            profile.slot_name(),
+        )),
         patches: profile.patched_crc32s.clone(),
+        shipped_maps: shipped_maps.clone(),
+        deleted_files: Arc::clone(&deleted_files),
+        serial_unzip: profile.serial_unzip.then(|| {
+            tracing::debug!("Writing one file at a time");
+            Arc::new(tokio::sync::Semaphore::new(1))
+        }),
+        update_log: update_log.clone(),
+    };
+    let mut bookkeeping = SyncBookkeeping {
+        download_url,
+        shipped_maps,
+        deleted_files,
+        last_save: Instant::now(),
+        started: Instant::now(),
+        kept_install,
+        watchdog: Watchdog::new(&profile),
+        timeout_restarts: 0,
+        last_progress: None,
+        unzip_bytes: None,
+        update_log: update_log.clone(),
     };
-    let config = remozipsy::Config::default();
-    let statemachine = Statemachine::new(remote.clone(), local, config);
+    let statemachine = Statemachine::new(
+        LoggedRemoteZip::new(SkipSymlinks::new(remote.clone(), symlinks), update_log),
+        local,
+        config,
+    );
 
     // we are triggering remozipsy ONCE, so we get the result of the evalute phase
     if let Some((pg, statemachine)) = statemachine.progress().await {
         if need_save_cache {
             match remote.try_cache_content() {
                 Some(content) => {
-                    match to_string_pretty(&content, PrettyConfig::default()) {
-                        Ok(ron_string) => {
-                            if let Err(e) = tokio::fs::write(cache_file, ron_string).await
-                            {
-                                tracing::warn!(?e, "Could not cache the remote zip");
-                            };
-                        },
-                        Err(e) => tracing::warn!(
-                            ?e,
-                            "Could not serialize remote zip file list for caching"
-                        ),
+                    if let Err(e) =
+                        save_file_list(&cache_dir, &remote_version, &content, compact)
+                    {
+                        tracing::warn!(?e, "Could not cache the remote zip");
                     }
                 },
                 None => tracing::warn!(
@@ -136,99 +981,517 @@ async fn evaluate(mut profile: Profile) -> Option<(Progress, State)> {
             }
         }
 
+        // e.g. the end of the zip wasn't found, as it is no zip
+        if let remozipsy::Progress::Errored(e) = pg {
+            let e =
+                explain_sync_error(e.into(), &profile, &bookkeeping.download_url).await;
+            return Some((Progress::Errored(e), State::Finished));
+        }
+
+        // remozipsy only exposes the totals of its plan, the files themselves are
+        // logged at trace level while they are processed
+        if let remozipsy::Progress::Incomplete {
+            download,
+            unzip,
+            delete,
+        } = &pg
+        {
+            tracing::debug!(
+                version = %remote_version,
+                download_bytes = download.total_bytes(),
+                unzip_bytes = unzip.total_bytes(),
+                delete_bytes = delete.total_bytes(),
+                "Update plan"
+            );
+        }
+
+        // checked once the update is confirmed, it might still be skipped
+        if let remozipsy::Progress::Incomplete { unzip, .. } = &pg
+            && unzip.total_bytes() > 0
+        {
+            bookkeeping.unzip_bytes = Some(unzip.total_bytes());
+        }
+
+        // nothing to download, so there is nothing the user has to confirm
+        if let remozipsy::Progress::Incomplete {
+            download,
+            unzip,
+            delete,
+        } = &pg
+            && download.total_bytes() == 0
+        {
+            return Some((
+                Progress::Incomplete {
+                    download: download.clone(),
+                    unzip: unzip.clone(),
+                    delete: delete.clone(),
+                    deleted_files: 0,
+                },
+                State::Sync(profile, statemachine, bookkeeping),
+            ));
+        }
+
         if !matches!(pg, remozipsy::Progress::Successful) {
+            let download_bytes = match &pg {
+                remozipsy::Progress::Incomplete { download, .. } => {
+                    download.total_bytes()
+                },
+                _ => 0,
+            };
             return Some((
                 Progress::ReadyToSync {
                     version: remote_version,
+                    download_bytes,
                 },
-                State::Sync(profile, statemachine),
+                State::Sync(profile, statemachine, bookkeeping),
             ));
         }
     };
 
-    Some((Progress::Successful(profile), State::Finished))
+    Some((Progress::Successful(Box::new(profile)), State::Finished))
+}
+
+/// Forgets the installed game if its folder was deleted or emptied behind the
+/// launcher's back, e.g. when the user moved it, so it is installed from scratch
+/// instead of comparing against files which aren't there. The folder is created again
+/// if needed.
+fn recover_missing_install(profile: &mut Profile, dir: &Path) -> std::io::Result<()> {
+    let missing = match std::fs::read_dir(dir) {
+        Ok(mut entries) => entries.next().is_none(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => true,
+        Err(e) => return Err(e),
+    };
+    if missing && profile.version.is_some() {
+        tracing::warn!(
+            "The install folder {} is missing or empty, installing the game again",
+            dir.display()
+        );
+        profile.version = None;
+        profile.install_fingerprint = None;
+        profile.last_updated = None;
+        profile.patched_crc32s.clear();
+        profile.shipped_maps.clear();
+        profile.compacted_files.clear();
+    }
+    std::fs::create_dir_all(dir)
+}
+
+/// Fails if writing `bytes` to the install would leave less than the free space margin
+/// of `profile`. Updates aren't blocked if the free space can't be determined.
+fn check_free_space(profile: &Profile, bytes: u64) -> Result<(), ClientError> {
+    let available = match crate::fs::available_space(&profile.directory()) {
+        Ok(available) => available,
+        Err(e) => {
+            tracing::warn!(?e, "Could not determine the free disk space");
+            return Ok(());
+        },
+    };
+    let needed = profile.min_free_space_margin.required_space(bytes);
+    tracing::debug!(needed, available, "Checking the free disk space");
+    if available < needed {
+        return Err(ClientError::NotEnoughSpace {
+            needed: pretty_bytes(needed),
+            available: pretty_bytes(available),
+        });
+    }
+    Ok(())
 }
 
 // checks if an update is necessary
 async fn sync(
     profile: Profile,
-    statemachine: Statemachine<
-        ReqwestCachedRemoteZip<reqwest::Client>,
-        PatchedLocalStorage,
-    >,
+    statemachine: SyncStatemachine,
+    mut bookkeeping: SyncBookkeeping,
 ) -> Option<(Progress, State)> {
-    match statemachine.progress().await {
-        Some((p, s)) => Some(match p {
+    if let Some(bytes) = bookkeeping.unzip_bytes.take() {
+        // the installed version is copied before the update, if it is kept
+        let kept = match bookkeeping.kept_install.clone() {
+            Some(installed) => tokio::task::spawn_blocking(move || {
+                crate::rollback::kept_size(&installed)
+            })
+            .await
+            .ok()
+            .and_then(|size| size.ok())
+            .unwrap_or_default(),
+            None => 0,
+        };
+        if let Err(e) = check_free_space(&profile, bytes.saturating_add(kept)) {
+            return Some((Progress::Errored(e), State::Finished));
+        }
+    }
+    if let Some(installed) = bookkeeping.kept_install.take() {
+        match tokio::task::spawn_blocking(move || {
+            crate::rollback::keep_installed(&installed)
+        })
+        .await
+        {
+            Ok(Ok(())) => {},
+            Ok(Err(e)) => tracing::warn!(?e, "Could not keep the installed version"),
+            Err(e) => tracing::warn!(?e, "Keeping the installed version panicked"),
+        }
+    }
+    let (p, s) = statemachine.progress().await?;
+    if let Some(log) = &bookkeeping.update_log {
+        match &p {
             remozipsy::Progress::Incomplete {
-                download,
-                unzip,
-                delete,
-            } => (
-                Progress::Incomplete {
+                download, unzip, ..
+            } => log.step(download, unzip),
+            remozipsy::Progress::Errored(remozipsy::Error::InvalidHash {
+                remote,
+                calculated,
+            }) => {
+                log.hash_mismatch(*remote, *calculated);
+                write_update_log(log).await
+            },
+            _ => write_update_log(log).await,
+        }
+    }
+    Some(match p {
+        remozipsy::Progress::Incomplete {
+            download,
+            unzip,
+            delete,
+        } => {
+            if bookkeeping.watchdog.stalled(
+                download.processed_bytes(),
+                download.total_bytes(),
+                unzip.processed_bytes(),
+                Instant::now(),
+            ) {
+                tracing::warn!(
+                    downloaded = download.processed_bytes(),
+                    total = download.total_bytes(),
+                    timeout = ?bookkeeping.watchdog.timeout,
+                    "The download stalled, restarting it"
+                );
+                // dropping the statemachine aborts its download tasks
+                drop(s);
+                let progress = Progress::Incomplete {
                     download,
                     unzip,
                     delete,
-                },
-                State::Sync(profile, s),
-            ),
-            remozipsy::Progress::Successful => match final_cleanup(profile).await {
-                Ok(p) => (Progress::Successful(p), State::Finished),
+                    deleted_files: bookkeeping.deleted_files.load(Ordering::Relaxed),
+                };
+                return restart_sync(profile, bookkeeping, Some(progress)).await;
+            }
+            if bookkeeping.last_save.elapsed() >= PROGRESS_SAVE_INTERVAL {
+                save_progress(&profile, bookkeeping.shipped_maps.as_ref()).await;
+                bookkeeping.last_save = Instant::now();
+            }
+            let progress = Progress::Incomplete {
+                download,
+                unzip,
+                delete,
+                deleted_files: bookkeeping.deleted_files.load(Ordering::Relaxed),
+            };
+            bookkeeping.last_progress = Some(progress.clone());
+            (progress, State::Sync(profile, s, bookkeeping))
+        },
+        remozipsy::Progress::Successful => {
+            tracing::info!(
+                serial_unzip = profile.serial_unzip,
+                "Synced the install in {:.1?}",
+                bookkeeping.started.elapsed()
+            );
+            let mut profile = profile;
+            if let Some(maps) = bookkeeping.shipped_maps {
+                profile.shipped_maps = maps.to_vec();
+            }
+            match final_cleanup(profile).await {
+                Ok(p) => (Progress::Successful(Box::new(p)), State::Finished),
                 Err(e) => (Progress::Errored(e), State::Finished),
-            },
-            remozipsy::Progress::Errored(e) => {
-                (Progress::Errored(e.into()), State::Finished)
-            },
-        }),
-        None => None,
+            }
+        },
+        remozipsy::Progress::Errored(remozipsy::Error::InvalidHash {
+            remote,
+            calculated,
+        }) => (
+            Progress::Errored(ClientError::Verification { remote, calculated }),
+            State::Finished,
+        ),
+        remozipsy::Progress::Errored(e) => {
+            if timed_out(&e) && bookkeeping.timeout_restarts < IDLE_TIMEOUT_RESTARTS {
+                tracing::warn!(
+                    ?e,
+                    restarts = bookkeeping.timeout_restarts,
+                    "A download connection timed out, restarting the sync"
+                );
+                bookkeeping.timeout_restarts += 1;
+                // also restarted if it timed out before the first progress
+                let progress = bookkeeping.last_progress.take();
+                return restart_sync(profile, bookkeeping, progress).await;
+            }
+            tracing::debug!(
+                url = ?bookkeeping.download_url,
+                "Syncing the install failed"
+            );
+            let e =
+                explain_sync_error(e.into(), &profile, &bookkeeping.download_url).await;
+            (Progress::Errored(e), State::Finished)
+        },
+    })
+}
+
+/// remozipsy reports a web page, e.g. of a captive portal, like a broken zip. If the
+/// download URL serves one, a [`ClientError::HtmlResponse`] explains the failure
+/// instead.
+async fn explain_sync_error(e: ClientError, profile: &Profile, url: &str) -> ClientError {
+    match e {
+        ClientError::GameUpdate(_) if crate::net::serves_html(url, profile).await => {
+            tracing::warn!(?e, ?url, "The download URL serves a web page");
+            ClientError::HtmlResponse(url.to_string())
+        },
+        e => e,
+    }
+}
+
+/// Evaluates the install again after the sync stalled, which spawns the downloads of
+/// the files which are still missing anew. `progress` is reported until the new sync
+/// makes progress, without one the new sync takes its first step right away.
+async fn restart_sync(
+    profile: Profile,
+    stalled: SyncBookkeeping,
+    progress: Option<Progress>,
+) -> Option<(Progress, State)> {
+    match evaluate(profile).await {
+        Some((_, State::Sync(profile, statemachine, mut bookkeeping))) => {
+            bookkeeping.started = stalled.started;
+            bookkeeping.timeout_restarts = stalled.timeout_restarts;
+            if let (Some(log), Some(stalled)) =
+                (&bookkeeping.update_log, &stalled.update_log)
+            {
+                log.continue_from(stalled);
+            }
+            bookkeeping.deleted_files.fetch_add(
+                stalled.deleted_files.load(Ordering::Relaxed),
+                Ordering::Relaxed,
+            );
+            match progress {
+                Some(progress) => {
+                    Some((progress, State::Sync(profile, statemachine, bookkeeping)))
+                },
+                None => Box::pin(sync(profile, statemachine, bookkeeping)).await,
+            }
+        },
+        Some(result) => Some(result),
+        None => Some((
+            Progress::Errored(ClientError::Custom(
+                "Restarting the stalled download failed".into(),
+            )),
+            State::Finished,
+        )),
+    }
+}
+
+/// default of [`Profile::download_idle_timeout`]
+pub const DEFAULT_DOWNLOAD_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// how often the sync is restarted after a connection went idle, before giving up
+const IDLE_TIMEOUT_RESTARTS: u32 = 3;
+
+/// `None` if disabled, see [`Profile::download_idle_timeout`]
+pub(crate) fn download_idle_timeout(profile: &Profile) -> Option<Duration> {
+    match profile.download_idle_timeout {
+        Some(0) => None,
+        Some(secs) => Some(Duration::from_secs(secs)),
+        None => Some(DEFAULT_DOWNLOAD_IDLE_TIMEOUT),
+    }
+}
+
+/// Whether a download connection timed out, e.g. it stopped receiving data for longer
+/// than [`download_idle_timeout`].
+fn timed_out(
+    e: &remozipsy::Error<ReqwestRemoteZipError, TokioLocalStorageError>,
+) -> bool {
+    matches!(
+        e,
+        remozipsy::Error::Remote(
+            ReqwestRemoteZipError::Reqwest(e)
+                | ReqwestRemoteZipError::RemoteFetch(remozipsy::RemoteFetchError::Fetch(e))
+        ) if e.is_timeout()
+    )
+}
+
+/// default of [`Profile::download_stall_timeout`]
+pub const DEFAULT_DOWNLOAD_STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Notices a sync whose connections are open but dead. Only the idle timeout of single
+/// connections can't tell, as the stalled requests never fail.
+#[derive(Debug)]
+struct Watchdog {
+    /// `None` disables the watchdog
+    timeout: Option<Duration>,
+    downloaded: u64,
+    unzipped: u64,
+    /// when the sync last advanced, unset until it is polled the first time
+    advanced: Option<Instant>,
+}
+
+impl Watchdog {
+    fn new(profile: &Profile) -> Self {
+        let timeout = match profile.download_stall_timeout {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(DEFAULT_DOWNLOAD_STALL_TIMEOUT),
+        };
+        Self {
+            timeout,
+            downloaded: 0,
+            unzipped: 0,
+            advanced: None,
+        }
+    }
+
+    /// Whether files are left to download, but neither downloading nor unzipping
+    /// advanced within the timeout. Unzipping counts as progress, the downloads wait
+    /// for it while it is the bottleneck.
+    fn stalled(
+        &mut self,
+        downloaded: u64,
+        download_total: u64,
+        unzipped: u64,
+        now: Instant,
+    ) -> bool {
+        let Some(advanced) = self.advanced.filter(|_| {
+            downloaded == self.downloaded
+                && unzipped == self.unzipped
+                && downloaded < download_total
+        }) else {
+            self.downloaded = downloaded;
+            self.unzipped = unzipped;
+            self.advanced = Some(now);
+            return false;
+        };
+        self.timeout
+            .is_some_and(|timeout| now.duration_since(advanced) >= timeout)
+    }
+}
+
+/// state of an update which is kept next to the statemachine
+#[derive(Debug)]
+pub(super) struct SyncBookkeeping {
+    /// the download URL after following its redirects, see [`resolve_download_url`]
+    download_url: String,
+    shipped_maps: Option<ShippedMaps>,
+    deleted_files: Arc<AtomicU64>,
+    /// last time the progress was saved
+    last_save: Instant,
+    /// when the sync started, to compare the duration with and without a serial unzip
+    started: Instant,
+    /// the install before the update, kept for a rollback once the sync starts
+    kept_install: Option<Box<Profile>>,
+    watchdog: Watchdog,
+    /// how often the sync was restarted after a connection timed out
+    timeout_restarts: u32,
+    /// reported while the sync is restarted after a timeout
+    last_progress: Option<Progress>,
+    /// bytes the sync writes, the free space is checked before its first step, see
+    /// [`check_free_space`]
+    unzip_bytes: Option<u64>,
+    /// see [`Profile::update_log`]
+    update_log: Option<UpdateLog>,
+}
+
+async fn write_update_log(log: &UpdateLog) {
+    let path = crate::fs::update_log_file();
+    let log = log.clone();
+    let written = {
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || log.write(&path)).await
+    };
+    match written {
+        Ok(Ok(())) => tracing::info!("Wrote the update log to {}", path.display()),
+        Ok(Err(e)) => tracing::warn!(?e, "Could not write the update log"),
+        Err(e) => tracing::warn!(?e, "Writing the update log panicked"),
+    }
+}
+
+const PROGRESS_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Follows the redirects of the download URL once, e.g. to a CDN edge, so the range
+/// requests of the sync all go to the same final URL instead of being redirected
+/// one by one. Falls back to `url` if it can't be resolved.
+async fn resolve_download_url(profile: &Profile, url: String) -> String {
+    match crate::net::download_server_request(Method::HEAD, &url, profile)
+        .send()
+        .await
+    {
+        Ok(response) if response.status().is_success() => {
+            let resolved = response.url().to_string();
+            if resolved != url {
+                tracing::info!(
+                    ?url,
+                    ?resolved,
+                    "Download redirects, using the final URL"
+                );
+            }
+            resolved
+        },
+        Ok(response) => {
+            tracing::debug!(
+                status = ?response.status(),
+                "Could not resolve the download URL"
+            );
+            url
+        },
+        Err(e) => {
+            tracing::debug!(?e, "Could not resolve the download URL");
+            url
+        },
+    }
+}
+
+/// Persists the bookkeeping of an ongoing update, so it is not lost if the process dies
+/// before the update completes, see [`Profile::save_update_progress`].
+async fn save_progress(profile: &Profile, shipped_maps: Option<&ShippedMaps>) {
+    let shipped_maps = shipped_maps.map(ShippedMaps::to_vec);
+    if let Err(e) = profile.save_update_progress(shipped_maps).await {
+        tracing::warn!(?e, "Failed to save update progress");
     }
 }
 
 // permissions, update params
 async fn final_cleanup(mut profile: Profile) -> Result<Profile, ClientError> {
     // dont error, if cleanup fails
-    const DAYS_14: Duration = Duration::from_secs(14 * 86400);
-    if let (Ok(dir), Some(max_age)) = (
-        std::fs::read_dir(cache_base_path()),
-        SystemTime::now().checked_sub(DAYS_14),
-    ) {
-        for file in dir.flatten() {
-            if let Err(e) = || -> Result<(), Box<dyn std::error::Error>> {
-                let meta = file.metadata()?;
-                if !meta.is_file() {
-                    return Ok(());
-                }
-
-                let time = meta.modified()?;
-                if time < max_age {
-                    std::fs::remove_file(file.path())?;
-                    tracing::info!("removed old cache file: {:?}", file.file_name());
-                }
-
-                Ok(())
-            }() {
-                tracing::warn!(?e, "Failed to cleanup download cache")
+    if let Ok(versions) = cached_versions() {
+        for cached in expired_cache_files(&versions, SystemTime::now()) {
+            match std::fs::remove_file(&cached.path) {
+                Ok(()) => {
+                    tracing::info!(
+                        "removed old cache file: {:?}",
+                        cached.path.file_name()
+                    )
+                },
+                Err(e) => tracing::warn!(?e, "Failed to cleanup download cache"),
             }
         }
     }
 
+    // signing needs the previous checksums to skip binaries which are still signed
+    #[cfg(target_os = "macos")]
+    let signed = crate::macos::sign_binaries(&profile)?;
     profile.patched_crc32s.clear();
+    #[cfg(target_os = "macos")]
+    profile.patched_crc32s.extend(signed);
 
     #[cfg(unix)]
     {
-        use crate::{
-            consts::{SERVER_CLI_FILE, VOXYGEN_FILE},
-            nix,
-        };
+        use crate::nix;
         use std::os::unix::fs::PermissionsExt;
 
         let profile_directory = profile.directory();
+        let executables: Vec<String> = profile
+            .installed_executables()
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
 
         // Patch executable files if we are on NixOS
-        if nix::is_nixos()? {
-            let info = nix::patch(&profile_directory, VOXYGEN_FILE)?;
-            profile.patched_crc32s.push(info);
-            let info = nix::patch(&profile_directory, SERVER_CLI_FILE)?;
-            profile.patched_crc32s.push(info);
+        if !profile.disable_nix_patching && nix::is_nixos()? {
+            nix::check_patchers(&executables)?;
+            for file in &executables {
+                let info = nix::patch(&profile_directory, file)?;
+                profile.patched_crc32s.push(info);
+            }
         } else {
             let p = |path| async move {
                 let meta = tokio::fs::metadata(&path).await?;
@@ -239,13 +1502,18 @@ async fn final_cleanup(mut profile: Profile) -> Result<Profile, ClientError> {
             };
 
             tracing::info!("patching unix exec files");
-            let voxygen_file = profile_directory.join(VOXYGEN_FILE);
-            p(voxygen_file).await?;
-            let server_cli_file = profile_directory.join(SERVER_CLI_FILE);
-            p(server_cli_file).await?;
+            for file in &executables {
+                p(profile_directory.join(file)).await?;
+            }
         }
     }
 
+    compact_install(&mut profile).await;
+
+    profile.last_updated = Some(chrono::Utc::now());
+    // taken last, patching changes the modification times of the binaries
+    profile.install_fingerprint = profile_fingerprint(&profile).await;
+
     Ok(profile)
 }
 
@@ -254,15 +1522,156 @@ async fn final_cleanup(mut profile: Profile) -> Result<Profile, ClientError> {
 #[derive(Debug, Clone)]
 pub struct PatchedLocalStorage {
     inner: TokioLocalStorage,
+    root: PathBuf,
+    /// paths left out of the comparison, like the ones of `inner`
+    ignore: Vec<String>,
+    /// file with the CRC32s of the last comparison, see [`indexed_files`]
+    hash_index: Option<PathBuf>,
     patches: Vec<PatchedInfo>,
+    shipped_maps: Option<ShippedMaps>,
+    deleted_files: Arc<AtomicU64>,
+    /// single permit held from creating a file until it is written, see
+    /// [`Profile::serial_unzip`]
+    serial_unzip: Option<Arc<tokio::sync::Semaphore>>,
+    update_log: Option<UpdateLog>,
+}
+
+/// CRC32 of an installed file, valid as long as its size and modification time match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedFile {
+    size: u64,
+    modified: SystemTime,
+    crc32: u32,
+}
+
+/// Lists and hashes the installed files like [`TokioLocalStorage`], but only reads the
+/// files whose size or modification time changed since the last run. The CRC32s of the
+/// others are taken from the index at `index_path`, which is rewritten afterwards.
+///
+/// Note: it's synchronous!
+fn indexed_files(
+    root: &Path,
+    ignore: &[String],
+    index_path: &Path,
+) -> Result<Vec<remozipsy::FileInfo>, TokioLocalStorageError> {
+    // a missing or unreadable index only means that every file is hashed
+    let old: std::collections::HashMap<String, IndexedFile> = std::fs::read(index_path)
+        .ok()
+        .and_then(|bytes| crate::fs::decode_stored(&bytes).ok())
+        .and_then(|content| ron::from_str(&content).ok())
+        .unwrap_or_default();
+    let mut index = std::collections::BTreeMap::new();
+    let mut files = Vec::new();
+    let mut hashed = 0;
+    let mut todo = vec![root.to_path_buf()];
+    while let Some(current) = todo.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let path = entry?.path();
+            let relative = path.strip_prefix(root)?;
+            if ignore.iter().any(|ignore| relative.starts_with(ignore)) {
+                continue;
+            }
+            if path.is_dir() {
+                todo.push(path);
+                continue;
+            }
+            let local_unix_path = remozipsy::calculate_local_unix_path(root, &path)
+                .ok_or(TokioLocalStorageError::InvalidUtf8Filename)?;
+            let meta = std::fs::metadata(&path)?;
+            let (size, modified) = (meta.len(), meta.modified()?);
+            let crc32 = match old.get(&local_unix_path) {
+                Some(file) if file.size == size && file.modified == modified => {
+                    file.crc32
+                },
+                _ => {
+                    hashed += 1;
+                    crc32fast::hash(&std::fs::read(&path)?)
+                },
+            };
+            index.insert(local_unix_path.clone(), IndexedFile {
+                size,
+                modified,
+                crc32,
+            });
+            files.push(remozipsy::FileInfo {
+                local_unix_path,
+                crc32,
+            });
+        }
+    }
+    tracing::debug!(
+        files = files.len(),
+        hashed,
+        "Hashed the changed installed files"
+    );
+
+    let saved = crate::fs::encode_stored(&index, crate::fs::compress_stored_files())
+        .and_then(|data| {
+            let temp = crate::fs::temp_path(index_path);
+            std::fs::write(&temp, data)?;
+            Ok(std::fs::rename(temp, index_path)?)
+        });
+    if let Err(e) = saved {
+        tracing::warn!(?e, "Could not save the hash index");
+    }
+    Ok(files)
+}
+
+/// file being stored, with the permit of a serial unzip
+#[derive(Debug)]
+pub struct PreparedFile {
+    file: tokio::fs::File,
+    permit: Option<tokio::sync::OwnedSemaphorePermit>,
+    /// path of the file and when it started to be stored, for the update log
+    logged: Option<(String, Instant)>,
+}
+
+/// keeps track of the map files which were installed by airshipper. Everything else in
+/// the maps directory was created by the user and is hidden from remozipsy, so it is
+/// neither overwritten nor deleted.
+#[derive(Debug, Clone)]
+pub struct ShippedMaps(Arc<Mutex<BTreeSet<String>>>);
+
+impl ShippedMaps {
+    fn new(shipped: &[String]) -> Self {
+        Self(Arc::new(Mutex::new(shipped.iter().cloned().collect())))
+    }
+
+    fn is_user_map(&self, local_unix_path: &str) -> bool {
+        local_unix_path.starts_with(MAPS_DIR)
+            && !self.0.lock().unwrap().contains(local_unix_path)
+    }
+
+    fn insert(&self, local_unix_path: &str) {
+        if local_unix_path.starts_with(MAPS_DIR) {
+            self.0.lock().unwrap().insert(local_unix_path.to_string());
+        }
+    }
+
+    fn remove(&self, local_unix_path: &str) {
+        self.0.lock().unwrap().remove(local_unix_path);
+    }
+
+    fn to_vec(&self) -> Vec<String> {
+        self.0.lock().unwrap().iter().cloned().collect()
+    }
 }
 
 impl remozipsy::FileSystem for PatchedLocalStorage {
     type Error = remozipsy::tokio::TokioLocalStorageError;
-    type StorePrepare = tokio::fs::File;
+    type StorePrepare = PreparedFile;
 
     async fn all_files(&mut self) -> Result<Vec<remozipsy::FileInfo>, Self::Error> {
-        let mut all_files = self.inner.all_files().await?;
+        let mut all_files = match self.hash_index.clone() {
+            Some(index) => {
+                let root = self.root.clone();
+                let ignore = self.ignore.clone();
+                tokio::task::spawn_blocking(move || indexed_files(&root, &ignore, &index))
+                    .await
+                    .map_err(|e| TokioLocalStorageError::Io(std::io::Error::other(e)))??
+            },
+            None => self.inner.all_files().await?,
+        };
 
         for patches in &self.patches {
             if let Some(to_be_manipulated) = all_files.iter_mut().find(|e| {
@@ -273,6 +1682,14 @@ impl remozipsy::FileSystem for PatchedLocalStorage {
             }
         }
 
+        if let Some(maps) = &self.shipped_maps {
+            all_files.retain(|e| !maps.is_user_map(&e.local_unix_path));
+        }
+
+        tracing::debug!(local_files = all_files.len(), "Comparing installed files");
+        for file in &all_files {
+            tracing::trace!(path = ?file.local_unix_path, crc32 = file.crc32, "Installed");
+        }
         Ok(all_files)
     }
 
@@ -280,14 +1697,58 @@ impl remozipsy::FileSystem for PatchedLocalStorage {
         &self,
         info: remozipsy::FileInfo,
     ) -> impl Future<Output = Result<(), Self::Error>> {
-        self.inner.delete_file(info)
+        let path = info.local_unix_path.clone();
+        let full_path = self.root.join(&path);
+        let maps = self.shipped_maps.clone();
+        let deleted_files = Arc::clone(&self.deleted_files);
+        let delete = self.inner.delete_file(info);
+        async move {
+            tracing::trace!(?path, "Deleting");
+            // a file that is already gone does not need to be deleted anymore
+            if tokio::fs::try_exists(&full_path).await.unwrap_or(true) {
+                delete.await?;
+            } else {
+                tracing::debug!(?path, "File to delete is already gone");
+            }
+            if let Some(maps) = maps {
+                maps.remove(&path);
+            }
+            deleted_files.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
     }
 
     fn prepare_store_file(
         &self,
         info: remozipsy::FileInfo,
     ) -> impl Future<Output = Result<Self::StorePrepare, Self::Error>> {
-        self.inner.prepare_store_file(info)
+        let path = info.local_unix_path.clone();
+        let maps = self.shipped_maps.clone();
+        let serial_unzip = self.serial_unzip.clone();
+        let inner = self.inner.clone();
+        let logged = self.update_log.is_some();
+        let root = self.root.clone();
+        async move {
+            let started = Instant::now();
+            check_no_symlink(&root, &path).await?;
+            // the semaphore is fair, so files are written in the order they get here.
+            // With parallel filesystem operations that is close to, but not exactly,
+            // the order of the zip
+            let permit = match serial_unzip {
+                Some(semaphore) => semaphore.acquire_owned().await.ok(),
+                None => None,
+            };
+            tracing::trace!(?path, "Storing");
+            let file = inner.prepare_store_file(info).await?;
+            if let Some(maps) = maps {
+                maps.insert(&path);
+            }
+            Ok(PreparedFile {
+                file,
+                permit,
+                logged: logged.then_some((path, started)),
+            })
+        }
     }
 
     fn store_file(
@@ -295,6 +1756,748 @@ impl remozipsy::FileSystem for PatchedLocalStorage {
         prepared: Self::StorePrepare,
         data: bytes::Bytes,
     ) -> impl Future<Output = Result<(), Self::Error>> {
-        self.inner.store_file(prepared, data)
+        // the permit is released once the file is written
+        let PreparedFile {
+            file,
+            permit,
+            logged,
+        } = prepared;
+        let bytes = data.len() as u64;
+        let store = self.inner.store_file(file, data);
+        let update_log = self.update_log.clone();
+        async move {
+            store.await?;
+            drop(permit);
+            if let (Some(log), Some((path, started))) = (update_log, logged) {
+                log.unzipped(path, bytes, started.elapsed());
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::test_server::{Response, serve};
+
+    /// Answers every request on `listener` with a redirect to `/final`, or an
+    /// empty response for `/final` itself.
+    async fn redirecting_server(listener: tokio::net::TcpListener) {
+        let port = listener.local_addr().unwrap().port();
+        serve(listener, |request| {
+            if request.starts_with("head /final ") {
+                Response::ok("")
+            } else {
+                Response {
+                    status: "302 Found",
+                    headers: format!("Location: http://127.0.0.1:{port}/final\r\n"),
+                    body: Vec::new(),
+                }
+            }
+        })
+        .await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_download_url_follows_redirects() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(redirecting_server(listener));
+        let profile = Profile::default();
+
+        let url = format!("http://127.0.0.1:{port}/latest/linux/x86_64/weekly");
+        assert_eq!(
+            resolve_download_url(&profile, url).await,
+            format!("http://127.0.0.1:{port}/final")
+        );
+        server.abort();
+        // closes the listener
+        let _ = server.await;
+
+        // unreachable servers are left to the sync to report
+        let url = format!("http://127.0.0.1:{port}/latest/linux/x86_64/weekly");
+        assert_eq!(resolve_download_url(&profile, url.clone()).await, url);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_version_html() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = format!("http://{}", listener.local_addr().unwrap());
+        let portal = tokio::spawn(crate::net::test_server::captive_portal(listener));
+
+        let mut profile = Profile::default();
+        profile.url_templates.version = Some(format!("{server}/version/{{channel}}"));
+        assert!(matches!(
+            version(&profile).await,
+            Err(ClientError::HtmlResponse(url)) if url == profile.version_url()
+        ));
+        assert!(matches!(
+            quick_check(&profile).await,
+            Err(ClientError::HtmlResponse(_))
+        ));
+        portal.abort();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_file_list_formats() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-file-lists-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let list = vec![("voxygen".to_string(), 0xdead_beef_u32); 100];
+
+        save_file_list(&dir, "0.17.0", &list, false).unwrap();
+        let pretty = std::fs::metadata(file_list_path(&dir, "0.17.0", false))
+            .unwrap()
+            .len();
+        let loaded: Option<(Vec<(String, u32)>, _)> =
+            load_file_list(&dir, "0.17.0", true).await;
+        assert_eq!(loaded, Some((list.clone(), false)));
+
+        // converting removes the list in the old format
+        save_file_list(&dir, "0.17.0", &list, true).unwrap();
+        assert!(!file_list_path(&dir, "0.17.0", false).exists());
+        let compact = std::fs::metadata(file_list_path(&dir, "0.17.0", true))
+            .unwrap()
+            .len();
+        assert!(compact < pretty);
+        let loaded: Option<(Vec<(String, u32)>, _)> =
+            load_file_list(&dir, "0.17.0", true).await;
+        assert_eq!(loaded, Some((list, true)));
+        assert_eq!(
+            file_list_version(&file_list_path(&dir, "0.17.0", true)),
+            "0.17.0"
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_percent_complete_zero_bytes() {
+        assert_eq!(percent(0, 0), 100);
+        assert_eq!(percent(0, 100), 0);
+        assert_eq!(percent(50, 100), 50);
+        // overreported bytes never exceed 100%
+        assert_eq!(percent(150, 100), 100);
+    }
+
+    #[test]
+    fn test_recover_missing_install() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-missing-install-{}", std::process::id()));
+        let mut profile = Profile::default();
+        profile.version = Some("installed".to_string());
+        profile.install_fingerprint = Some(1);
+        profile.compacted_files = vec!["veloren-voxygen.pdb".to_string()];
+
+        // an installed game is kept
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("veloren-voxygen"), b"game").unwrap();
+        recover_missing_install(&mut profile, &dir).unwrap();
+        assert_eq!(profile.version.as_deref(), Some("installed"));
+
+        // the folder was removed, so the game is installed fresh into a new one
+        std::fs::remove_dir_all(&dir).unwrap();
+        recover_missing_install(&mut profile, &dir).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(profile.version, None);
+        assert_eq!(profile.install_fingerprint, None);
+        assert!(profile.compacted_files.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_fingerprint_ignores_kept_files() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-fingerprint-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("userdata")).unwrap();
+        std::fs::create_dir_all(dir.join("maps")).unwrap();
+        std::fs::write(dir.join("veloren-voxygen"), b"game").unwrap();
+
+        let before = install_fingerprint(&dir, None).unwrap();
+        std::fs::write(dir.join("userdata/settings.ron"), b"()").unwrap();
+        std::fs::write(dir.join("maps/custom.bin"), b"map").unwrap();
+        assert_eq!(install_fingerprint(&dir, None).unwrap(), before);
+
+        std::fs::write(dir.join("assets.bin"), b"assets").unwrap();
+        assert_ne!(install_fingerprint(&dir, None).unwrap(), before);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_compaction() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-compaction-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("bin/veloren.dSYM/Contents")).unwrap();
+        std::fs::create_dir_all(dir.join("userdata")).unwrap();
+        std::fs::write(dir.join("veloren-voxygen"), b"game").unwrap();
+        std::fs::write(dir.join("veloren-voxygen.pdb"), b"symbols").unwrap();
+        std::fs::write(dir.join("bin/veloren.dSYM/Contents/Info.plist"), b"x").unwrap();
+        std::fs::write(dir.join("userdata/crash.pdb"), b"kept").unwrap();
+
+        let patterns: Vec<_> = ["*.pdb", "**/*.dSYM/**"]
+            .iter()
+            .map(|pattern| compaction_regex(pattern).unwrap())
+            .collect();
+        let (mut removed, reclaimed) = compact(&dir, &patterns).unwrap();
+        removed.sort();
+        assert_eq!(removed, vec![
+            "bin/veloren.dSYM/Contents/Info.plist".to_string(),
+            "veloren-voxygen.pdb".to_string(),
+        ]);
+        assert_eq!(reclaimed, 8);
+        assert!(dir.join("veloren-voxygen").exists());
+        assert!(dir.join("userdata/crash.pdb").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_loop_is_not_followed() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-symlinks-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("assets/voxel.vox"), b"vox").unwrap();
+        std::os::unix::fs::symlink(&dir, dir.join("assets/loop")).unwrap();
+
+        assert_eq!(find_symlinks(&dir).unwrap(), vec![
+            "assets/loop".to_string()
+        ]);
+        assert!(install_fingerprint(&dir, None).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_watchdog() {
+        let mut profile = Profile::default();
+        profile.download_stall_timeout = Some(10);
+        let mut watchdog = Watchdog::new(&profile);
+        let start = Instant::now();
+        let after = |secs| start + Duration::from_secs(secs);
+        assert!(!watchdog.stalled(0, 100, 0, start));
+        assert!(!watchdog.stalled(0, 100, 0, after(9)));
+        // unzipping still advances while the downloads wait for it
+        assert!(!watchdog.stalled(0, 100, 10, after(15)));
+        assert!(!watchdog.stalled(50, 100, 10, after(20)));
+        assert!(watchdog.stalled(50, 100, 10, after(30)));
+        // nothing left to download
+        assert!(!watchdog.stalled(100, 100, 10, after(40)));
+        assert!(!watchdog.stalled(100, 100, 10, after(60)));
+
+        profile.download_stall_timeout = Some(0);
+        let mut disabled = Watchdog::new(&profile);
+        assert!(!disabled.stalled(0, 100, 0, start));
+        assert!(!disabled.stalled(0, 100, 0, after(3600)));
+    }
+
+    #[test]
+    fn test_weighted_percent() {
+        // 10 MB compressed which unzip to 90 MB
+        assert_eq!(weighted_percent([(10, 10), (0, 90)]), 10);
+        assert_eq!(weighted_percent([(10, 10), (45, 90)]), 55);
+        assert_eq!(weighted_percent([(5, 10), (0, 90)]), 5);
+        // processed bytes beyond the total don't count twice
+        assert_eq!(weighted_percent([(12, 10), (90, 90)]), 100);
+        // an update with nothing to download or unzip is complete
+        assert_eq!(weighted_percent([(0, 0), (0, 0)]), 100);
+    }
+
+    #[test]
+    fn test_expired_cache_files() {
+        let now = SystemTime::now();
+        let cached = |version: &str, modified| CachedVersion {
+            version: version.to_string(),
+            path: PathBuf::from(version),
+            size: 0,
+            modified,
+        };
+        let day = Duration::from_secs(86400);
+        let mut versions = vec![cached("new", now - day), cached("old", now - 20 * day)];
+        let expired = expired_cache_files(&versions, now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].version, "old");
+        assert_eq!(clock_skew(&versions, now), None);
+
+        // a file from the future means the clock was set back, nothing is removed
+        versions.push(cached("future", now + 30 * day));
+        assert_eq!(clock_skew(&versions, now), Some(30 * day));
+        assert!(expired_cache_files(&versions, now).is_empty());
+    }
+
+    /// Zip served from memory, with the files stored uncompressed. Remembers the ranges
+    /// of the downloaded batches.
+    #[derive(Debug, Clone)]
+    struct FakeRemoteZip {
+        zip: bytes::Bytes,
+        fetched: Arc<Mutex<Vec<std::ops::RangeInclusive<usize>>>>,
+    }
+
+    impl FakeRemoteZip {
+        fn new(files: &[(&str, &[u8])]) -> Self {
+            Self::with_comment(files, &[])
+        }
+
+        fn with_comment(files: &[(&str, &[u8])], comment: &[u8]) -> Self {
+            let mut zip = Vec::new();
+            let mut central_directory = Vec::new();
+            for (name, data) in files {
+                let offset = zip.len() as u32;
+                let crc32 = crc32fast::hash(data);
+                let fields = |header: &mut Vec<u8>| {
+                    // version, flags, stored, time and date
+                    header.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+                    header.extend_from_slice(&crc32.to_le_bytes());
+                    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+                    header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+                    header.extend_from_slice(&0u16.to_le_bytes());
+                };
+                zip.extend_from_slice(&0x04034b50u32.to_le_bytes());
+                fields(&mut zip);
+                zip.extend_from_slice(name.as_bytes());
+                zip.extend_from_slice(data);
+
+                central_directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+                central_directory.extend_from_slice(&20u16.to_le_bytes());
+                fields(&mut central_directory);
+                // comment, disk and attributes
+                central_directory.extend_from_slice(&[0; 10]);
+                central_directory.extend_from_slice(&offset.to_le_bytes());
+                central_directory.extend_from_slice(name.as_bytes());
+            }
+            let central_directory_offset = zip.len() as u32;
+            zip.extend_from_slice(&central_directory);
+            zip.extend_from_slice(&0x06054b50u32.to_le_bytes());
+            zip.extend_from_slice(&[0; 4]);
+            zip.extend_from_slice(&(files.len() as u16).to_le_bytes());
+            zip.extend_from_slice(&(files.len() as u16).to_le_bytes());
+            zip.extend_from_slice(&(central_directory.len() as u32).to_le_bytes());
+            zip.extend_from_slice(&central_directory_offset.to_le_bytes());
+            zip.extend_from_slice(&(comment.len() as u16).to_le_bytes());
+            zip.extend_from_slice(comment);
+            Self {
+                zip: zip.into(),
+                fetched: Arc::new(Mutex::new(Vec::new())),
+            }
+        }
+    }
+
+    impl remozipsy::RemoteZip for FakeRemoteZip {
+        type Error = remozipsy::RemoteFetchError<std::convert::Infallible>;
+
+        async fn fetch_remote_file_info(
+            &self,
+        ) -> Result<Vec<remozipsy::RemoteFileInfo>, Self::Error> {
+            let zip = self.zip.clone();
+            remozipsy::fetch_remote_file_info(zip.len(), MAX_EOCD_SIZE, move |range| {
+                let bytes = zip.slice(range);
+                Box::pin(async move { Ok(bytes) })
+            })
+            .await
+        }
+
+        fn fetch_bytes_stream(
+            &self,
+            range: std::ops::RangeInclusive<usize>,
+        ) -> impl Future<
+            Output = Result<
+                impl Stream<Item = Result<bytes::Bytes, Self::Error>> + Send,
+                Self::Error,
+            >,
+        > + Send {
+            self.fetched.lock().unwrap().push(range.clone());
+            let bytes = self.zip.slice(range);
+            async move { Ok(stream::iter([Ok(bytes)])) }
+        }
+    }
+
+    /// Storage of the install in `dir`, without patches, maps or an update log.
+    fn local_storage(dir: &Path) -> PatchedLocalStorage {
+        PatchedLocalStorage {
+            inner: TokioLocalStorage::new(dir.to_path_buf(), Vec::new()),
+            root: dir.to_path_buf(),
+            ignore: Vec::new(),
+            hash_index: None,
+            patches: Vec::new(),
+            shipped_maps: None,
+            deleted_files: Arc::new(AtomicU64::new(0)),
+            serial_unzip: None,
+            update_log: None,
+        }
+    }
+
+    /// Runs the sync to its end and returns how it ended.
+    async fn run_sync<R, F>(
+        mut machine: Statemachine<R, F>,
+    ) -> remozipsy::Progress<R::Error, F::Error>
+    where
+        R: remozipsy::RemoteZip + Clone + Send + 'static,
+        F: remozipsy::FileSystem + Clone + Send + 'static,
+    {
+        loop {
+            match machine.progress().await {
+                Some((remozipsy::Progress::Incomplete { .. }, next)) => machine = next,
+                Some((progress, _)) => return progress,
+                None => panic!("the update ended without a result"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_hash_index() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-test-hash-index-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let install = dir.join("install");
+        std::fs::create_dir_all(install.join("assets")).unwrap();
+        std::fs::create_dir_all(install.join("userdata")).unwrap();
+        std::fs::write(install.join("assets/untouched"), "same").unwrap();
+        std::fs::write(install.join("assets/touched"), "old").unwrap();
+        std::fs::write(install.join("userdata/ignored"), "").unwrap();
+        let index = dir.join("index.ron");
+        let ignore = vec!["userdata/".to_owned()];
+        let crc32s = |files: Vec<remozipsy::FileInfo>| {
+            files
+                .into_iter()
+                .map(|file| (file.local_unix_path, file.crc32))
+                .collect::<std::collections::BTreeMap<_, _>>()
+        };
+
+        let files = crc32s(indexed_files(&install, &ignore, &index).unwrap());
+        assert_eq!(files.keys().collect::<Vec<_>>(), [
+            "assets/touched",
+            "assets/untouched"
+        ]);
+        assert_eq!(files["assets/untouched"], crc32fast::hash(b"same"));
+
+        // a CRC32 in the index is only reused while the file is unchanged
+        let content = std::fs::read_to_string(&index).unwrap();
+        let forged = crc32fast::hash(b"same").to_string();
+        std::fs::write(&index, content.replace(&forged, "1")).unwrap();
+        std::fs::write(install.join("assets/touched"), "new").unwrap();
+        let file = std::fs::File::options()
+            .write(true)
+            .open(install.join("assets/touched"))
+            .unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let files = crc32s(indexed_files(&install, &ignore, &index).unwrap());
+        assert_eq!(files["assets/untouched"], 1);
+        assert_eq!(files["assets/touched"], crc32fast::hash(b"new"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_long_zip_comment() {
+        use remozipsy::RemoteZip;
+
+        // the longest comment a zip can carry
+        let remote = FakeRemoteZip::with_comment(&[("a.txt", b"a")], &[b'#'; 65_535]);
+        let files = remote.fetch_remote_file_info().await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_name, "a.txt");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sync_skips_symlinked_dirs() {
+        let dir = std::env::temp_dir().join(format!(
+            "airshipper-test-sync-symlinks-{}",
+            std::process::id()
+        ));
+        let install = dir.join("install");
+        let outside = dir.join("outside");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&install).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, install.join("assets")).unwrap();
+
+        let symlinks = find_symlinks(&install).unwrap();
+        assert_eq!(symlinks, ["assets"]);
+        let remote = FakeRemoteZip::new(&[
+            ("veloren-voxygen", b"voxygen"),
+            ("assets/common/items.ron", b"()"),
+            ("assets.ron", b"not in the link"),
+        ]);
+        let skipping = SkipSymlinks::new(remote.clone(), symlinks.clone());
+        let names: Vec<_> = remozipsy::RemoteZip::fetch_remote_file_info(&skipping)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|file| file.file_name)
+            .collect();
+        assert_eq!(names, ["veloren-voxygen", "assets.ron"]);
+
+        let storage = PatchedLocalStorage {
+            inner: TokioLocalStorage::new(install.clone(), symlinks),
+            ..local_storage(&install)
+        };
+        let machine =
+            Statemachine::new(skipping, storage, sync_config(&Profile::default()));
+        assert!(matches!(
+            run_sync(machine).await,
+            remozipsy::Progress::Successful
+        ));
+        assert_eq!(
+            std::fs::read(install.join("veloren-voxygen")).unwrap(),
+            b"voxygen"
+        );
+        assert_eq!(std::fs::read_dir(&outside).unwrap().count(), 0);
+
+        // a link which appeared after looking for them isn't written through
+        let machine = Statemachine::new(
+            remote,
+            local_storage(&install),
+            sync_config(&Profile::default()),
+        );
+        assert!(matches!(
+            run_sync(machine).await,
+            remozipsy::Progress::Errored(_)
+        ));
+        assert_eq!(std::fs::read_dir(&outside).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Remembers the order in which the files were written.
+    #[derive(Debug, Clone)]
+    struct RecordingStorage {
+        inner: PatchedLocalStorage,
+        written: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl remozipsy::FileSystem for RecordingStorage {
+        type Error = remozipsy::tokio::TokioLocalStorageError;
+        type StorePrepare = (PreparedFile, String);
+
+        async fn all_files(&mut self) -> Result<Vec<remozipsy::FileInfo>, Self::Error> {
+            self.inner.all_files().await
+        }
+
+        fn delete_file(
+            &self,
+            info: remozipsy::FileInfo,
+        ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+            self.inner.delete_file(info)
+        }
+
+        fn prepare_store_file(
+            &self,
+            info: remozipsy::FileInfo,
+        ) -> impl Future<Output = Result<Self::StorePrepare, Self::Error>> + Send
+        {
+            let path = info.local_unix_path.clone();
+            let prepare = self.inner.prepare_store_file(info);
+            async move { Ok((prepare.await?, path)) }
+        }
+
+        fn store_file(
+            &self,
+            (prepared, path): Self::StorePrepare,
+            data: bytes::Bytes,
+        ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+            let store = self.inner.store_file(prepared, data);
+            let written = Arc::clone(&self.written);
+            async move {
+                store.await?;
+                written.lock().unwrap().push(path);
+                Ok(())
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sync_writes_files_in_zip_order() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-test-sync-order-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("assets/old.ron"), "removed by the update").unwrap();
+        std::fs::write(dir.join("veloren-voxygen"), "outdated").unwrap();
+
+        let files: &[(&str, &[u8])] = &[
+            ("veloren-voxygen", b"voxygen"),
+            ("assets/voxygen/i18n.ron", b"()"),
+            ("assets/common/items.ron", &[7; 4096]),
+            ("veloren-server-cli", b"server"),
+        ];
+        let storage = RecordingStorage {
+            inner: local_storage(&dir),
+            written: Arc::new(Mutex::new(Vec::new())),
+        };
+        let mut machine = Statemachine::new(
+            FakeRemoteZip::new(files),
+            storage.clone(),
+            sync_config(&Profile::default()),
+        );
+
+        let mut unzipped = Vec::new();
+        let mut deletions = Vec::new();
+        let finished = loop {
+            match machine.progress().await {
+                Some((remozipsy::Progress::Incomplete { unzip, delete, .. }, next)) => {
+                    unzipped.push((unzip.processed_bytes(), unzip.total_bytes()));
+                    deletions.push(delete);
+                    machine = next;
+                },
+                Some((progress, _)) => break progress,
+                None => panic!("the update ended without a result"),
+            }
+        };
+        assert!(matches!(finished, remozipsy::Progress::Successful));
+
+        let written = storage.written.lock().unwrap().clone();
+        let expected: Vec<_> = files.iter().map(|(name, _)| name.to_string()).collect();
+        assert_eq!(written, expected);
+        for (name, data) in files {
+            assert_eq!(std::fs::read(dir.join(name)).unwrap(), *data);
+        }
+        assert!(!dir.join("assets/old.ron").exists());
+        // remozipsy counts the files to delete
+        assert_eq!(deleted_files_text(&deletions[0], 0), "0 / 1 files");
+
+        let total = files.iter().map(|(_, data)| data.len() as u64).sum();
+        assert!(unzipped.is_sorted());
+        assert_eq!(unzipped.first(), Some(&(0, total)));
+        assert_eq!(unzipped.last(), Some(&(total, total)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_installed_shipped_maps() {
+        let dir = std::env::temp_dir().join(format!(
+            "airshipper-test-shipped-maps-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("maps/worlds")).unwrap();
+        std::fs::write(dir.join("maps/default.bin"), "older version").unwrap();
+        std::fs::write(dir.join("maps/worlds/island.bin"), "island").unwrap();
+        std::fs::write(dir.join("maps/mine.bin"), "made by the user").unwrap();
+
+        let remote = FakeRemoteZip::new(&[
+            ("veloren-voxygen", b"voxygen"),
+            ("maps/default.bin", b"default"),
+            ("maps/worlds/island.bin", b"island"),
+            ("maps/desert.bin", b"not installed yet"),
+        ]);
+        assert_eq!(installed_shipped_maps(&remote, &dir).await, vec![
+            "maps/default.bin".to_string(),
+            "maps/worlds/island.bin".to_string(),
+        ]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Steps through a synthetic update with `delay` before every step, like the GUI and
+    /// the CLI do. Returns how long it took and how many steps were needed.
+    async fn synthetic_update(
+        remote: &FakeRemoteZip,
+        delay: Duration,
+    ) -> (Duration, u32) {
+        let dir = std::env::temp_dir().join(format!(
+            "airshipper-bench-{}-{}",
+            delay.as_micros(),
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let storage = local_storage(&dir);
+        let mut machine =
+            Statemachine::new(remote.clone(), storage, sync_config(&Profile::default()));
+
+        let started = Instant::now();
+        let mut steps = 0;
+        loop {
+            tokio::time::sleep(delay).await;
+            steps += 1;
+            match machine.progress().await {
+                Some((remozipsy::Progress::Incomplete { .. }, next)) => machine = next,
+                Some((progress, _)) => {
+                    assert!(matches!(progress, remozipsy::Progress::Successful));
+                    break;
+                },
+                None => panic!("the update ended without a result"),
+            }
+        }
+        let elapsed = started.elapsed();
+        std::fs::remove_dir_all(&dir).unwrap();
+        (elapsed, steps)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore = "benchmark, run with `cargo test bench_step_delay -- --ignored \
+                --nocapture`"]
+    async fn bench_step_delay() {
+        let names: Vec<_> = (0..500).map(|i| format!("assets/{i}.ron")).collect();
+        let data = vec![1; 64 * 1024];
+        let files: Vec<_> = names
+            .iter()
+            .map(|name| (name.as_str(), &data[..]))
+            .collect();
+        let remote = FakeRemoteZip::new(&files);
+
+        for (mode, delay) in [
+            ("gui", GUI_STEP_DELAY),
+            ("cli", CLI_STEP_DELAY),
+            ("no pause", Duration::ZERO),
+        ] {
+            let (elapsed, steps) = synthetic_update(&remote, delay).await;
+            println!("{mode:>8}: {elapsed:?} in {steps} steps");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_max_batch_gap() {
+        let dir = std::env::temp_dir()
+            .join(format!("airshipper-test-batch-gap-{}", std::process::id()));
+        let up_to_date = [9; 1000];
+        let files: &[(&str, &[u8])] = &[
+            ("a.ron", b"changed"),
+            ("b.ron", &up_to_date),
+            ("c.ron", b"changed"),
+        ];
+
+        // the gap is the entry of the up to date file, about 1 KB
+        let mut profile = Profile::default();
+        for (gap, requests) in [(Some(0), 2), (Some(2000), 1), (Some(500), 2)] {
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("b.ron"), up_to_date).unwrap();
+            let remote = FakeRemoteZip::new(files);
+            let storage = local_storage(&dir);
+            profile.max_batch_gap = gap;
+            let mut machine =
+                Statemachine::new(remote.clone(), storage, sync_config(&profile));
+            while let Some((progress, next)) = machine.progress().await {
+                assert!(!matches!(progress, remozipsy::Progress::Errored(_)));
+                machine = next;
+            }
+            assert_eq!(remote.fetched.lock().unwrap().len(), requests, "{gap:?}");
+            assert_eq!(std::fs::read(dir.join("c.ron")).unwrap(), b"changed");
+        }
+
+        profile.max_batch_gap = None;
+        profile.low_memory = true;
+        assert_eq!(sync_config(&profile).max_junk_bytes_before_next_batch, 0);
+        profile.max_batch_gap = Some(2000);
+        assert_eq!(sync_config(&profile).max_junk_bytes_before_next_batch, 2000);
+        profile.max_batch_gap = Some(100_000_000);
+        assert_eq!(
+            sync_config(&profile).max_junk_bytes_before_next_batch,
+            LOW_MEMORY_MAX_BATCH_GAP
+        );
+        profile.low_memory = false;
+        assert_eq!(
+            sync_config(&profile).max_junk_bytes_before_next_batch,
+            100_000_000
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,386 @@
+//! Detailed log of a single update for performance debugging, see
+//! [`Profile::update_log`](crate::profiles::Profile::update_log).
+//!
+//! It records how long every downloaded batch and every unzipped file took and the
+//! duration of the update phases, and is written as JSON lines once the update
+//! finished. Slow batches point at the network, slow unzips at the CPU or disk.
+use crate::logger::bytes_per_second;
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use remozipsy::{ProgressDetails, RemoteZip};
+use serde::Serialize;
+use std::{
+    future::Future,
+    io::Write,
+    ops::RangeInclusive,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Entry {
+    /// a range of the zip, which contains one or more files
+    Batch {
+        start: usize,
+        end: usize,
+        bytes: u64,
+        /// until the response headers arrived
+        first_byte_ms: u128,
+        duration_ms: u128,
+        /// `None` if the batch arrived too fast to measure
+        bytes_per_second: Option<u64>,
+        /// the download failed or was cancelled otherwise
+        complete: bool,
+    },
+    /// a file written to the install
+    Unzip {
+        path: String,
+        bytes: u64,
+        /// from creating the file until it was written, including the decompression
+        /// and waiting for the serial unzip
+        duration_ms: u128,
+    },
+    /// a downloaded file didn't match the CRC32 listed in the zip, which ends the update
+    HashMismatch {
+        remote_crc32: u32,
+        calculated_crc32: u32,
+    },
+    Summary(Summary),
+}
+
+/// What was updated and with which concurrency
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Setup {
+    pub version: String,
+    pub max_parallel_downloads: usize,
+    pub max_parallel_filesystem: usize,
+    pub serial_unzip: bool,
+}
+
+/// Totals of the update, the last line of the log
+#[derive(Debug, Serialize)]
+struct Summary {
+    #[serde(flatten)]
+    setup: Setup,
+    /// from the first step of the sync until the last batch was downloaded
+    download_ms: u128,
+    /// after the downloads, until the files were unzipped and deleted
+    finish_ms: u128,
+    total_ms: u128,
+    downloaded_bytes: u64,
+    unzipped_bytes: u64,
+    download_bytes_per_second: Option<u64>,
+    unzip_bytes_per_second: Option<u64>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    setup: Setup,
+    entries: Vec<Entry>,
+    sync_started: Option<Instant>,
+    downloaded: Option<Instant>,
+    downloaded_bytes: u64,
+    unzipped_bytes: u64,
+    /// bytes of the syncs this one continues, see [`UpdateLog::continue_from`]
+    carried_downloaded_bytes: u64,
+    carried_unzipped_bytes: u64,
+}
+
+/// Collects the entries of an update, clones share them.
+#[derive(Debug, Clone)]
+pub(crate) struct UpdateLog(Arc<Mutex<Inner>>);
+
+impl UpdateLog {
+    pub(crate) fn new(setup: Setup) -> Self {
+        Self(Arc::new(Mutex::new(Inner {
+            setup,
+            entries: Vec::new(),
+            sync_started: None,
+            downloaded: None,
+            downloaded_bytes: 0,
+            unzipped_bytes: 0,
+            carried_downloaded_bytes: 0,
+            carried_unzipped_bytes: 0,
+        })))
+    }
+
+    /// Takes over the entries of a sync which was restarted, so the log covers the
+    /// whole update.
+    pub(crate) fn continue_from(&self, previous: &UpdateLog) {
+        let mut previous = previous.0.lock().unwrap_or_else(|e| e.into_inner());
+        let mut inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries = std::mem::take(&mut previous.entries);
+        entries.append(&mut inner.entries);
+        inner.entries = entries;
+        inner.sync_started = previous.sync_started.or(inner.sync_started);
+        // the steps of this sync only count its own bytes
+        inner.carried_downloaded_bytes += previous.downloaded_bytes;
+        inner.carried_unzipped_bytes += previous.unzipped_bytes;
+        inner.downloaded_bytes += previous.downloaded_bytes;
+        inner.unzipped_bytes += previous.unzipped_bytes;
+    }
+
+    fn push(&self, entry: Entry) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .push(entry);
+    }
+
+    /// Notes the progress of the sync, called on every step of it.
+    pub(crate) fn step(&self, download: &ProgressDetails, unzip: &ProgressDetails) {
+        self.record_step(
+            download.is_finished(),
+            download.processed_bytes(),
+            unzip.processed_bytes(),
+        );
+    }
+
+    fn record_step(&self, download_finished: bool, downloaded: u64, unzipped: u64) {
+        let mut inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        inner.sync_started.get_or_insert(now);
+        if download_finished {
+            inner.downloaded.get_or_insert(now);
+        }
+        inner.downloaded_bytes = inner.carried_downloaded_bytes + downloaded;
+        inner.unzipped_bytes = inner.carried_unzipped_bytes + unzipped;
+    }
+
+    pub(crate) fn unzipped(&self, path: String, bytes: u64, duration: Duration) {
+        self.push(Entry::Unzip {
+            path,
+            bytes,
+            duration_ms: duration.as_millis(),
+        });
+    }
+
+    pub(crate) fn hash_mismatch(&self, remote: u32, calculated: u32) {
+        self.push(Entry::HashMismatch {
+            remote_crc32: remote,
+            calculated_crc32: calculated,
+        });
+    }
+
+    /// Writes the entries and a summary of the phases to `path`.
+    pub(crate) fn write(&self, path: &Path) -> std::io::Result<()> {
+        let mut inner = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let started = inner.sync_started.unwrap_or(now);
+        let downloaded = inner.downloaded.unwrap_or(now);
+        let download = downloaded.duration_since(started);
+        let total = now.duration_since(started);
+        let summary = Summary {
+            setup: inner.setup.clone(),
+            download_ms: download.as_millis(),
+            finish_ms: now.duration_since(downloaded).as_millis(),
+            total_ms: total.as_millis(),
+            downloaded_bytes: inner.downloaded_bytes,
+            unzipped_bytes: inner.unzipped_bytes,
+            download_bytes_per_second: bytes_per_second(inner.downloaded_bytes, download),
+            unzip_bytes_per_second: bytes_per_second(inner.unzipped_bytes, total),
+        };
+        inner.entries.push(Entry::Summary(summary));
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for entry in inner.entries.drain(..) {
+            serde_json::to_writer(&mut file, &entry)?;
+            file.write_all(b"\n")?;
+        }
+        file.flush()
+    }
+}
+
+/// Times the batches fetched by the sync, passes everything else through.
+#[derive(Debug, Clone)]
+pub(crate) struct LoggedRemoteZip<R> {
+    inner: R,
+    log: Option<UpdateLog>,
+}
+
+impl<R> LoggedRemoteZip<R> {
+    pub(crate) fn new(inner: R, log: Option<UpdateLog>) -> Self {
+        Self { inner, log }
+    }
+}
+
+impl<R> RemoteZip for LoggedRemoteZip<R>
+where
+    R: RemoteZip + Sync,
+{
+    type Error = R::Error;
+
+    fn fetch_remote_file_info(
+        &self,
+    ) -> impl Future<Output = Result<Vec<remozipsy::RemoteFileInfo>, Self::Error>> + Send
+    {
+        self.inner.fetch_remote_file_info()
+    }
+
+    fn fetch_bytes_stream(
+        &self,
+        range: RangeInclusive<usize>,
+    ) -> impl Future<
+        Output = Result<
+            impl Stream<Item = Result<Bytes, Self::Error>> + Send,
+            Self::Error,
+        >,
+    > + Send {
+        let started = Instant::now();
+        let fetch = self.inner.fetch_bytes_stream(range.clone());
+        let log = self.log.clone();
+        async move {
+            let stream = fetch.await?;
+            let mut timer = log.map(|log| BatchTimer {
+                log,
+                range,
+                started,
+                first_byte: started.elapsed(),
+                bytes: 0,
+                complete: false,
+            });
+            Ok(stream.map(move |chunk| {
+                if let (Some(timer), Ok(chunk)) = (&mut timer, &chunk) {
+                    timer.bytes += chunk.len() as u64;
+                    timer.complete = timer.bytes as usize
+                        >= timer.range.end() + 1 - timer.range.start();
+                }
+                chunk
+            }))
+        }
+    }
+}
+
+/// Records its batch once the stream of the batch is dropped, which happens right
+/// after it was downloaded.
+struct BatchTimer {
+    log: UpdateLog,
+    range: RangeInclusive<usize>,
+    started: Instant,
+    first_byte: Duration,
+    bytes: u64,
+    complete: bool,
+}
+
+impl Drop for BatchTimer {
+    fn drop(&mut self) {
+        let duration = self.started.elapsed();
+        self.log.push(Entry::Batch {
+            start: *self.range.start(),
+            end: *self.range.end(),
+            bytes: self.bytes,
+            first_byte_ms: self.first_byte.as_millis(),
+            duration_ms: duration.as_millis(),
+            bytes_per_second: bytes_per_second(self.bytes, duration),
+            complete: self.complete,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update_log() -> UpdateLog {
+        UpdateLog::new(Setup {
+            version: "1.0".to_string(),
+            max_parallel_downloads: 2,
+            max_parallel_filesystem: 8,
+            serial_unzip: false,
+        })
+    }
+
+    #[test]
+    fn test_continue_from() {
+        let stalled = update_log();
+        stalled.record_step(false, 100, 40);
+        stalled.unzipped("a".to_string(), 40, Duration::ZERO);
+
+        let log = update_log();
+        log.continue_from(&stalled);
+        // the restarted sync counts from zero again
+        log.record_step(false, 10, 5);
+        log.unzipped("b".to_string(), 5, Duration::ZERO);
+        log.record_step(true, 30, 20);
+        let inner = log.0.lock().unwrap();
+        assert_eq!(inner.downloaded_bytes, 130);
+        assert_eq!(inner.unzipped_bytes, 60);
+        assert!(inner.downloaded.is_some());
+        assert!(matches!(
+            &inner.entries[..],
+            [Entry::Unzip { path: a, .. }, Entry::Unzip { path: b, .. }] if a == "a" && b == "b"
+        ));
+    }
+
+    #[test]
+    fn test_batch_timer() {
+        let log = update_log();
+        for (bytes, complete) in [(10, true), (4, false)] {
+            drop(BatchTimer {
+                log: log.clone(),
+                range: 0..=9,
+                started: Instant::now() - Duration::from_secs(2),
+                first_byte: Duration::from_millis(50),
+                bytes,
+                complete,
+            });
+        }
+        let inner = log.0.lock().unwrap();
+        let [
+            Entry::Batch {
+                start: 0,
+                end: 9,
+                bytes: 10,
+                first_byte_ms: 50,
+                duration_ms,
+                bytes_per_second,
+                complete: true,
+            },
+            Entry::Batch {
+                bytes: 4,
+                complete: false,
+                ..
+            },
+        ] = &inner.entries[..]
+        else {
+            panic!("unexpected entries {:?}", inner.entries);
+        };
+        assert!(*duration_ms >= 2000);
+        assert!((4..=5).contains(&bytes_per_second.unwrap()));
+    }
+
+    #[test]
+    fn test_entries_are_json_lines() {
+        let log = update_log();
+        log.unzipped(
+            "assets/voxel.vox".to_string(),
+            100,
+            Duration::from_millis(5),
+        );
+        log.hash_mismatch(1, 2);
+
+        let path = std::env::temp_dir().join(format!(
+            "airshipper-update-log-{}.jsonl",
+            std::process::id()
+        ));
+        log.write(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["event"], "unzip");
+        assert_eq!(lines[0]["path"], "assets/voxel.vox");
+        assert_eq!(lines[1]["event"], "hash_mismatch");
+        assert_eq!(lines[1]["remote_crc32"], 1);
+        assert_eq!(lines[1]["calculated_crc32"], 2);
+        assert_eq!(lines[2]["event"], "summary");
+        assert_eq!(lines[2]["version"], "1.0");
+        assert_eq!(lines[2]["max_parallel_downloads"], 2);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,12 +1,17 @@
-use crate::{Result, fs, windows};
+use crate::{ClientError, Result, WEB_CLIENT, fs, profiles::ConsoleMode, windows};
+use reqwest::{
+    StatusCode,
+    header::{ACCEPT, RANGE},
+};
 use self_update::update::{Release, ReleaseAsset};
 use semver::Version;
 use std::{
     ffi::{OsStr, OsString},
-    fs::File,
     os::windows::ffi::OsStrExt,
+    path::Path,
     ptr,
 };
+use tokio::io::AsyncWriteExt;
 use windows_sys::Win32::{
     System::{Console::GetConsoleWindow, Threading::GetCurrentProcessId},
     UI::{
@@ -33,11 +38,14 @@ fn get_asset(release: &Release) -> Option<ReleaseAsset> {
 }
 
 pub fn query() -> Result<Option<Release>> {
-    let releases = self_update::backends::gitlab::ReleaseList::configure()
-        .repo_owner("veloren")
-        .repo_name("airshipper")
-        .build()?
-        .fetch()?;
+    let mut releases = self_update::backends::gitlab::ReleaseList::configure();
+    releases.repo_owner("veloren").repo_name("airshipper");
+    // for forks which publish their releases in a private repository
+    let token = std::env::var("AIRSHIPPER_GITLAB_TOKEN").ok();
+    if let Some(token) = &token {
+        releases.auth_token(token);
+    }
+    let releases = releases.build()?.fetch()?;
 
     // Get latest Github release
     if let Some(latest_release) = releases.first() {
@@ -63,9 +71,6 @@ pub fn query() -> Result<Option<Release>> {
 /// Tries to self update with provided release
 pub(crate) fn update(latest_release: &Release) -> Result<()> {
     let update_cache_path = fs::get_cache_path().join("update");
-
-    // Cleanup
-    let _ = std::fs::remove_dir_all(&update_cache_path);
     std::fs::create_dir_all(&update_cache_path)
         .expect("failed to create cache directory!");
 
@@ -88,18 +93,17 @@ pub(crate) fn update(latest_release: &Release) -> Result<()> {
         );
         let install_file_path = update_cache_path.join(&download_file_name);
 
-        let install_file = File::create(&install_file_path)?;
-
-        self_update::Download::from_url(&asset.download_url)
-            .set_header(
-                reqwest::header::ACCEPT,
-                "application/octet-stream".parse().unwrap(),
-            )
-            .show_progress(false)
-            .download_to(&install_file)?;
+        // Cleanup everything but a partial download of this installer
+        for entry in std::fs::read_dir(&update_cache_path)?.flatten() {
+            if entry.file_name() != download_file_name {
+                let path = entry.path();
+                let _ = std::fs::remove_file(&path)
+                    .or_else(|_| std::fs::remove_dir_all(&path));
+            }
+        }
 
-        install_file.sync_all()?; //make sure we block on sync before we start it
-        drop(install_file);
+        tokio::runtime::Handle::current()
+            .block_on(download_resumable(&asset.download_url, &install_file_path))?;
 
         tracing::debug!("Starting installer...");
         // Execute the installer
@@ -127,6 +131,66 @@ pub(crate) fn update(latest_release: &Release) -> Result<()> {
     Ok(())
 }
 
+/// Downloads `url` to `path`, continuing a previous partial download if the server
+/// supports range requests. GitLab doesn't provide checksums for release assets, so
+/// only the size of the finished file is verified.
+async fn download_resumable(url: &str, path: &Path) -> Result<()> {
+    let remote_size = WEB_CLIENT
+        .head(url)
+        .header(ACCEPT, "application/octet-stream")
+        .send()
+        .await
+        .ok()
+        .filter(|res| res.status().is_success())
+        .and_then(|res| res.content_length());
+    let local_size = tokio::fs::metadata(path).await.map_or(0, |meta| meta.len());
+
+    let resume_from = match remote_size {
+        Some(remote_size) if local_size == remote_size => {
+            tracing::debug!("Installer has already been downloaded");
+            return Ok(());
+        },
+        Some(remote_size) if local_size < remote_size => local_size,
+        _ => 0,
+    };
+
+    let mut request = WEB_CLIENT
+        .get(url)
+        .header(ACCEPT, "application/octet-stream");
+    if resume_from > 0 {
+        tracing::debug!("Resuming installer download at {} bytes", resume_from);
+        request = request.header(RANGE, format!("bytes={resume_from}-"));
+    }
+    let mut response = request.send().await?.error_for_status()?;
+
+    // the server might ignore the range and send the whole file instead
+    let mut file = if response.status() == StatusCode::PARTIAL_CONTENT {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .await?
+    } else {
+        tokio::fs::File::create(path).await?
+    };
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk).await?;
+    }
+    file.sync_all().await?; // make sure we block on sync before we start it
+
+    let size = file.metadata().await?.len();
+    if let Some(remote_size) = remote_size
+        && size != remote_size
+    {
+        drop(file);
+        let _ = tokio::fs::remove_file(path).await;
+        return Err(ClientError::Custom(format!(
+            "Downloaded installer has {size} bytes, expected {remote_size} bytes"
+        )));
+    }
+
+    Ok(())
+}
+
 pub fn execute_as_admin<T, T2>(program: T, args: T2) -> i32
 where
     T: Into<OsString>,
@@ -153,15 +217,19 @@ where
     }
 }
 
-/// Hides the console incase the process hasn't been started from one.
-pub fn hide_non_inherited_console() {
-    if !started_from_console() {
-        let window = unsafe { GetConsoleWindow() };
-        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-showwindow
-        if !window.is_null() {
-            unsafe {
-                ShowWindow(window, SW_HIDE);
-            }
+/// Shows or hides the console window, [`ConsoleMode::Auto`] hides it in case the
+/// process hasn't been started from one.
+pub fn set_console_visibility(mode: ConsoleMode) {
+    let show = match mode {
+        ConsoleMode::Auto => started_from_console(),
+        ConsoleMode::Show => true,
+        ConsoleMode::Hide => false,
+    };
+    let window = unsafe { GetConsoleWindow() };
+    // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-showwindow
+    if !window.is_null() {
+        unsafe {
+            ShowWindow(window, if show { SW_SHOW } else { SW_HIDE });
         }
     }
 }